@@ -0,0 +1,58 @@
+// Not every benchmark uses every item
+#![allow(dead_code)]
+
+use std::fmt::Write;
+
+pub const RESOURCES: &str = "./resources";
+
+pub const RESOURCE: &str =
+    "./resources/Himeringo - Yotsuya-san ni Yoroshiku (RLC) [Winber1's Extreme].osu";
+
+/// Generate a storyboard with 500k commands similar to script output.
+///
+/// Layers, paths, and some commands go through variables to cover their
+/// expansion.
+pub fn generate_storyboard() -> String {
+    let mut osb = String::from(
+        "osu file format v14
+
+[Variables]
+$fg=Foreground
+$bg=Background
+$fade=F,0
+$particle=\"sb/particle
+$center=320,240
+
+[Events]
+",
+    );
+
+    for i in 0..20_000 {
+        let start = i * 10;
+        let layer = if i % 4 == 0 { "$bg" } else { "$fg" };
+
+        let _ = writeln!(
+            osb,
+            "Sprite,{layer},Centre,$particle{}.png\",$center",
+            i % 8
+        );
+        let _ = writeln!(osb, " $fade,{start},{},0,1", start + 500);
+        let _ = writeln!(osb, " S,0,{start},{},0.5,1", start + 1000);
+        let _ = writeln!(osb, " V,0,{start},,1,1");
+        let _ = writeln!(osb, " P,0,{start},,A");
+
+        for j in 0..6 {
+            let t = start + j * 100;
+            let (end, to) = (t + 100, j + 10);
+            let _ = writeln!(osb, " M,1,{t},{end},{j},{j},{to},{to}");
+            let _ = writeln!(osb, " R,0,{t},{end},0,0.1");
+        }
+
+        let _ = writeln!(osb, " L,{start},4");
+        let _ = writeln!(osb, "  C,0,0,100,255,255,255,0,0,0");
+        let _ = writeln!(osb, "  $fade,100,200,1,0");
+        let _ = writeln!(osb, "  MX,0,0,100,320,400");
+    }
+
+    osb
+}
@@ -0,0 +1,43 @@
+use std::{env, fs};
+
+use criterion::{criterion_group, criterion_main, Criterion};
+use rosu_storyboard::Storyboard;
+
+use self::common::{generate_storyboard, RESOURCE};
+
+mod common;
+
+fn decode(c: &mut Criterion) {
+    let path = env::temp_dir().join("rosu-storyboard-bench.osb");
+    fs::write(&path, generate_storyboard()).unwrap();
+
+    let mut group = c.benchmark_group("decode_large");
+    group.sample_size(10);
+
+    group.bench_function("from_path", |b| {
+        b.iter(|| Storyboard::from_path(&path).unwrap())
+    });
+
+    group.bench_function("from_path_parallel", |b| {
+        b.iter(|| Storyboard::from_path_parallel(&path).unwrap())
+    });
+
+    group.finish();
+
+    let mut group = c.benchmark_group("decode_resource");
+
+    group.bench_function("from_path", |b| {
+        b.iter(|| Storyboard::from_path(RESOURCE).unwrap())
+    });
+
+    group.bench_function("from_path_parallel", |b| {
+        b.iter(|| Storyboard::from_path_parallel(RESOURCE).unwrap())
+    });
+
+    group.finish();
+
+    let _ = fs::remove_file(path);
+}
+
+criterion_group!(benches, decode);
+criterion_main!(benches);
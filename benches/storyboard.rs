@@ -0,0 +1,135 @@
+use std::{fs, hint::black_box};
+
+use criterion::{criterion_group, criterion_main, Criterion};
+use rosu_storyboard::{element::ElementKind, index::TimeIndex, Storyboard};
+
+use self::common::{generate_storyboard, RESOURCES};
+
+mod common;
+
+/// All bundled resources as raw bytes.
+fn resources() -> Vec<(String, Vec<u8>)> {
+    let mut resources: Vec<_> = fs::read_dir(RESOURCES)
+        .unwrap()
+        .map(|entry| {
+            let entry = entry.unwrap();
+            let name = entry.file_name().to_string_lossy().into_owned();
+
+            (name, fs::read(entry.path()).unwrap())
+        })
+        .collect();
+
+    resources.sort_unstable_by(|(a, _), (b, _)| a.cmp(b));
+
+    resources
+}
+
+fn decode(c: &mut Criterion) {
+    let mut group = c.benchmark_group("decode_resources");
+
+    for (name, bytes) in resources() {
+        group.bench_function(name, |b| {
+            b.iter(|| Storyboard::from_bytes(black_box(&bytes)).unwrap())
+        });
+    }
+
+    group.finish();
+
+    let osb = generate_storyboard();
+
+    let mut group = c.benchmark_group("decode_large");
+    group.sample_size(10);
+
+    group.bench_function("from_str", |b| {
+        b.iter(|| black_box(&osb).parse::<Storyboard>().unwrap())
+    });
+
+    group.finish();
+}
+
+fn encode(c: &mut Criterion) {
+    let mut group = c.benchmark_group("encode_resources");
+
+    for (name, bytes) in resources() {
+        let storyboard = Storyboard::from_bytes(&bytes).unwrap();
+        let mut buf = Vec::with_capacity(bytes.len());
+
+        group.bench_function(name, |b| {
+            b.iter(|| {
+                buf.clear();
+                black_box(&storyboard).encode(&mut buf).unwrap();
+            })
+        });
+    }
+
+    group.finish();
+
+    let osb = generate_storyboard();
+    let storyboard: Storyboard = osb.parse().unwrap();
+    let mut buf = Vec::with_capacity(osb.len());
+
+    let mut group = c.benchmark_group("encode_large");
+    group.sample_size(10);
+
+    group.bench_function("encode", |b| {
+        b.iter(|| {
+            buf.clear();
+            black_box(&storyboard).encode(&mut buf).unwrap();
+        })
+    });
+
+    group.finish();
+}
+
+fn evaluate(c: &mut Criterion) {
+    let storyboard: Storyboard = generate_storyboard().parse().unwrap();
+
+    let start = storyboard.earliest_event_time().unwrap();
+    let end = storyboard.latest_event_time().unwrap();
+    let times: Vec<_> = (0..100)
+        .map(|i| start + (end - start) * f64::from(i) / 100.0)
+        .collect();
+
+    let mut group = c.benchmark_group("evaluate_large");
+    group.sample_size(10);
+
+    group.bench_function("state_at_all", |b| {
+        b.iter(|| {
+            let elements = storyboard
+                .layers
+                .values()
+                .flat_map(|layer| layer.elements.iter());
+
+            for elem in elements {
+                if let ElementKind::Sprite(ref sprite) = elem.kind {
+                    black_box(sprite.state_at(black_box(times[50])));
+                }
+            }
+        })
+    });
+
+    group.bench_function("time_index_new", |b| {
+        b.iter(|| TimeIndex::new(black_box(&storyboard)))
+    });
+
+    let index = TimeIndex::new(&storyboard);
+
+    group.bench_function("state_at_active", |b| {
+        b.iter(|| {
+            for &time in times.iter() {
+                for elem in index.active_at(time) {
+                    let element = &storyboard.layers[elem.layer].elements[elem.index];
+
+                    if let ElementKind::Sprite(ref sprite) = element.kind {
+                        black_box(sprite.state_at(time));
+                    }
+                }
+            }
+        })
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, decode, encode, evaluate);
+criterion_main!(benches);
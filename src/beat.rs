@@ -0,0 +1,298 @@
+use std::ops::Range;
+
+use rosu_map::{
+    section::timing_points::{ControlPoints, TimingPoint},
+    Beatmap,
+};
+
+use crate::{builder::TimeSpan, element::ElementKind, LayerId, Storyboard};
+
+/// Converts between beat positions and milliseconds based on timing points.
+///
+/// Each timing point starts a new measure. If a section between two timing
+/// points does not end on a full measure, its last measure is cut short.
+#[derive(Clone, Debug, PartialEq)]
+pub struct BeatClock {
+    /// Sorted by time; never empty.
+    sections: Vec<Section>,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+struct Section {
+    time: f64,
+    beat_len: f64,
+    beats_per_measure: u32,
+    /// Index of the section's first measure.
+    first_measure: i32,
+}
+
+impl Section {
+    fn measure_len(&self) -> f64 {
+        self.beat_len * f64::from(self.beats_per_measure)
+    }
+}
+
+/// A position in a beatmap in terms of measures and beats.
+///
+/// All parts are zero-based, e.g. `BeatPos::new(0, 0)` is the first beat of
+/// the first measure.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct BeatPos {
+    pub measure: i32,
+    pub beat: u32,
+    /// Subdivision of the beat in units of `1 / divisor`.
+    pub tick: u32,
+    pub divisor: u32,
+}
+
+impl BeatPos {
+    /// The position of a whole beat.
+    pub const fn new(measure: i32, beat: u32) -> Self {
+        Self {
+            measure,
+            beat,
+            tick: 0,
+            divisor: 1,
+        }
+    }
+
+    /// The position of `tick / divisor` beats after the given beat.
+    pub const fn subdivided(measure: i32, beat: u32, tick: u32, divisor: u32) -> Self {
+        Self {
+            measure,
+            beat,
+            tick,
+            divisor,
+        }
+    }
+
+    fn beats(self) -> f64 {
+        f64::from(self.beat) + f64::from(self.tick) / f64::from(self.divisor.max(1))
+    }
+}
+
+impl BeatClock {
+    /// Create a new [`BeatClock`] from uninherited timing points.
+    ///
+    /// Without any timing points, a single one with default values at time
+    /// `0` is assumed.
+    pub fn new(timing_points: &[TimingPoint]) -> Self {
+        let mut points: Vec<_> = timing_points.iter().collect();
+        points.sort_by(|a, b| a.time.total_cmp(&b.time));
+
+        let default = TimingPoint::default();
+
+        if points.is_empty() {
+            points.push(&default);
+        }
+
+        let mut sections: Vec<Section> = Vec::with_capacity(points.len());
+
+        for point in points {
+            let first_measure = match sections.last() {
+                Some(prev) => {
+                    let measures = (point.time - prev.time) / prev.measure_len();
+
+                    // Ignore floating point imprecision
+                    prev.first_measure + (measures - 1e-6).ceil().max(0.0) as i32
+                }
+                None => 0,
+            };
+
+            sections.push(Section {
+                time: point.time,
+                beat_len: point.beat_len,
+                beats_per_measure: point.time_signature.numerator.get(),
+                first_measure,
+            });
+        }
+
+        Self { sections }
+    }
+
+    /// Create a new [`BeatClock`] from the timing points of [`ControlPoints`].
+    pub fn from_control_points(control_points: &ControlPoints) -> Self {
+        Self::new(&control_points.timing_points)
+    }
+
+    /// Create a new [`BeatClock`] from the timing points of a [`Beatmap`].
+    pub fn from_beatmap(beatmap: &Beatmap) -> Self {
+        Self::from_control_points(&beatmap.control_points)
+    }
+
+    fn section_at_time(&self, time: f64) -> &Section {
+        let idx = self
+            .sections
+            .partition_point(|section| section.time <= time);
+
+        &self.sections[idx.saturating_sub(1)]
+    }
+
+    fn section_at_measure(&self, measure: i32) -> &Section {
+        let idx = self
+            .sections
+            .partition_point(|section| section.first_measure <= measure);
+
+        &self.sections[idx.saturating_sub(1)]
+    }
+
+    /// The time in milliseconds of the given position.
+    pub fn time_of(&self, pos: BeatPos) -> f64 {
+        let section = self.section_at_measure(pos.measure);
+        let measures = f64::from(pos.measure - section.first_measure);
+
+        section.time + measures * section.measure_len() + pos.beats() * section.beat_len
+    }
+
+    /// The duration in milliseconds of a beat at the given time.
+    pub fn beat_len_at(&self, time: f64) -> f64 {
+        self.section_at_time(time).beat_len
+    }
+
+    /// Snap the time to the nearest `1 / divisor` beat of its timing section.
+    pub fn snap(&self, time: f64, divisor: u32) -> f64 {
+        let section = self.section_at_time(time);
+        let divisor = f64::from(divisor.max(1));
+        let beats = (time - section.time) / section.beat_len;
+
+        section.time + (beats * divisor).round() / divisor * section.beat_len
+    }
+
+    /// The time range between two positions.
+    pub fn range(&self, range: Range<BeatPos>) -> Range<f64> {
+        self.time_of(range.start)..self.time_of(range.end)
+    }
+}
+
+impl TimeSpan for (&BeatClock, BeatPos) {
+    fn times(self) -> (f64, f64) {
+        let time = self.0.time_of(self.1);
+
+        (time, time)
+    }
+}
+
+impl TimeSpan for (&BeatClock, Range<BeatPos>) {
+    fn times(self) -> (f64, f64) {
+        self.0.range(self.1).times()
+    }
+}
+
+/// What kind of time was moved by [`Storyboard::snap_to_beats`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum SnapTarget {
+    /// The start or end time of a command.
+    Command,
+    /// The start time of a loop.
+    Loop,
+    /// The start or end time of a trigger's window.
+    Trigger,
+    /// The start time of a sample.
+    Sample,
+    /// The start time of a video.
+    Video,
+}
+
+/// A time that was moved by [`Storyboard::snap_to_beats`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct SnappedTime {
+    /// The element's layer.
+    pub layer: LayerId,
+    /// Index of the element within its layer.
+    pub index: usize,
+    pub target: SnapTarget,
+    pub original: f64,
+    pub snapped: f64,
+}
+
+impl SnappedTime {
+    /// The signed distance in milliseconds that the time was moved by.
+    pub fn offset(&self) -> f64 {
+        self.snapped - self.original
+    }
+}
+
+/// All times that were moved by [`Storyboard::snap_to_beats`].
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct SnapReport {
+    pub snapped: Vec<SnappedTime>,
+}
+
+impl SnapReport {
+    /// The largest absolute distance that any time was moved by.
+    pub fn max_offset(&self) -> f64 {
+        self.snapped
+            .iter()
+            .map(|snapped| snapped.offset().abs())
+            .fold(0.0, f64::max)
+    }
+}
+
+impl Storyboard {
+    /// Snap all absolute times to the nearest `1 / divisor` beat.
+    ///
+    /// This includes the times of commands outside of loops and triggers, the
+    /// start times of loops, trigger windows, and the start times of samples
+    /// and videos. Times within loops and triggers are relative and thus
+    /// remain unchanged.
+    pub fn snap_to_beats(&mut self, clock: &BeatClock, divisor: u32) -> SnapReport {
+        let mut report = SnapReport::default();
+
+        for (name, layer) in self.layers.iter_mut() {
+            for (index, elem) in layer.elements.iter_mut().enumerate() {
+                let mut snap = |target, time: &mut f64| {
+                    let snapped = clock.snap(*time, divisor);
+
+                    if (snapped - *time).abs() >= f64::EPSILON {
+                        report.snapped.push(SnappedTime {
+                            layer: name.clone(),
+                            index,
+                            target,
+                            original: *time,
+                            snapped,
+                        });
+
+                        *time = snapped;
+                    }
+                };
+
+                let sprite = match elem.kind {
+                    ElementKind::Animation(ref mut animation) => &mut animation.sprite,
+                    ElementKind::Sprite(ref mut sprite) => sprite,
+                    ElementKind::Sample(ref mut sample) => {
+                        snap(SnapTarget::Sample, &mut sample.start_time);
+
+                        continue;
+                    }
+                    ElementKind::Video(ref mut video) => {
+                        snap(SnapTarget::Video, &mut video.start_time);
+
+                        continue;
+                    }
+                };
+
+                sprite.timeline_group.map_times(|mut time| {
+                    snap(SnapTarget::Command, &mut time);
+
+                    time
+                });
+
+                for l in sprite.loops.iter_mut() {
+                    snap(SnapTarget::Loop, &mut l.loop_start_time);
+                }
+
+                for trigger in sprite.triggers.iter_mut() {
+                    if trigger.start_time > f64::MIN {
+                        snap(SnapTarget::Trigger, &mut trigger.start_time);
+                    }
+
+                    if trigger.end_time < f64::MAX {
+                        snap(SnapTarget::Trigger, &mut trigger.end_time);
+                    }
+                }
+            }
+        }
+
+        report
+    }
+}
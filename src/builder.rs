@@ -0,0 +1,445 @@
+use std::{
+    error, fmt,
+    ops::{Range, RangeInclusive},
+};
+
+use rosu_map::{section::colors::Color, util::Pos};
+
+use crate::{
+    command::{CommandLoop, CommandTimelineGroup, CommandTrigger},
+    element::{Animation, AnimationLoopType, Element, Sample, Sprite},
+    visual::{Anchor, Easing},
+    Layer, LayerId, Storyboard,
+};
+
+impl Storyboard {
+    /// Start authoring elements on the [`Layer`] with the given id.
+    ///
+    /// If the storyboard doesn't have that layer, return `None`.
+    ///
+    /// ```
+    /// use rosu_storyboard::{LayerId, Storyboard, reexport::Pos, visual::{Anchor, Easing}};
+    ///
+    /// let mut storyboard = Storyboard::default();
+    ///
+    /// storyboard
+    ///     .layer(&LayerId::Foreground)
+    ///     .unwrap()
+    ///     .sprite("sb/star.png", Anchor::CENTER, Pos::new(320.0, 240.0))
+    ///     .fade(Easing::Out, 1000.0..2000.0, 0.0, 1.0)
+    ///     .move_to(Easing::None, 1000.0..2000.0, Pos::new(320.0, 240.0), Pos::new(320.0, 100.0))
+    ///     .loop_(3000.0, 4, |l| l.scale(Easing::None, 0.0..500.0, 1.0, 2.0))
+    ///     .build()
+    ///     .unwrap();
+    ///
+    /// let osb = storyboard.encode_to_string().unwrap();
+    /// ```
+    pub fn layer(&mut self, id: &LayerId) -> Option<LayerBuilder<'_>> {
+        self.get_layer(id).map(|layer| LayerBuilder { layer })
+    }
+}
+
+/// Error when building elements through a [`SpriteBuilder`].
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum BuildError {
+    /// The end time of a command lies before its start time or either of
+    /// them is not finite.
+    InvalidTimeRange { start_time: f64, end_time: f64 },
+    /// A loop or trigger does not contain any commands.
+    EmptyGroup,
+    /// A loop has zero iterations.
+    ZeroIterations,
+}
+
+impl error::Error for BuildError {}
+
+impl fmt::Display for BuildError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidTimeRange {
+                start_time,
+                end_time,
+            } => write!(f, "invalid time range {start_time}..{end_time}"),
+            Self::EmptyGroup => f.write_str("loop or trigger without commands"),
+            Self::ZeroIterations => f.write_str("loop with zero iterations"),
+        }
+    }
+}
+
+/// The time span of a command.
+///
+/// A single time results in a command without duration.
+pub trait TimeSpan {
+    /// The start and end time.
+    fn times(self) -> (f64, f64);
+}
+
+impl TimeSpan for f64 {
+    fn times(self) -> (f64, f64) {
+        (self, self)
+    }
+}
+
+impl TimeSpan for Range<f64> {
+    fn times(self) -> (f64, f64) {
+        (self.start, self.end)
+    }
+}
+
+impl TimeSpan for RangeInclusive<f64> {
+    fn times(self) -> (f64, f64) {
+        self.into_inner()
+    }
+}
+
+/// Adds elements to a [`Layer`], see [`Storyboard::layer`].
+pub struct LayerBuilder<'a> {
+    layer: &'a mut Layer,
+}
+
+impl<'a> LayerBuilder<'a> {
+    /// Start building a [`Sprite`].
+    ///
+    /// The sprite is only added to the layer once [`SpriteBuilder::build`]
+    /// is called.
+    pub fn sprite(
+        &mut self,
+        path: impl Into<String>,
+        origin: Anchor,
+        pos: Pos,
+    ) -> SpriteBuilder<'_> {
+        SpriteBuilder::new(self.layer, path.into(), Sprite::new(origin, pos), None)
+    }
+
+    /// Start building an [`Animation`].
+    ///
+    /// The animation is only added to the layer once [`SpriteBuilder::build`]
+    /// is called.
+    pub fn animation(
+        &mut self,
+        path: impl Into<String>,
+        origin: Anchor,
+        pos: Pos,
+        frame_count: i32,
+        frame_delay: f64,
+        loop_kind: AnimationLoopType,
+    ) -> SpriteBuilder<'_> {
+        let animation = AnimationParams {
+            frame_count,
+            frame_delay,
+            loop_kind,
+        };
+
+        SpriteBuilder::new(
+            self.layer,
+            path.into(),
+            Sprite::new(origin, pos),
+            Some(animation),
+        )
+    }
+
+    /// Add a [`Sample`] to the layer.
+    pub fn sample(&mut self, path: impl Into<String>, start_time: f64, volume: f32) -> &mut Self {
+        self.layer
+            .add(Element::new(path.into(), Sample::new(start_time, volume)));
+
+        self
+    }
+
+    /// The underlying [`Layer`].
+    pub const fn into_layer(self) -> &'a mut Layer {
+        self.layer
+    }
+}
+
+struct AnimationParams {
+    frame_count: i32,
+    frame_delay: f64,
+    loop_kind: AnimationLoopType,
+}
+
+/// Builds a [`Sprite`] or [`Animation`] command by command, see
+/// [`LayerBuilder::sprite`] and [`LayerBuilder::animation`].
+///
+/// Invalid commands are not added and the first error is returned by
+/// [`SpriteBuilder::build`].
+#[must_use = "the sprite is only added to the layer when calling `build`"]
+pub struct SpriteBuilder<'a> {
+    layer: &'a mut Layer,
+    path: String,
+    sprite: Sprite,
+    animation: Option<AnimationParams>,
+    error: Option<BuildError>,
+}
+
+impl<'a> SpriteBuilder<'a> {
+    const fn new(
+        layer: &'a mut Layer,
+        path: String,
+        sprite: Sprite,
+        animation: Option<AnimationParams>,
+    ) -> Self {
+        Self {
+            layer,
+            path,
+            sprite,
+            animation,
+            error: None,
+        }
+    }
+
+    fn add<F>(mut self, time: impl TimeSpan, f: F) -> Self
+    where
+        F: FnOnce(&mut CommandTimelineGroup, f64, f64),
+    {
+        match validate_times(time) {
+            Ok((start_time, end_time)) => f(&mut self.sprite.timeline_group, start_time, end_time),
+            Err(err) => {
+                self.error.get_or_insert(err);
+            }
+        }
+
+        self
+    }
+
+    /// Add a loop starting at `start_time` that repeats its commands for a
+    /// total of `iterations` times.
+    ///
+    /// Command times within the loop are relative to `start_time`.
+    pub fn loop_<F>(mut self, start_time: f64, iterations: u32, f: F) -> Self
+    where
+        F: FnOnce(GroupBuilder) -> GroupBuilder,
+    {
+        let GroupBuilder { group, error } = f(GroupBuilder::new());
+
+        let res = if let Some(err) = error {
+            Err(err)
+        } else if !start_time.is_finite() {
+            Err(BuildError::InvalidTimeRange {
+                start_time,
+                end_time: start_time,
+            })
+        } else if iterations == 0 {
+            Err(BuildError::ZeroIterations)
+        } else if !group.has_commands() {
+            Err(BuildError::EmptyGroup)
+        } else {
+            Ok(())
+        };
+
+        match res {
+            Ok(()) => self.sprite.loops.push(CommandLoop {
+                group,
+                loop_start_time: start_time,
+                total_iterations: iterations,
+            }),
+            Err(err) => {
+                self.error.get_or_insert(err);
+            }
+        }
+
+        self
+    }
+
+    /// Add a trigger that is active within the given time span.
+    ///
+    /// Command times within the trigger are relative to the time at which it
+    /// fires.
+    pub fn trigger<F>(
+        mut self,
+        name: impl Into<String>,
+        time: impl TimeSpan,
+        group_num: i32,
+        f: F,
+    ) -> Self
+    where
+        F: FnOnce(GroupBuilder) -> GroupBuilder,
+    {
+        let GroupBuilder { group, error } = f(GroupBuilder::new());
+
+        let res = match (error, validate_times(time)) {
+            (Some(err), _) | (None, Err(err)) => Err(err),
+            (None, Ok(_)) if !group.has_commands() => Err(BuildError::EmptyGroup),
+            (None, Ok(times)) => Ok(times),
+        };
+
+        match res {
+            Ok((start_time, end_time)) => {
+                let mut trigger = CommandTrigger::new(name.into(), start_time, end_time, group_num);
+                trigger.group = group;
+                self.sprite.triggers.push(trigger);
+            }
+            Err(err) => {
+                self.error.get_or_insert(err);
+            }
+        }
+
+        self
+    }
+
+    /// Add the element to its layer.
+    pub fn build(self) -> Result<(), BuildError> {
+        if let Some(err) = self.error {
+            return Err(err);
+        }
+
+        let elem = match self.animation {
+            Some(AnimationParams {
+                frame_count,
+                frame_delay,
+                loop_kind,
+            }) => Element::new(
+                self.path,
+                Animation {
+                    sprite: self.sprite,
+                    frame_count,
+                    frame_delay,
+                    loop_kind,
+                },
+            ),
+            None => Element::new(self.path, self.sprite),
+        };
+
+        self.layer.add(elem);
+
+        Ok(())
+    }
+}
+
+/// Builds the commands of a loop or trigger, see [`SpriteBuilder::loop_`]
+/// and [`SpriteBuilder::trigger`].
+#[must_use]
+pub struct GroupBuilder {
+    group: CommandTimelineGroup,
+    error: Option<BuildError>,
+}
+
+impl GroupBuilder {
+    fn new() -> Self {
+        Self {
+            group: CommandTimelineGroup::default(),
+            error: None,
+        }
+    }
+
+    fn add<F>(mut self, time: impl TimeSpan, f: F) -> Self
+    where
+        F: FnOnce(&mut CommandTimelineGroup, f64, f64),
+    {
+        match validate_times(time) {
+            Ok((start_time, end_time)) => f(&mut self.group, start_time, end_time),
+            Err(err) => {
+                self.error.get_or_insert(err);
+            }
+        }
+
+        self
+    }
+}
+
+fn validate_times(time: impl TimeSpan) -> Result<(f64, f64), BuildError> {
+    let (start_time, end_time) = time.times();
+
+    if start_time.is_finite() && end_time.is_finite() && start_time <= end_time {
+        Ok((start_time, end_time))
+    } else {
+        Err(BuildError::InvalidTimeRange {
+            start_time,
+            end_time,
+        })
+    }
+}
+
+macro_rules! command_methods {
+    ( $( $builder:ident $( <$lt:lifetime> )? ),* ) => {
+        $(
+            impl $( <$lt> )? $builder $( <$lt> )? {
+                /// Add a fade command (`F`).
+                pub fn fade(self, easing: Easing, time: impl TimeSpan, start: f32, end: f32) -> Self {
+                    self.add(time, |group, start_time, end_time| {
+                        group.alpha.add(easing, start_time, end_time, start, end);
+                    })
+                }
+
+                /// Add a move command (`M`).
+                pub fn move_to(self, easing: Easing, time: impl TimeSpan, start: Pos, end: Pos) -> Self {
+                    self.add(time, |group, start_time, end_time| {
+                        group.x.add(easing, start_time, end_time, start.x, end.x);
+                        group.y.add(easing, start_time, end_time, start.y, end.y);
+                    })
+                }
+
+                /// Add a horizontal move command (`MX`).
+                pub fn move_x(self, easing: Easing, time: impl TimeSpan, start: f32, end: f32) -> Self {
+                    self.add(time, |group, start_time, end_time| {
+                        group.x.add(easing, start_time, end_time, start, end);
+                    })
+                }
+
+                /// Add a vertical move command (`MY`).
+                pub fn move_y(self, easing: Easing, time: impl TimeSpan, start: f32, end: f32) -> Self {
+                    self.add(time, |group, start_time, end_time| {
+                        group.y.add(easing, start_time, end_time, start, end);
+                    })
+                }
+
+                /// Add a scale command (`S`).
+                pub fn scale(self, easing: Easing, time: impl TimeSpan, start: f32, end: f32) -> Self {
+                    self.add(time, |group, start_time, end_time| {
+                        group.scale.add(easing, start_time, end_time, start, end);
+                    })
+                }
+
+                /// Add a vector scale command (`V`).
+                pub fn scale_vec(self, easing: Easing, time: impl TimeSpan, start: Pos, end: Pos) -> Self {
+                    self.add(time, |group, start_time, end_time| {
+                        group.vector_scale.add(easing, start_time, end_time, start, end);
+                    })
+                }
+
+                /// Add a rotate command (`R`) with values in radians.
+                pub fn rotate(self, easing: Easing, time: impl TimeSpan, start: f32, end: f32) -> Self {
+                    self.add(time, |group, start_time, end_time| {
+                        group.rotation.add(
+                            easing,
+                            start_time,
+                            end_time,
+                            start.to_degrees(),
+                            end.to_degrees(),
+                        );
+                    })
+                }
+
+                /// Add a color command (`C`).
+                pub fn color(self, easing: Easing, time: impl TimeSpan, start: Color, end: Color) -> Self {
+                    self.add(time, |group, start_time, end_time| {
+                        group.color.add(easing, start_time, end_time, start, end);
+                    })
+                }
+
+                /// Add an additive blending command (`P,..,A`).
+                pub fn additive(self, time: impl TimeSpan) -> Self {
+                    self.add(time, |group, start_time, end_time| {
+                        group.add_additive(Easing::None, start_time, end_time);
+                    })
+                }
+
+                /// Add a horizontal flip command (`P,..,H`).
+                pub fn flip_h(self, time: impl TimeSpan) -> Self {
+                    self.add(time, |group, start_time, end_time| {
+                        group.add_flip_h(Easing::None, start_time, end_time);
+                    })
+                }
+
+                /// Add a vertical flip command (`P,..,V`).
+                pub fn flip_v(self, time: impl TimeSpan) -> Self {
+                    self.add(time, |group, start_time, end_time| {
+                        group.add_flip_v(Easing::None, start_time, end_time);
+                    })
+                }
+            }
+        )*
+    };
+}
+
+command_methods!(SpriteBuilder<'a>, GroupBuilder);
@@ -1,14 +1,14 @@
+pub(crate) use self::timeline::TypedCommand;
 pub use self::{
     command_loop::CommandLoop,
-    timeline::{CommandTimeline, ICommandTimeline},
+    timeline::{CommandTimeline, ICommandTimeline, Interpolate},
     timeline_group::CommandTimelineGroup,
     trigger::CommandTrigger,
-};
-pub(crate) use self::{
-    command_loop::CommandLoopInternal, timeline::TypedCommand, trigger::CommandTriggerInternal,
+    trigger_kind::{HitSoundAddition, HitSoundTrigger, TriggerKind},
 };
 
 mod command_loop;
 mod timeline;
 mod timeline_group;
 mod trigger;
+mod trigger_kind;
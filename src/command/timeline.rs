@@ -1,4 +1,6 @@
-use crate::visual::Easing;
+use rosu_map::{section::colors::Color, util::Pos};
+
+use crate::visual::{BlendingParameters, Easing};
 
 /// A timeline of commands.
 #[derive(Clone, Debug, PartialEq)]
@@ -50,6 +52,89 @@ impl<T: Copy> CommandTimeline<T> {
     }
 }
 
+impl<T: Copy + Default> CommandTimeline<T> {
+    /// Map the start and end time of every command while keeping the cached
+    /// fields consistent.
+    pub(crate) fn map_times<F: FnMut(f64) -> f64>(&mut self, mut f: F) {
+        let commands = std::mem::take(&mut self.commands);
+        *self = Self::default();
+
+        for command in commands {
+            let start_time = f(command.start_time);
+            let end_time = f(command.end_time);
+
+            self.add(
+                command.easing,
+                start_time,
+                end_time,
+                command.start_value,
+                command.end_value,
+            );
+        }
+    }
+}
+
+impl<T: Copy> CommandTimeline<T> {
+    /// Map the start and end value of every command.
+    pub(crate) fn map_values<F: FnMut(T) -> T>(&mut self, mut f: F) {
+        for command in self.commands.iter_mut() {
+            command.start_value = f(command.start_value);
+            command.end_value = f(command.end_value);
+        }
+
+        if !self.commands.is_empty() {
+            self.start_value = f(self.start_value);
+            self.end_value = f(self.end_value);
+        }
+    }
+}
+
+impl<T> CommandTimeline<T> {
+    /// The amount of commands in the timeline.
+    pub const fn len(&self) -> usize {
+        self.commands.len()
+    }
+
+    /// Whether the timeline contains no commands.
+    pub const fn is_empty(&self) -> bool {
+        self.commands.is_empty()
+    }
+
+    /// The command that started last at or before the given time.
+    ///
+    /// Commands with the same start time are ordered by their end time.
+    pub(crate) fn latest_command_at(&self, time: f64) -> Option<&TypedCommand<T>> {
+        self.commands
+            .iter()
+            .filter(|command| command.start_time <= time)
+            .max_by(|a, b| {
+                a.start_time
+                    .total_cmp(&b.start_time)
+                    .then(a.end_time.total_cmp(&b.end_time))
+            })
+    }
+
+    /// The command that starts first.
+    pub(crate) fn first_command(&self) -> Option<&TypedCommand<T>> {
+        self.commands
+            .iter()
+            .min_by(|a, b| a.start_time.total_cmp(&b.start_time))
+    }
+}
+
+impl<T: Interpolate> CommandTimeline<T> {
+    /// The value of the timeline at the given time.
+    ///
+    /// Before its first command, the timeline takes on that command's start
+    /// value. Returns `None` if the timeline has no commands.
+    pub fn value_at(&self, time: f64) -> Option<T> {
+        match self.latest_command_at(time) {
+            Some(command) => Some(command.value_at(time)),
+            None => self.first_command().map(|command| command.start_value),
+        }
+    }
+}
+
 impl<T: Default> Default for CommandTimeline<T> {
     fn default() -> Self {
         Self {
@@ -71,6 +156,75 @@ pub(crate) struct TypedCommand<T> {
     pub(crate) end_value: T,
 }
 
+impl<T: Interpolate> TypedCommand<T> {
+    /// The value of the command at the given time.
+    pub(crate) fn value_at(&self, time: f64) -> T {
+        if time >= self.end_time {
+            return self.end_value;
+        }
+
+        let duration = self.end_time - self.start_time;
+        let progress = ((time - self.start_time) / duration).clamp(0.0, 1.0);
+
+        T::interpolate(
+            self.start_value,
+            self.end_value,
+            self.easing.apply(progress),
+        )
+    }
+}
+
+/// Values that can be transitioned between by a command.
+pub trait Interpolate: Copy {
+    /// Interpolate between `start` and `end` based on `progress`.
+    ///
+    /// `progress` is generally between `0.0` and `1.0` but may overshoot for
+    /// some easings.
+    fn interpolate(start: Self, end: Self, progress: f64) -> Self;
+}
+
+impl Interpolate for f32 {
+    fn interpolate(start: Self, end: Self, progress: f64) -> Self {
+        start + (end - start) * progress as f32
+    }
+}
+
+impl Interpolate for Pos {
+    fn interpolate(start: Self, end: Self, progress: f64) -> Self {
+        Pos::new(
+            f32::interpolate(start.x, end.x, progress),
+            f32::interpolate(start.y, end.y, progress),
+        )
+    }
+}
+
+impl Interpolate for Color {
+    fn interpolate(start: Self, end: Self, progress: f64) -> Self {
+        let channel = |i: usize| {
+            let start = f64::from(start[i]);
+            let end = f64::from(end[i]);
+
+            (start + (end - start) * progress).round().clamp(0.0, 255.0) as u8
+        };
+
+        Color::new(channel(0), channel(1), channel(2), channel(3))
+    }
+}
+
+// Parameter commands don't transition; they hold their start value until
+// they end.
+impl Interpolate for bool {
+    fn interpolate(start: Self, _: Self, _: f64) -> Self {
+        start
+    }
+}
+
+impl Interpolate for BlendingParameters {
+    fn interpolate(start: Self, _: Self, _: f64) -> Self {
+        start
+    }
+}
+
 /// Interface of [`CommandTimeline`] without its generic type.
 pub trait ICommandTimeline {
     fn start_time(&self) -> f64;
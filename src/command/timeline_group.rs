@@ -1,11 +1,14 @@
+use std::mem;
+
 use rosu_map::{section::colors::Color, util::Pos};
 
-use crate::visual::BlendingParameters;
+use crate::visual::{BlendingParameters, Easing};
 
 use super::{CommandTimeline, ICommandTimeline};
 
 /// Collections of [`CommandTimeline`].
 #[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct CommandTimelineGroup {
     pub x: CommandTimeline<f32>,
     pub y: CommandTimeline<f32>,
@@ -40,6 +43,127 @@ impl CommandTimelineGroup {
         self.end_time() - self.start_time()
     }
 
+    /// Add an additive blending command the way `P,..,A` does.
+    ///
+    /// The blending only persists after the command if it has no duration.
+    pub fn add_additive(&mut self, easing: Easing, start_time: f64, end_time: f64) {
+        self.blending_parameters.add(
+            easing,
+            start_time,
+            end_time,
+            BlendingParameters::ADDITIVE,
+            if (end_time - start_time).abs() < f64::EPSILON {
+                BlendingParameters::ADDITIVE
+            } else {
+                BlendingParameters::INHERIT
+            },
+        );
+    }
+
+    /// Add a horizontal flip command the way `P,..,H` does.
+    ///
+    /// The flip only persists after the command if it has no duration.
+    pub fn add_flip_h(&mut self, easing: Easing, start_time: f64, end_time: f64) {
+        self.flip_h.add(
+            easing,
+            start_time,
+            end_time,
+            true,
+            (end_time - start_time).abs() < f64::EPSILON,
+        );
+    }
+
+    /// Add a vertical flip command the way `P,..,V` does.
+    ///
+    /// The flip only persists after the command if it has no duration.
+    pub fn add_flip_v(&mut self, easing: Easing, start_time: f64, end_time: f64) {
+        self.flip_v.add(
+            easing,
+            start_time,
+            end_time,
+            true,
+            (end_time - start_time).abs() < f64::EPSILON,
+        );
+    }
+
+    /// Map the start and end time of every command.
+    ///
+    /// Parameter commands that were added through [`add_additive`],
+    /// [`add_flip_h`], or [`add_flip_v`] are added through them again so
+    /// that whether they persist matches their new duration.
+    ///
+    /// [`add_additive`]: CommandTimelineGroup::add_additive
+    /// [`add_flip_h`]: CommandTimelineGroup::add_flip_h
+    /// [`add_flip_v`]: CommandTimelineGroup::add_flip_v
+    pub(crate) fn map_times<F: FnMut(f64) -> f64>(&mut self, mut f: F) {
+        self.x.map_times(&mut f);
+        self.y.map_times(&mut f);
+        self.scale.map_times(&mut f);
+        self.vector_scale.map_times(&mut f);
+        self.rotation.map_times(&mut f);
+        self.color.map_times(&mut f);
+        self.alpha.map_times(&mut f);
+
+        let blending_parameters = mem::take(&mut self.blending_parameters);
+        let flip_h = mem::take(&mut self.flip_h);
+        let flip_v = mem::take(&mut self.flip_v);
+
+        for command in blending_parameters.commands {
+            let start_time = f(command.start_time);
+            let end_time = f(command.end_time);
+
+            let is_additive = command.start_value == BlendingParameters::ADDITIVE
+                && (command.end_value == BlendingParameters::ADDITIVE
+                    || command.end_value == BlendingParameters::INHERIT);
+
+            if is_additive {
+                self.add_additive(command.easing, start_time, end_time);
+            } else {
+                self.blending_parameters.add(
+                    command.easing,
+                    start_time,
+                    end_time,
+                    command.start_value,
+                    command.end_value,
+                );
+            }
+        }
+
+        for command in flip_h.commands {
+            let start_time = f(command.start_time);
+            let end_time = f(command.end_time);
+
+            if command.start_value {
+                self.add_flip_h(command.easing, start_time, end_time);
+            } else {
+                self.flip_h.add(
+                    command.easing,
+                    start_time,
+                    end_time,
+                    false,
+                    command.end_value,
+                );
+            }
+        }
+
+        for command in flip_v.commands {
+            let start_time = f(command.start_time);
+            let end_time = f(command.end_time);
+
+            if command.start_value {
+                self.add_flip_v(command.easing, start_time, end_time);
+            } else {
+                self.flip_v.add(
+                    command.easing,
+                    start_time,
+                    end_time,
+                    false,
+                    command.end_value,
+                );
+            }
+        }
+    }
+
     /// Fold all timelines through the given function.
     pub fn fold_timelines<B, F>(&self, init: B, mut f: F) -> B
     where
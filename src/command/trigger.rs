@@ -1,11 +1,10 @@
-use std::{cell::RefCell, rc::Rc};
-
-use super::CommandTimelineGroup;
+use super::{CommandTimelineGroup, TriggerKind};
 
 /// Command trigger for a [`Sprite`].
 ///
 /// [`Sprite`]: crate::element::Sprite
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct CommandTrigger {
     pub group: CommandTimelineGroup,
     pub name: String,
@@ -25,38 +24,9 @@ impl CommandTrigger {
             group_num,
         }
     }
-}
-
-pub(crate) struct CommandTriggerInternal {
-    pub group: Rc<RefCell<CommandTimelineGroup>>,
-    pub name: String,
-    pub start_time: f64,
-    pub end_time: f64,
-    pub group_num: i32,
-}
-
-impl From<CommandTriggerInternal> for CommandTrigger {
-    fn from(trigger: CommandTriggerInternal) -> Self {
-        Self {
-            group: Rc::into_inner(trigger.group)
-                .expect("multiple strong references around")
-                .into_inner(),
-            name: trigger.name,
-            start_time: trigger.start_time,
-            end_time: trigger.end_time,
-            group_num: trigger.group_num,
-        }
-    }
-}
 
-impl CommandTriggerInternal {
-    pub fn new(name: String, start_time: f64, end_time: f64, group_num: i32) -> Self {
-        Self {
-            group: Rc::new(RefCell::new(CommandTimelineGroup::default())),
-            name,
-            start_time,
-            end_time,
-            group_num,
-        }
+    /// Parse the trigger's name into a [`TriggerKind`].
+    pub fn kind(&self) -> TriggerKind {
+        TriggerKind::parse(&self.name)
     }
 }
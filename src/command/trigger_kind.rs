@@ -0,0 +1,187 @@
+use std::fmt::{Display, Formatter, Result as FmtResult};
+
+use rosu_map::section::hit_objects::hit_samples::SampleBank;
+
+/// The condition that activates a [`CommandTrigger`].
+///
+/// [`CommandTrigger`]: crate::command::CommandTrigger
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum TriggerKind {
+    /// The player switches to passing.
+    Passing,
+    /// The player switches to failing.
+    Failing,
+    /// Any hit object is hit.
+    HitObjectHit,
+    /// A hit object with matching hit sounds is hit.
+    HitSound(HitSoundTrigger),
+    /// An unrecognized trigger name.
+    Unknown(String),
+}
+
+impl TriggerKind {
+    /// Parse a trigger name such as `"Passing"` or `"HitSoundDrumFinish2"`.
+    pub fn parse(s: &str) -> Self {
+        match s {
+            "Passing" => Self::Passing,
+            "Failing" => Self::Failing,
+            "HitObjectHit" => Self::HitObjectHit,
+            _ => s
+                .strip_prefix("HitSound")
+                .and_then(HitSoundTrigger::parse)
+                .map_or_else(|| Self::Unknown(s.to_owned()), Self::HitSound),
+        }
+    }
+}
+
+impl Display for TriggerKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        match self {
+            Self::Passing => f.write_str("Passing"),
+            Self::Failing => f.write_str("Failing"),
+            Self::HitObjectHit => f.write_str("HitObjectHit"),
+            Self::HitSound(trigger) => write!(f, "HitSound{trigger}"),
+            Self::Unknown(name) => f.write_str(name),
+        }
+    }
+}
+
+/// The hit sound requirements of a [`TriggerKind::HitSound`].
+///
+/// The name is of the form
+/// `HitSound[SampleSet][AdditionsSampleSet][Addition][CustomSampleSet]` where
+/// each part is optional. Unspecified parts, as well as the `All` sample set,
+/// match anything.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct HitSoundTrigger {
+    pub sample_set: Option<SampleBank>,
+    pub additions_sample_set: Option<SampleBank>,
+    pub addition: Option<HitSoundAddition>,
+    pub custom_sample_set: Option<i32>,
+}
+
+impl HitSoundTrigger {
+    /// Parse the part of a trigger name after `"HitSound"`.
+    fn parse(mut s: &str) -> Option<Self> {
+        // `SampleBank::None` represents `All`
+        fn parse_sample_set(s: &mut &str) -> Option<SampleBank> {
+            const SAMPLE_SETS: [(&str, SampleBank); 4] = [
+                ("All", SampleBank::None),
+                ("Normal", SampleBank::Normal),
+                ("Soft", SampleBank::Soft),
+                ("Drum", SampleBank::Drum),
+            ];
+
+            SAMPLE_SETS.iter().find_map(|(name, sample_set)| {
+                let rest = s.strip_prefix(name)?;
+                *s = rest;
+
+                Some(*sample_set)
+            })
+        }
+
+        let specified =
+            |sample_set: SampleBank| (sample_set != SampleBank::None).then_some(sample_set);
+        let mut trigger = Self::default();
+
+        if let Some(sample_set) = parse_sample_set(&mut s) {
+            trigger.sample_set = specified(sample_set);
+
+            if let Some(additions_sample_set) = parse_sample_set(&mut s) {
+                trigger.additions_sample_set = specified(additions_sample_set);
+            }
+        }
+
+        trigger.addition = [
+            HitSoundAddition::Whistle,
+            HitSoundAddition::Finish,
+            HitSoundAddition::Clap,
+        ]
+        .into_iter()
+        .find_map(|addition| {
+            let rest = s.strip_prefix(addition.as_str())?;
+            s = rest;
+
+            Some(addition)
+        });
+
+        if !s.is_empty() {
+            if !s.bytes().all(|byte| byte.is_ascii_digit()) {
+                return None;
+            }
+
+            trigger.custom_sample_set = Some(s.parse().ok()?);
+        }
+
+        Some(trigger)
+    }
+
+    /// Whether the given hit sound satisfies the trigger's requirements.
+    pub fn matches(
+        &self,
+        sample_set: SampleBank,
+        additions_sample_set: SampleBank,
+        additions: &[HitSoundAddition],
+        custom_sample_set: i32,
+    ) -> bool {
+        self.sample_set.is_none_or(|set| set == sample_set)
+            && self
+                .additions_sample_set
+                .is_none_or(|set| set == additions_sample_set)
+            && self
+                .addition
+                .is_none_or(|addition| additions.contains(&addition))
+            && self
+                .custom_sample_set
+                .is_none_or(|custom| custom == custom_sample_set)
+    }
+}
+
+impl Display for HitSoundTrigger {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        const fn sample_set_str(sample_set: Option<SampleBank>) -> &'static str {
+            match sample_set {
+                None | Some(SampleBank::None) => "All",
+                Some(SampleBank::Normal) => "Normal",
+                Some(SampleBank::Soft) => "Soft",
+                Some(SampleBank::Drum) => "Drum",
+            }
+        }
+
+        if self.sample_set.is_some() || self.additions_sample_set.is_some() {
+            f.write_str(sample_set_str(self.sample_set))?;
+        }
+
+        if self.additions_sample_set.is_some() {
+            f.write_str(sample_set_str(self.additions_sample_set))?;
+        }
+
+        if let Some(addition) = self.addition {
+            f.write_str(addition.as_str())?;
+        }
+
+        if let Some(custom_sample_set) = self.custom_sample_set {
+            write!(f, "{custom_sample_set}")?;
+        }
+
+        Ok(())
+    }
+}
+
+/// A hit sound addition.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum HitSoundAddition {
+    Whistle,
+    Finish,
+    Clap,
+}
+
+impl HitSoundAddition {
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Whistle => "Whistle",
+            Self::Finish => "Finish",
+            Self::Clap => "Clap",
+        }
+    }
+}
@@ -0,0 +1,803 @@
+use std::{
+    cmp::Reverse,
+    collections::{
+        hash_map::{DefaultHasher, Entry},
+        HashMap,
+    },
+    hash::{Hash, Hasher},
+    mem,
+    ops::Range,
+};
+
+use rosu_map::{
+    section::{colors::Color, events::BreakPeriod},
+    util::Pos,
+};
+
+use crate::{
+    command::{CommandLoop, CommandTimeline, CommandTimelineGroup, CommandTrigger, TypedCommand},
+    element::{Animation, AnimationLoopType, Element, ElementKind, Sample, Sprite, Video},
+    layer::{Layer, LayerId},
+    visual::{Anchor, BlendingParameters, Easing},
+    BackgroundColor, Storyboard,
+};
+
+/// A [`Storyboard`] stored in a compact form to reduce memory usage.
+///
+/// Paths and trigger names are interned so that each distinct string is
+/// stored only once, and commands of all sprites are stored column by column
+/// per value type. Sprites only keep the ranges of their non-empty
+/// timelines instead of a full [`CommandTimelineGroup`].
+///
+/// Elements are converted back into their regular form on access, e.g. through
+/// [`CompactStoryboard::element`] or [`CompactStoryboard::to_storyboard`].
+///
+/// ```
+/// use rosu_storyboard::{compact::CompactStoryboard, Storyboard};
+///
+/// let path = "./resources/Himeringo - Yotsuya-san ni Yoroshiku (RLC) [Winber1's Extreme].osu";
+/// let storyboard = Storyboard::from_path(path).unwrap();
+/// let compact = CompactStoryboard::new(&storyboard);
+///
+/// assert!(compact.heap_size() < storyboard.heap_size());
+/// assert_eq!(compact.to_storyboard(), storyboard);
+/// ```
+///
+/// To avoid holding the full [`Storyboard`] in memory at any point, the
+/// elements of a [`StoryboardEvents`] can be pushed one by one.
+///
+/// ```
+/// use rosu_storyboard::{compact::CompactStoryboard, StoryboardEvent, StoryboardEvents};
+///
+/// let path = "./resources/Himeringo - Yotsuya-san ni Yoroshiku (RLC) [Winber1's Extreme].osu";
+/// let events = StoryboardEvents::from_path(path).unwrap();
+///
+/// let mut compact = CompactStoryboard::default();
+/// compact.format_version = events.format_version();
+///
+/// for event in events {
+///     match event.unwrap() {
+///         StoryboardEvent::Element { layer, element } => compact.push(&layer, &element),
+///         StoryboardEvent::Background { file, offset } => {
+///             compact.background_file = file;
+///             compact.background_offset = offset;
+///         }
+///         StoryboardEvent::Break(period) => compact.breaks.push(period),
+///         StoryboardEvent::BackgroundColor(color) => compact.background_colors.push(color),
+///     }
+/// }
+///
+/// assert!(!compact.is_empty());
+/// ```
+///
+/// [`StoryboardEvents`]: crate::StoryboardEvents
+#[derive(Clone, Debug)]
+pub struct CompactStoryboard {
+    pub format_version: i32,
+    pub use_skin_sprites: bool,
+    pub background_file: String,
+    pub background_offset: Pos,
+    pub breaks: Vec<BreakPeriod>,
+    pub background_colors: Vec<BackgroundColor>,
+    /// Sorted by their order of creation.
+    layers: Vec<CompactLayer>,
+    min_layer_depth: i32,
+    strings: StringTable,
+    commands: CommandStore,
+    loops: Vec<CompactLoop>,
+    triggers: Vec<CompactTrigger>,
+}
+
+impl CompactStoryboard {
+    /// Create a [`CompactStoryboard`] from the given [`Storyboard`].
+    pub fn new(storyboard: &Storyboard) -> Self {
+        let mut layers: Vec<_> = storyboard.layers.iter().collect();
+
+        // Storyboards assign decreasing depths to layers as they are created
+        layers.sort_unstable_by_key(|(_, layer)| Reverse(layer.depth));
+
+        let mut compact = Self {
+            format_version: storyboard.format_version,
+            use_skin_sprites: storyboard.use_skin_sprites,
+            background_file: storyboard.background_file.clone(),
+            background_offset: storyboard.background_offset,
+            breaks: storyboard.breaks.clone(),
+            background_colors: storyboard.background_colors.clone(),
+            layers: Vec::with_capacity(layers.len()),
+            min_layer_depth: storyboard.min_layer_depth,
+            strings: StringTable::default(),
+            commands: CommandStore::default(),
+            loops: Vec::new(),
+            triggers: Vec::new(),
+        };
+
+        for (id, layer) in layers {
+            let elements = layer
+                .elements
+                .iter()
+                .map(|elem| compact.compact_element(elem))
+                .collect();
+
+            compact.layers.push(CompactLayer {
+                id: id.clone(),
+                depth: layer.depth,
+                masking: layer.masking,
+                visible_when_passing: layer.visible_when_passing,
+                visible_when_failing: layer.visible_when_failing,
+                elements,
+            });
+        }
+
+        compact
+    }
+
+    /// Convert back into a regular [`Storyboard`].
+    pub fn to_storyboard(&self) -> Storyboard {
+        let layers = self
+            .layers
+            .iter()
+            .map(|layer| {
+                let elements = layer
+                    .elements
+                    .iter()
+                    .map(|elem| self.expand_element(elem))
+                    .collect();
+
+                let layer_ = Layer {
+                    depth: layer.depth,
+                    masking: layer.masking,
+                    visible_when_passing: layer.visible_when_passing,
+                    visible_when_failing: layer.visible_when_failing,
+                    elements,
+                };
+
+                (layer.id.clone(), layer_)
+            })
+            .collect();
+
+        Storyboard {
+            format_version: self.format_version,
+            use_skin_sprites: self.use_skin_sprites,
+            background_file: self.background_file.clone(),
+            background_offset: self.background_offset,
+            breaks: self.breaks.clone(),
+            background_colors: self.background_colors.clone(),
+            layers,
+            min_layer_depth: self.min_layer_depth,
+        }
+    }
+
+    /// Add an [`Element`] to the layer with the given id.
+    ///
+    /// If there is no such layer yet, a new one is created below all other layers,
+    /// just like the decoder does for unknown layers.
+    pub fn push(&mut self, layer: &LayerId, element: &Element) {
+        let element = self.compact_element(element);
+
+        let idx = if let Some(idx) = self.layers.iter().position(|l| l.id == *layer) {
+            idx
+        } else {
+            self.min_layer_depth -= 1;
+
+            self.layers.push(CompactLayer {
+                id: layer.clone(),
+                depth: self.min_layer_depth,
+                masking: true,
+                visible_when_passing: true,
+                visible_when_failing: true,
+                elements: Vec::new(),
+            });
+
+            self.layers.len() - 1
+        };
+
+        self.layers[idx].elements.push(element);
+    }
+
+    /// The ids of all layers in their order of creation.
+    pub fn layer_ids(&self) -> impl ExactSizeIterator<Item = &LayerId> {
+        self.layers.iter().map(|layer| &layer.id)
+    }
+
+    /// The total amount of elements across all layers.
+    pub fn len(&self) -> usize {
+        self.layers.iter().map(|layer| layer.elements.len()).sum()
+    }
+
+    /// Whether no layer contains an element.
+    pub fn is_empty(&self) -> bool {
+        self.layers.iter().all(|layer| layer.elements.is_empty())
+    }
+
+    /// The total amount of commands across all elements.
+    pub const fn command_count(&self) -> usize {
+        self.commands.len()
+    }
+
+    /// All distinct paths and trigger names.
+    pub fn strings(&self) -> impl ExactSizeIterator<Item = &str> {
+        self.strings.iter()
+    }
+
+    /// Convert the element at the given index of a layer into its regular
+    /// form.
+    pub fn element(&self, layer: &LayerId, index: usize) -> Option<Element> {
+        self.layer(layer)?
+            .elements
+            .get(index)
+            .map(|elem| self.expand_element(elem))
+    }
+
+    /// Convert all elements of a layer into their regular form.
+    ///
+    /// If there is no such layer, the iterator is empty.
+    pub fn elements<'a>(&'a self, layer: &LayerId) -> impl Iterator<Item = Element> + 'a {
+        self.layer(layer)
+            .map_or(&[][..], |layer| &layer.elements)
+            .iter()
+            .map(|elem| self.expand_element(elem))
+    }
+
+    /// The amount of bytes allocated on the heap.
+    pub fn heap_size(&self) -> usize {
+        let layers = vec_size(&self.layers)
+            + self
+                .layers
+                .iter()
+                .map(|layer| layer_id_heap_size(&layer.id) + vec_size(&layer.elements))
+                .sum::<usize>();
+
+        self.background_file.capacity()
+            + vec_size(&self.breaks)
+            + vec_size(&self.background_colors)
+            + layers
+            + self.strings.heap_size()
+            + self.commands.heap_size()
+            + vec_size(&self.loops)
+            + vec_size(&self.triggers)
+    }
+
+    fn layer(&self, id: &LayerId) -> Option<&CompactLayer> {
+        self.layers.iter().find(|layer| layer.id == *id)
+    }
+
+    fn compact_element(&mut self, element: &Element) -> CompactElement {
+        let kind = match element.kind {
+            ElementKind::Animation(ref animation) => CompactKind::Animation {
+                sprite: self.compact_sprite(&animation.sprite),
+                frame_count: animation.frame_count,
+                frame_delay: animation.frame_delay,
+                loop_kind: animation.loop_kind,
+            },
+            ElementKind::Sample(sample) => CompactKind::Sample(sample),
+            ElementKind::Sprite(ref sprite) => CompactKind::Sprite(self.compact_sprite(sprite)),
+            ElementKind::Video(video) => CompactKind::Video(video),
+        };
+
+        CompactElement {
+            path: self.strings.intern(&element.path),
+            kind,
+        }
+    }
+
+    fn compact_sprite(&mut self, sprite: &Sprite) -> CompactSprite {
+        let group = self.commands.push_group(&sprite.timeline_group);
+
+        let loops_start = self.loops.len();
+
+        for l in sprite.loops.iter() {
+            let group = self.commands.push_group(&l.group);
+
+            self.loops.push(CompactLoop {
+                group,
+                loop_start_time: l.loop_start_time,
+                total_iterations: l.total_iterations,
+            });
+        }
+
+        let triggers_start = self.triggers.len();
+
+        for trigger in sprite.triggers.iter() {
+            let group = self.commands.push_group(&trigger.group);
+
+            self.triggers.push(CompactTrigger {
+                group,
+                name: self.strings.intern(&trigger.name),
+                start_time: trigger.start_time,
+                end_time: trigger.end_time,
+                group_num: trigger.group_num,
+            });
+        }
+
+        CompactSprite {
+            origin: sprite.origin,
+            initial_pos: sprite.initial_pos,
+            group,
+            loops: Span::new(loops_start, self.loops.len()),
+            triggers: Span::new(triggers_start, self.triggers.len()),
+        }
+    }
+
+    fn expand_element(&self, element: &CompactElement) -> Element {
+        let kind = match element.kind {
+            CompactKind::Animation {
+                ref sprite,
+                frame_count,
+                frame_delay,
+                loop_kind,
+            } => ElementKind::Animation(Animation {
+                sprite: self.expand_sprite(sprite),
+                frame_count,
+                frame_delay,
+                loop_kind,
+            }),
+            CompactKind::Sample(sample) => ElementKind::Sample(sample),
+            CompactKind::Sprite(ref sprite) => ElementKind::Sprite(self.expand_sprite(sprite)),
+            CompactKind::Video(video) => ElementKind::Video(video),
+        };
+
+        Element::new(self.strings.get(element.path).to_owned(), kind)
+    }
+
+    fn expand_sprite(&self, sprite: &CompactSprite) -> Sprite {
+        let loops = self.loops[sprite.loops.range()]
+            .iter()
+            .map(|l| CommandLoop {
+                group: self.commands.group(l.group),
+                loop_start_time: l.loop_start_time,
+                total_iterations: l.total_iterations,
+            })
+            .collect();
+
+        let triggers = self.triggers[sprite.triggers.range()]
+            .iter()
+            .map(|trigger| CommandTrigger {
+                group: self.commands.group(trigger.group),
+                name: self.strings.get(trigger.name).to_owned(),
+                start_time: trigger.start_time,
+                end_time: trigger.end_time,
+                group_num: trigger.group_num,
+            })
+            .collect();
+
+        Sprite {
+            origin: sprite.origin,
+            initial_pos: sprite.initial_pos,
+            timeline_group: self.commands.group(sprite.group),
+            loops,
+            triggers,
+        }
+    }
+}
+
+impl Default for CompactStoryboard {
+    fn default() -> Self {
+        Self::new(&Storyboard::default())
+    }
+}
+
+impl From<&Storyboard> for CompactStoryboard {
+    fn from(storyboard: &Storyboard) -> Self {
+        Self::new(storyboard)
+    }
+}
+
+impl From<&CompactStoryboard> for Storyboard {
+    fn from(compact: &CompactStoryboard) -> Self {
+        compact.to_storyboard()
+    }
+}
+
+impl Storyboard {
+    /// The amount of bytes allocated on the heap.
+    ///
+    /// The size of the layer map is estimated based on its capacity.
+    pub fn heap_size(&self) -> usize {
+        let layers = self.layers.capacity() * (mem::size_of::<(LayerId, Layer)>() + 1)
+            + self
+                .layers
+                .iter()
+                .map(|(id, layer)| {
+                    layer_id_heap_size(id)
+                        + vec_size(&layer.elements)
+                        + layer.elements.iter().map(element_heap_size).sum::<usize>()
+                })
+                .sum::<usize>();
+
+        self.background_file.capacity()
+            + vec_size(&self.breaks)
+            + vec_size(&self.background_colors)
+            + layers
+    }
+}
+
+const fn layer_id_heap_size(id: &LayerId) -> usize {
+    match id {
+        LayerId::Custom(name) => name.capacity(),
+        _ => 0,
+    }
+}
+
+fn element_heap_size(element: &Element) -> usize {
+    let kind = match element.kind {
+        ElementKind::Animation(ref animation) => sprite_heap_size(&animation.sprite),
+        ElementKind::Sprite(ref sprite) => sprite_heap_size(sprite),
+        ElementKind::Sample(_) | ElementKind::Video(_) => 0,
+    };
+
+    element.path.capacity() + kind
+}
+
+fn sprite_heap_size(sprite: &Sprite) -> usize {
+    let loops = sprite
+        .loops
+        .iter()
+        .map(|l| group_heap_size(&l.group))
+        .sum::<usize>();
+
+    let triggers = sprite
+        .triggers
+        .iter()
+        .map(|trigger| trigger.name.capacity() + group_heap_size(&trigger.group))
+        .sum::<usize>();
+
+    group_heap_size(&sprite.timeline_group)
+        + vec_size(&sprite.loops)
+        + loops
+        + vec_size(&sprite.triggers)
+        + triggers
+}
+
+const fn group_heap_size(group: &CommandTimelineGroup) -> usize {
+    vec_size(&group.x.commands)
+        + vec_size(&group.y.commands)
+        + vec_size(&group.scale.commands)
+        + vec_size(&group.vector_scale.commands)
+        + vec_size(&group.rotation.commands)
+        + vec_size(&group.color.commands)
+        + vec_size(&group.alpha.commands)
+        + vec_size(&group.blending_parameters.commands)
+        + vec_size(&group.flip_h.commands)
+        + vec_size(&group.flip_v.commands)
+}
+
+const fn vec_size<T>(vec: &Vec<T>) -> usize {
+    vec.capacity() * mem::size_of::<T>()
+}
+
+#[derive(Clone, Debug)]
+struct CompactLayer {
+    id: LayerId,
+    depth: i32,
+    masking: bool,
+    visible_when_passing: bool,
+    visible_when_failing: bool,
+    elements: Vec<CompactElement>,
+}
+
+#[derive(Clone, Debug)]
+struct CompactElement {
+    path: StrId,
+    kind: CompactKind,
+}
+
+#[derive(Clone, Debug)]
+enum CompactKind {
+    Animation {
+        sprite: CompactSprite,
+        frame_count: i32,
+        frame_delay: f64,
+        loop_kind: AnimationLoopType,
+    },
+    Sample(Sample),
+    Sprite(CompactSprite),
+    Video(Video),
+}
+
+#[derive(Clone, Debug)]
+struct CompactSprite {
+    origin: Anchor,
+    initial_pos: Pos,
+    /// Timelines of the main group.
+    group: Span,
+    loops: Span,
+    triggers: Span,
+}
+
+#[derive(Clone, Debug)]
+struct CompactLoop {
+    group: Span,
+    loop_start_time: f64,
+    total_iterations: u32,
+}
+
+#[derive(Clone, Debug)]
+struct CompactTrigger {
+    group: Span,
+    name: StrId,
+    start_time: f64,
+    end_time: f64,
+    group_num: i32,
+}
+
+/// A range of indices.
+#[derive(Copy, Clone, Debug, Default)]
+struct Span {
+    start: u32,
+    end: u32,
+}
+
+impl Span {
+    const fn new(start: usize, end: usize) -> Self {
+        Self {
+            start: start as u32,
+            end: end as u32,
+        }
+    }
+
+    const fn range(self) -> Range<usize> {
+        self.start as usize..self.end as usize
+    }
+}
+
+type StrId = u32;
+
+/// Distinct strings stored in a single buffer.
+#[derive(Clone, Debug, Default)]
+struct StringTable {
+    data: String,
+    /// End offset of each string in `data`.
+    ends: Vec<u32>,
+    /// Maps string hashes to their id. Colliding hashes are resolved by
+    /// probing the following hash values.
+    ids: HashMap<u64, StrId>,
+}
+
+impl StringTable {
+    fn get(&self, id: StrId) -> &str {
+        get_str(&self.data, &self.ends, id)
+    }
+
+    fn intern(&mut self, s: &str) -> StrId {
+        let mut hasher = DefaultHasher::new();
+        s.hash(&mut hasher);
+        let mut hash = hasher.finish();
+
+        loop {
+            match self.ids.entry(hash) {
+                Entry::Occupied(entry) => {
+                    let id = *entry.get();
+
+                    if get_str(&self.data, &self.ends, id) == s {
+                        return id;
+                    }
+
+                    hash = hash.wrapping_add(1);
+                }
+                Entry::Vacant(entry) => {
+                    let id = self.ends.len() as StrId;
+                    self.data.push_str(s);
+                    self.ends.push(self.data.len() as u32);
+
+                    return *entry.insert(id);
+                }
+            }
+        }
+    }
+
+    fn iter(&self) -> impl ExactSizeIterator<Item = &str> {
+        (0..self.ends.len() as StrId).map(|id| self.get(id))
+    }
+
+    fn heap_size(&self) -> usize {
+        // Estimate the map's allocation based on its capacity
+        self.data.capacity()
+            + vec_size(&self.ends)
+            + self.ids.capacity() * (mem::size_of::<(u64, StrId)>() + 1)
+    }
+}
+
+fn get_str<'a>(data: &'a str, ends: &[u32], id: StrId) -> &'a str {
+    let id = id as usize;
+    let start = id.checked_sub(1).map_or(0, |prev| ends[prev] as usize);
+
+    &data[start..ends[id] as usize]
+}
+
+/// Commands of all groups, stored column by column per value type.
+#[derive(Clone, Debug, Default)]
+struct CommandStore {
+    floats: Columns<f32>,
+    positions: Columns<Pos>,
+    colors: Columns<Color>,
+    blending_parameters: Columns<BlendingParameters>,
+    flags: Columns<bool>,
+    /// Non-empty timelines of all groups. Each group references a range of
+    /// them.
+    timelines: Vec<TimelineSpan>,
+}
+
+#[derive(Copy, Clone, Debug)]
+struct TimelineSpan {
+    kind: TimelineKind,
+    commands: Span,
+}
+
+#[derive(Copy, Clone, Debug)]
+enum TimelineKind {
+    X,
+    Y,
+    Scale,
+    VectorScale,
+    Rotation,
+    Color,
+    Alpha,
+    BlendingParameters,
+    FlipH,
+    FlipV,
+}
+
+impl CommandStore {
+    const fn len(&self) -> usize {
+        self.floats.len()
+            + self.positions.len()
+            + self.colors.len()
+            + self.blending_parameters.len()
+            + self.flags.len()
+    }
+
+    fn push_group(&mut self, group: &CommandTimelineGroup) -> Span {
+        let start = self.timelines.len();
+
+        self.push_timeline(TimelineKind::X, &group.x);
+        self.push_timeline(TimelineKind::Y, &group.y);
+        self.push_timeline(TimelineKind::Scale, &group.scale);
+        self.push_timeline(TimelineKind::VectorScale, &group.vector_scale);
+        self.push_timeline(TimelineKind::Rotation, &group.rotation);
+        self.push_timeline(TimelineKind::Color, &group.color);
+        self.push_timeline(TimelineKind::Alpha, &group.alpha);
+        self.push_timeline(TimelineKind::BlendingParameters, &group.blending_parameters);
+        self.push_timeline(TimelineKind::FlipH, &group.flip_h);
+        self.push_timeline(TimelineKind::FlipV, &group.flip_v);
+
+        Span::new(start, self.timelines.len())
+    }
+
+    fn push_timeline<T: ColumnValue>(&mut self, kind: TimelineKind, timeline: &CommandTimeline<T>) {
+        if timeline.commands.is_empty() {
+            return;
+        }
+
+        let columns = T::columns_mut(self);
+        let start = columns.len();
+        columns.extend(&timeline.commands);
+        let commands = Span::new(start, columns.len());
+
+        self.timelines.push(TimelineSpan { kind, commands });
+    }
+
+    /// Rebuild a group by adding its commands in their original order.
+    fn group(&self, span: Span) -> CommandTimelineGroup {
+        let mut group = CommandTimelineGroup::default();
+
+        for timeline in self.timelines[span.range()].iter() {
+            let commands = timeline.commands;
+
+            match timeline.kind {
+                TimelineKind::X => self.fill(commands, &mut group.x),
+                TimelineKind::Y => self.fill(commands, &mut group.y),
+                TimelineKind::Scale => self.fill(commands, &mut group.scale),
+                TimelineKind::VectorScale => self.fill(commands, &mut group.vector_scale),
+                TimelineKind::Rotation => self.fill(commands, &mut group.rotation),
+                TimelineKind::Color => self.fill(commands, &mut group.color),
+                TimelineKind::Alpha => self.fill(commands, &mut group.alpha),
+                TimelineKind::BlendingParameters => {
+                    self.fill(commands, &mut group.blending_parameters);
+                }
+                TimelineKind::FlipH => self.fill(commands, &mut group.flip_h),
+                TimelineKind::FlipV => self.fill(commands, &mut group.flip_v),
+            }
+        }
+
+        group
+    }
+
+    fn fill<T: ColumnValue>(&self, commands: Span, timeline: &mut CommandTimeline<T>) {
+        T::columns(self).fill(commands, timeline);
+    }
+
+    const fn heap_size(&self) -> usize {
+        self.floats.heap_size()
+            + self.positions.heap_size()
+            + self.colors.heap_size()
+            + self.blending_parameters.heap_size()
+            + self.flags.heap_size()
+            + vec_size(&self.timelines)
+    }
+}
+
+/// Command values that have their own [`Columns`] in a [`CommandStore`].
+trait ColumnValue: Copy + Sized {
+    fn columns(store: &CommandStore) -> &Columns<Self>;
+    fn columns_mut(store: &mut CommandStore) -> &mut Columns<Self>;
+}
+
+macro_rules! column_value {
+    ($ty:ty, $field:ident) => {
+        impl ColumnValue for $ty {
+            fn columns(store: &CommandStore) -> &Columns<Self> {
+                &store.$field
+            }
+
+            fn columns_mut(store: &mut CommandStore) -> &mut Columns<Self> {
+                &mut store.$field
+            }
+        }
+    };
+}
+
+column_value!(f32, floats);
+column_value!(Pos, positions);
+column_value!(Color, colors);
+column_value!(BlendingParameters, blending_parameters);
+column_value!(bool, flags);
+
+/// Fields of [`TypedCommand`]s stored in separate vectors.
+#[derive(Clone, Debug)]
+struct Columns<T> {
+    easings: Vec<Easing>,
+    start_times: Vec<f64>,
+    end_times: Vec<f64>,
+    start_values: Vec<T>,
+    end_values: Vec<T>,
+}
+
+impl<T: Copy> Columns<T> {
+    const fn len(&self) -> usize {
+        self.easings.len()
+    }
+
+    fn extend(&mut self, commands: &[TypedCommand<T>]) {
+        for command in commands {
+            self.easings.push(command.easing);
+            self.start_times.push(command.start_time);
+            self.end_times.push(command.end_time);
+            self.start_values.push(command.start_value);
+            self.end_values.push(command.end_value);
+        }
+    }
+
+    fn fill(&self, commands: Span, timeline: &mut CommandTimeline<T>) {
+        timeline.commands.reserve_exact(commands.range().len());
+
+        for i in commands.range() {
+            timeline.add(
+                self.easings[i],
+                self.start_times[i],
+                self.end_times[i],
+                self.start_values[i],
+                self.end_values[i],
+            );
+        }
+    }
+
+    const fn heap_size(&self) -> usize {
+        vec_size(&self.easings)
+            + vec_size(&self.start_times)
+            + vec_size(&self.end_times)
+            + vec_size(&self.start_values)
+            + vec_size(&self.end_values)
+    }
+}
+
+impl<T> Default for Columns<T> {
+    fn default() -> Self {
+        Self {
+            easings: Vec::new(),
+            start_times: Vec::new(),
+            end_times: Vec::new(),
+            start_values: Vec::new(),
+            end_values: Vec::new(),
+        }
+    }
+}
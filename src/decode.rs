@@ -1,4 +1,4 @@
-use std::{borrow::Cow, cell::RefCell, cmp, collections::HashMap, error, fmt, rc::Rc, str::Split};
+use std::{borrow::Cow, cmp, collections::HashMap, error, fmt, mem, str::Split, vec};
 
 use rosu_map::{
     section::{
@@ -11,18 +11,12 @@ use rosu_map::{
 
 use crate::{
     command::CommandTimelineGroup,
-    element::{
-        AnimationInternal, AnimationLoopType, ElementInternal, ElementKindInternal, Sample,
-        SpriteInternal, Video,
-    },
-    layer::StoryLayer,
-    storyboard::StoryboardInternal,
-    visual::{BlendingParameters, Easing, Origins},
-    Storyboard,
+    element::{Animation, AnimationLoopType, Element, ElementKind, Sample, Sprite, Video},
+    layer::LayerId,
+    visual::{Easing, Origins},
+    BackgroundColor, Storyboard,
 };
 
-use self::pending::PendingSprite;
-
 /// All the ways that parsing an osu! file into a [`Storyboard`] can fail.
 #[derive(Debug)]
 pub enum ParseStoryboardError {
@@ -30,6 +24,10 @@ pub enum ParseStoryboardError {
     InvalidLine,
     Number(ParseNumberError),
     UnknownCommandType,
+    /// The volume of a sample is not between 0 and 100.
+    ///
+    /// The sample is still added with its volume clamped.
+    VolumeOutOfRange,
 }
 
 impl error::Error for ParseStoryboardError {
@@ -37,7 +35,9 @@ impl error::Error for ParseStoryboardError {
         match self {
             ParseStoryboardError::EventType(err) => Some(err),
             ParseStoryboardError::Number(err) => Some(err),
-            ParseStoryboardError::InvalidLine | ParseStoryboardError::UnknownCommandType => None,
+            ParseStoryboardError::InvalidLine
+            | ParseStoryboardError::UnknownCommandType
+            | ParseStoryboardError::VolumeOutOfRange => None,
         }
     }
 }
@@ -49,6 +49,7 @@ impl fmt::Display for ParseStoryboardError {
             Self::InvalidLine => "invalid line",
             Self::Number(_) => "failed to parse number",
             Self::UnknownCommandType => "unknown command type",
+            Self::VolumeOutOfRange => "volume must be between 0 and 100",
         };
 
         f.write_str(s)
@@ -69,18 +70,234 @@ impl From<ParseNumberError> for ParseStoryboardError {
 
 /// The parsing state for [`Storyboard`] in [`DecodeBeatmap`].
 pub struct StoryboardState {
-    format_version: i32,
-    use_skin_sprites: bool,
-    background_file: String,
-    breaks: Vec<BreakPeriod>,
-    storyboard: StoryboardInternal,
-    sprite: PendingSprite,
-    timeline_group: Option<Rc<RefCell<CommandTimelineGroup>>>,
+    storyboard: Storyboard,
+    /// Names of the layers that contain sprites, indexed by [`SpriteRef`].
+    layer_ids: Vec<LayerId>,
+    /// The sprite of the current event, if any.
+    sprite: Option<SpriteRef>,
+    /// The group that commands are currently added to.
+    ///
+    /// Note that it is not reset by new events so it may still point to an
+    /// earlier sprite.
+    timeline_group: Option<GroupRef>,
+    /// Whether the background file was set by an event rather than by the
+    /// first sprite since it was last taken.
+    explicit_background: bool,
     variables: HashMap<Box<str>, Box<str>>,
 }
 
+/// Location of a [`Sprite`] or [`Animation`] within the decoded layers.
+#[derive(Copy, Clone)]
+struct SpriteRef {
+    layer: usize,
+    element: usize,
+}
+
+/// Location of a [`CommandTimelineGroup`] within the decoded layers.
+#[derive(Copy, Clone)]
+struct GroupRef {
+    sprite: SpriteRef,
+    kind: GroupKind,
+}
+
+#[derive(Copy, Clone)]
+enum GroupKind {
+    Main,
+    Loop(usize),
+    Trigger(usize),
+}
+
 impl StoryboardState {
-    fn decode_variables<'a>(&self, line: &'a str) -> Cow<'a, str> {
+    fn push_sprite(&mut self, layer: LayerId, path: String, kind: ElementKind) {
+        let layer_idx = if let Some(idx) = self.layer_ids.iter().position(|id| *id == layer) {
+            idx
+        } else {
+            self.layer_ids.push(layer.clone());
+
+            self.layer_ids.len() - 1
+        };
+
+        let elements = &mut self.storyboard.get_or_insert_layer(layer).elements;
+        elements.push(Element { path, kind });
+
+        self.sprite = Some(SpriteRef {
+            layer: layer_idx,
+            element: elements.len() - 1,
+        });
+    }
+
+    fn sprite_mut(&mut self, sprite: SpriteRef) -> Option<&mut Sprite> {
+        let layer = self
+            .storyboard
+            .layers
+            .get_mut(&self.layer_ids[sprite.layer])?;
+
+        match layer.elements.get_mut(sprite.element)?.kind {
+            ElementKind::Animation(ref mut animation) => Some(&mut animation.sprite),
+            ElementKind::Sprite(ref mut sprite) => Some(sprite),
+            ElementKind::Sample(_) | ElementKind::Video(_) => None,
+        }
+    }
+
+    fn current_sprite_mut(&mut self) -> Option<(SpriteRef, &mut Sprite)> {
+        let sprite_ref = self.sprite?;
+
+        self.sprite_mut(sprite_ref)
+            .map(|sprite| (sprite_ref, sprite))
+    }
+
+    fn group_mut(&mut self) -> Option<&mut CommandTimelineGroup> {
+        let group = self.timeline_group?;
+        let sprite = self.sprite_mut(group.sprite)?;
+
+        match group.kind {
+            GroupKind::Main => Some(&mut sprite.timeline_group),
+            GroupKind::Loop(idx) => sprite.loops.get_mut(idx).map(|l| &mut l.group),
+            GroupKind::Trigger(idx) => sprite.triggers.get_mut(idx).map(|t| &mut t.group),
+        }
+    }
+
+    #[cfg(feature = "parallel")]
+    pub(crate) const fn has_timeline_group(&self) -> bool {
+        self.timeline_group.is_some()
+    }
+
+    #[cfg(feature = "parallel")]
+    pub(crate) const fn explicit_background(&self) -> bool {
+        self.explicit_background
+    }
+
+    pub(crate) const fn storyboard(&self) -> &Storyboard {
+        &self.storyboard
+    }
+
+    pub(crate) const fn has_sprite(&self) -> bool {
+        self.sprite.is_some()
+    }
+
+    /// Remove all decoded elements and forget the current sprite and group.
+    pub(crate) fn drain_elements(&mut self) -> impl Iterator<Item = (&LayerId, Element)> {
+        self.sprite = None;
+        self.timeline_group = None;
+
+        self.storyboard
+            .layers
+            .iter_mut()
+            .flat_map(|(id, layer)| layer.elements.drain(..).map(move |element| (id, element)))
+    }
+
+    pub(crate) fn drain_breaks(&mut self) -> vec::Drain<'_, BreakPeriod> {
+        self.storyboard.breaks.drain(..)
+    }
+
+    pub(crate) fn drain_background_colors(&mut self) -> vec::Drain<'_, BackgroundColor> {
+        self.storyboard.background_colors.drain(..)
+    }
+
+    /// Return the background file if it has been set by an event since the
+    /// last call.
+    pub(crate) fn take_background(&mut self) -> Option<(String, Pos)> {
+        mem::take(&mut self.explicit_background).then(|| {
+            let storyboard = &self.storyboard;
+
+            (
+                storyboard.background_file.clone(),
+                storyboard.background_offset,
+            )
+        })
+    }
+
+    /// Parse an event line whose comments and variables have already been
+    /// handled.
+    pub(crate) fn parse_event(&mut self, line: &str) -> Result<(), ParseStoryboardError> {
+        let depth = line
+            .chars()
+            .take_while(|ch| matches!(ch, ' ' | '_'))
+            .count();
+
+        let line = &line[depth..];
+        let mut split = line.split(',');
+
+        if depth == 0 {
+            let Some(event_type) = split.next() else {
+                return Err(ParseStoryboardError::InvalidLine);
+            };
+
+            self.sprite = None;
+
+            return match event_type.parse()? {
+                EventType::Video => self.parse_video(&mut split),
+                EventType::Sprite => self.parse_sprite(&mut split),
+                EventType::Animation => self.parse_animation(&mut split),
+                EventType::Sample => self.parse_sample(&mut split),
+                EventType::Background => self.parse_background(&mut split),
+                EventType::Break => self.parse_break(&mut split),
+                EventType::Color => self.parse_background_color(&mut split),
+            };
+        }
+
+        if depth < 2 {
+            if let Some(sprite) = self.sprite {
+                self.timeline_group = Some(GroupRef {
+                    sprite,
+                    kind: GroupKind::Main,
+                });
+            }
+        }
+
+        let Some(command_type) = split.next() else {
+            return Err(ParseStoryboardError::InvalidLine);
+        };
+
+        match command_type {
+            "T" => return self.parse_trigger(&mut split),
+            "L" => return self.parse_loop(&mut split),
+            _ => {}
+        }
+
+        let Some(((easing, start_time), end_time)) =
+            split.next().zip(split.next()).zip(split.next())
+        else {
+            return Err(ParseStoryboardError::InvalidLine);
+        };
+
+        let easing = Easing::from(i32::parse(easing)?);
+        let start_time = f64::parse(start_time)?;
+
+        let end_time = if end_time.is_empty() {
+            start_time
+        } else {
+            f64::parse(end_time)?
+        };
+
+        match command_type {
+            "F" => self.parse_alpha(&mut split, easing, start_time, end_time),
+            "S" => self.parse_scale(&mut split, easing, start_time, end_time),
+            "V" => self.parse_vector_scale(&mut split, easing, start_time, end_time),
+            "R" => self.parse_rotation(&mut split, easing, start_time, end_time),
+            "M" => self.parse_pos(&mut split, easing, start_time, end_time),
+            "MX" => self.parse_x(&mut split, easing, start_time, end_time),
+            "MY" => self.parse_y(&mut split, easing, start_time, end_time),
+            "C" => self.parse_color(&mut split, easing, start_time, end_time),
+            "P" => {
+                let Some(kind) = split.next() else {
+                    return Err(ParseStoryboardError::InvalidLine);
+                };
+
+                match kind {
+                    "A" => self.add_blending(easing, start_time, end_time),
+                    "H" => self.add_flip_h(easing, start_time, end_time),
+                    "V" => self.add_flip_v(easing, start_time, end_time),
+                    _ => {}
+                }
+
+                Ok(())
+            }
+            _ => Err(ParseStoryboardError::UnknownCommandType),
+        }
+    }
+
+    pub(crate) fn decode_variables<'a>(&self, line: &'a str) -> Cow<'a, str> {
         let mut line = Cow::Borrowed(line);
 
         while line.contains('$') {
@@ -102,8 +319,8 @@ impl StoryboardState {
     }
 
     fn parse_video(&mut self, split: &mut Split<'_, char>) -> Result<(), ParseStoryboardError> {
-        const VIDEO_EXTENSIONS: &[[u8; 3]] = &[
-            *b"mp4", *b"mov", *b"avi", *b"flv", *b"mpg", *b"wmv", *b"m4v",
+        const VIDEO_EXTENSIONS: &[&str] = &[
+            "mp4", "mov", "avi", "flv", "mpg", "wmv", "m4v", "mkv", "webm", "mpeg",
         ];
 
         let Some((offset, path)) = split.next().zip(split.next()) else {
@@ -112,26 +329,27 @@ impl StoryboardState {
 
         let offset = i32::parse(offset)?;
         let path = path.clean_filename();
+        let pos = parse_offset(split);
 
-        if let [.., a, b, c] = path.as_bytes() {
-            let extension = [
-                a.to_ascii_lowercase(),
-                b.to_ascii_lowercase(),
-                c.to_ascii_lowercase(),
-            ];
-
-            if VIDEO_EXTENSIONS.contains(&extension) {
-                let video = Video::new(f64::from(offset));
-                self.storyboard
-                    .get_layer("Video")
-                    .elements
-                    .push(ElementInternal {
-                        path,
-                        kind: ElementKindInternal::Video(video),
-                    });
-            } else {
-                self.background_file = path;
-            }
+        let is_video = path.rsplit_once('.').is_some_and(|(_, extension)| {
+            VIDEO_EXTENSIONS
+                .iter()
+                .any(|video| extension.eq_ignore_ascii_case(video))
+        });
+
+        if is_video {
+            let video = Video::new(f64::from(offset)).with_offset(pos);
+            self.storyboard
+                .get_or_insert_layer(LayerId::Video)
+                .elements
+                .push(Element {
+                    path,
+                    kind: ElementKind::Video(video),
+                });
+        } else {
+            self.storyboard.background_file = path;
+            self.storyboard.background_offset = pos;
+            self.explicit_background = true;
         }
 
         Ok(())
@@ -148,18 +366,18 @@ impl StoryboardState {
             return Err(ParseStoryboardError::InvalidLine);
         };
 
-        let layer = StoryLayer::parse(layer);
+        let layer = LayerId::parse(layer);
         let origin = Origins::parse(origin);
         let path = path.clean_filename();
         let x = f32::parse_with_limits(x, MAX_COORDINATE_VALUE as f32)?;
         let y = f32::parse_with_limits(y, MAX_COORDINATE_VALUE as f32)?;
-        let sprite = SpriteInternal::new(origin, Pos::new(x, y));
+        let sprite = Sprite::new(origin, Pos::new(x, y));
 
-        if self.background_file.is_empty() {
-            self.background_file = path.clone();
+        if self.storyboard.background_file.is_empty() {
+            self.storyboard.background_file.clone_from(&path);
         }
 
-        self.sprite.set_sprite(path, &layer, sprite);
+        self.push_sprite(layer, path, ElementKind::Sprite(sprite));
 
         Ok(())
     }
@@ -177,7 +395,7 @@ impl StoryboardState {
             return Err(ParseStoryboardError::InvalidLine);
         };
 
-        let layer = StoryLayer::parse(layer);
+        let layer = LayerId::parse(layer);
         let origin = Origins::parse(origin);
         let path = path.clean_filename();
         let x = f32::parse_with_limits(x, MAX_COORDINATE_VALUE as f32)?;
@@ -185,7 +403,7 @@ impl StoryboardState {
         let frame_count = i32::parse(frame_count)?;
         let mut frame_delay = f64::parse(frame_delay)?;
 
-        if self.format_version < 6 {
+        if self.storyboard.format_version < 6 {
             frame_delay = (0.015 * frame_delay).round() * 1.186 * (1000.0 / 60.0);
         }
 
@@ -195,10 +413,9 @@ impl StoryboardState {
             AnimationLoopType::LoopForever
         };
 
-        let animation =
-            AnimationInternal::new(origin, Pos::new(x, y), frame_count, frame_delay, loop_type);
+        let animation = Animation::new(origin, Pos::new(x, y), frame_count, frame_delay, loop_type);
 
-        self.sprite.set_animation(path, &layer, animation);
+        self.push_sprite(layer, path, ElementKind::Animation(animation));
 
         Ok(())
     }
@@ -209,7 +426,7 @@ impl StoryboardState {
         };
 
         let time = f64::parse(time)?;
-        let layer = StoryLayer::parse(layer);
+        let layer = LayerId::parse(layer);
         let path = path.clean_filename();
 
         let volume = if let Some(volume) = split.next() {
@@ -218,16 +435,20 @@ impl StoryboardState {
             100.0
         };
 
-        let sample = Sample::new(time, volume as i32);
+        let sample = Sample::new(time, volume.clamp(0.0, 100.0));
         self.storyboard
-            .get_layer(layer.as_str())
+            .get_or_insert_layer(layer)
             .elements
-            .push(ElementInternal {
+            .push(Element {
                 path,
-                kind: ElementKindInternal::Sample(sample),
+                kind: ElementKind::Sample(sample),
             });
 
-        Ok(())
+        if (0.0..=100.0).contains(&volume) {
+            Ok(())
+        } else {
+            Err(ParseStoryboardError::VolumeOutOfRange)
+        }
     }
 
     fn parse_background(
@@ -235,7 +456,9 @@ impl StoryboardState {
         split: &mut Split<'_, char>,
     ) -> Result<(), ParseStoryboardError> {
         let background_file = split.nth(1).ok_or(ParseStoryboardError::InvalidLine)?;
-        self.background_file = background_file.clean_filename();
+        self.storyboard.background_file = background_file.clean_filename();
+        self.storyboard.background_offset = parse_offset(split);
+        self.explicit_background = true;
 
         Ok(())
     }
@@ -248,7 +471,7 @@ impl StoryboardState {
         let start_time = f64::parse(start_time)?;
         let end_time = start_time.max(f64::parse(end_time)?);
 
-        self.breaks.push(BreakPeriod {
+        self.storyboard.breaks.push(BreakPeriod {
             start_time,
             end_time,
         });
@@ -256,12 +479,36 @@ impl StoryboardState {
         Ok(())
     }
 
+    fn parse_background_color(
+        &mut self,
+        split: &mut Split<'_, char>,
+    ) -> Result<(), ParseStoryboardError> {
+        let Some((((time, r), g), b)) = split
+            .next()
+            .zip(split.next())
+            .zip(split.next())
+            .zip(split.next())
+        else {
+            return Err(ParseStoryboardError::InvalidLine);
+        };
+
+        let time = f64::parse(time)?;
+        let channel = |s: &str| i32::parse(s).map(|n| n.clamp(0, 255) as u8);
+        let color = Color::new(channel(r)?, channel(g)?, channel(b)?, 255);
+
+        self.storyboard
+            .background_colors
+            .push(BackgroundColor::new(time, color));
+
+        Ok(())
+    }
+
     fn parse_trigger(&mut self, split: &mut Split<'_, char>) -> Result<(), ParseStoryboardError> {
         let Some(name) = split.next() else {
             return Err(ParseStoryboardError::InvalidLine);
         };
 
-        let Some(sprite) = self.sprite.inner_mut() else {
+        let Some((sprite_ref, sprite)) = self.current_sprite_mut() else {
             return Ok(());
         };
 
@@ -283,8 +530,12 @@ impl StoryboardState {
             0
         };
 
-        let trigger = sprite.add_trigger(name.to_owned(), start_time, end_time, group_num);
-        self.timeline_group = Some(Rc::clone(&trigger.group));
+        sprite.add_trigger(name.to_owned(), start_time, end_time, group_num);
+
+        self.timeline_group = Some(GroupRef {
+            sprite: sprite_ref,
+            kind: GroupKind::Trigger(sprite.triggers.len() - 1),
+        });
 
         Ok(())
     }
@@ -294,15 +545,19 @@ impl StoryboardState {
             return Err(ParseStoryboardError::InvalidLine);
         };
 
-        let Some(sprite) = self.sprite.inner_mut() else {
+        let Some((sprite_ref, sprite)) = self.current_sprite_mut() else {
             return Ok(());
         };
 
         let start_time = f64::parse(start_time)?;
         let repeat_count = i32::parse(repeat_count)?;
 
-        let new_loop = sprite.add_loop(start_time, cmp::max(0, repeat_count - 1) as u32);
-        self.timeline_group = Some(Rc::clone(&new_loop.group));
+        sprite.add_loop(start_time, cmp::max(0, repeat_count - 1) as u32);
+
+        self.timeline_group = Some(GroupRef {
+            sprite: sprite_ref,
+            kind: GroupKind::Loop(sprite.loops.len() - 1),
+        });
 
         Ok(())
     }
@@ -318,7 +573,7 @@ impl StoryboardState {
             return Err(ParseStoryboardError::InvalidLine);
         };
 
-        let Some(ref group) = self.timeline_group else {
+        let Some(group) = self.group_mut() else {
             return Ok(());
         };
 
@@ -331,7 +586,6 @@ impl StoryboardState {
         };
 
         group
-            .borrow_mut()
             .alpha
             .add(easing, start_time, end_time, start_value, end_value);
 
@@ -349,7 +603,7 @@ impl StoryboardState {
             return Err(ParseStoryboardError::InvalidLine);
         };
 
-        let Some(ref group) = self.timeline_group else {
+        let Some(group) = self.group_mut() else {
             return Ok(());
         };
 
@@ -362,7 +616,6 @@ impl StoryboardState {
         };
 
         group
-            .borrow_mut()
             .scale
             .add(easing, start_time, end_time, start_value, end_value);
 
@@ -380,7 +633,7 @@ impl StoryboardState {
             return Err(ParseStoryboardError::InvalidLine);
         };
 
-        let Some(ref group) = self.timeline_group else {
+        let Some(group) = self.group_mut() else {
             return Ok(());
         };
 
@@ -399,7 +652,7 @@ impl StoryboardState {
             start_y
         };
 
-        group.borrow_mut().vector_scale.add(
+        group.vector_scale.add(
             easing,
             start_time,
             end_time,
@@ -421,7 +674,7 @@ impl StoryboardState {
             return Err(ParseStoryboardError::InvalidLine);
         };
 
-        let Some(ref group) = self.timeline_group else {
+        let Some(group) = self.group_mut() else {
             return Ok(());
         };
 
@@ -433,7 +686,7 @@ impl StoryboardState {
             start_value
         };
 
-        group.borrow_mut().rotation.add(
+        group.rotation.add(
             easing,
             start_time,
             end_time,
@@ -455,7 +708,7 @@ impl StoryboardState {
             return Err(ParseStoryboardError::InvalidLine);
         };
 
-        let Some(ref group) = self.timeline_group else {
+        let Some(group) = self.group_mut() else {
             return Ok(());
         };
 
@@ -474,14 +727,8 @@ impl StoryboardState {
             start_y
         };
 
-        group
-            .borrow_mut()
-            .x
-            .add(easing, start_time, end_time, start_x, end_x);
-        group
-            .borrow_mut()
-            .y
-            .add(easing, start_time, end_time, start_y, end_y);
+        group.x.add(easing, start_time, end_time, start_x, end_x);
+        group.y.add(easing, start_time, end_time, start_y, end_y);
 
         Ok(())
     }
@@ -497,7 +744,7 @@ impl StoryboardState {
             return Err(ParseStoryboardError::InvalidLine);
         };
 
-        let Some(ref group) = self.timeline_group else {
+        let Some(group) = self.group_mut() else {
             return Ok(());
         };
 
@@ -510,7 +757,6 @@ impl StoryboardState {
         };
 
         group
-            .borrow_mut()
             .x
             .add(easing, start_time, end_time, start_value, end_value);
 
@@ -528,7 +774,7 @@ impl StoryboardState {
             return Err(ParseStoryboardError::InvalidLine);
         };
 
-        let Some(ref group) = self.timeline_group else {
+        let Some(group) = self.group_mut() else {
             return Ok(());
         };
 
@@ -541,7 +787,6 @@ impl StoryboardState {
         };
 
         group
-            .borrow_mut()
             .y
             .add(easing, start_time, end_time, start_value, end_value);
 
@@ -561,7 +806,7 @@ impl StoryboardState {
             return Err(ParseStoryboardError::InvalidLine);
         };
 
-        let Some(ref group) = self.timeline_group else {
+        let Some(group) = self.group_mut() else {
             return Ok(());
         };
 
@@ -587,7 +832,7 @@ impl StoryboardState {
             start_blue
         };
 
-        group.borrow_mut().color.add(
+        group.color.add(
             easing,
             start_time,
             end_time,
@@ -599,89 +844,66 @@ impl StoryboardState {
     }
 
     fn add_blending(&mut self, easing: Easing, start_time: f64, end_time: f64) {
-        if let Some(ref group) = self.timeline_group {
-            group.borrow_mut().blending_parameters.add(
-                easing,
-                start_time,
-                end_time,
-                BlendingParameters::ADDITIVE,
-                if (end_time - start_time).abs() < f64::EPSILON {
-                    BlendingParameters::ADDITIVE
-                } else {
-                    BlendingParameters::INHERIT
-                },
-            );
+        if let Some(group) = self.group_mut() {
+            group.add_additive(easing, start_time, end_time);
         }
     }
 
     fn add_flip_h(&mut self, easing: Easing, start_time: f64, end_time: f64) {
-        if let Some(ref group) = self.timeline_group {
-            group.borrow_mut().flip_h.add(
-                easing,
-                start_time,
-                end_time,
-                true,
-                (end_time - start_time).abs() < f64::EPSILON,
-            );
+        if let Some(group) = self.group_mut() {
+            group.add_flip_h(easing, start_time, end_time);
         }
     }
 
     fn add_flip_v(&mut self, easing: Easing, start_time: f64, end_time: f64) {
-        if let Some(ref group) = self.timeline_group {
-            group.borrow_mut().flip_v.add(
-                easing,
-                start_time,
-                end_time,
-                true,
-                (end_time - start_time).abs() < f64::EPSILON,
-            );
+        if let Some(group) = self.group_mut() {
+            group.add_flip_v(easing, start_time, end_time);
         }
     }
 }
 
 impl DecodeState for StoryboardState {
     fn create(format_version: i32) -> Self {
-        let storyboard = Storyboard {
-            format_version,
-            ..Default::default()
-        };
-
         Self {
-            format_version: storyboard.format_version,
-            use_skin_sprites: storyboard.use_skin_sprites,
-            background_file: storyboard.background_file,
-            breaks: storyboard.breaks,
-            storyboard: StoryboardInternal::default(),
-            sprite: PendingSprite::default(),
+            storyboard: Storyboard {
+                format_version,
+                ..Default::default()
+            },
+            layer_ids: Vec::new(),
+            sprite: None,
             timeline_group: None,
+            explicit_background: false,
             variables: HashMap::default(),
         }
     }
 }
 
 impl From<StoryboardState> for Storyboard {
-    fn from(mut state: StoryboardState) -> Self {
-        state.sprite.add(&mut state.storyboard);
-
-        // drop the Rc so that the Rcs in the layers are unique
-        state.timeline_group = None;
-
-        let min_layer_depth = state.storyboard.min_layer_depth;
-        let layers = state.storyboard.convert_layers();
-
-        Storyboard {
-            format_version: state.format_version,
-            use_skin_sprites: state.use_skin_sprites,
-            background_file: state.background_file,
-            breaks: state.breaks,
-            layers,
-            min_layer_depth,
-        }
+    fn from(state: StoryboardState) -> Self {
+        state.storyboard
     }
 }
 
 const MAX_COORDINATE_VALUE: i32 = 131_072;
 
+/// Parse the optional x and y offset of a background or video event.
+///
+/// Missing or invalid values default to zero so that they don't invalidate
+/// the whole event.
+fn parse_offset(split: &mut Split<'_, char>) -> Pos {
+    let mut next = || {
+        split
+            .next()
+            .and_then(|n| f32::parse_with_limits(n, MAX_COORDINATE_VALUE as f32).ok())
+            .unwrap_or(0.0)
+    };
+
+    let x = next();
+    let y = next();
+
+    Pos::new(x, y)
+}
+
 impl DecodeBeatmap for Storyboard {
     type Error = ParseStoryboardError;
     type State = StoryboardState;
@@ -694,7 +916,7 @@ impl DecodeBeatmap for Storyboard {
         let mut split = line.trim_comment().split(':').map(str::trim);
 
         if split.next() == Some("UseSkinSprites") {
-            state.use_skin_sprites = split.next() == Some("1");
+            state.storyboard.use_skin_sprites = split.next() == Some("1");
         }
 
         Ok(())
@@ -715,88 +937,7 @@ impl DecodeBeatmap for Storyboard {
     fn parse_events(state: &mut Self::State, line: &str) -> Result<(), Self::Error> {
         let line = state.decode_variables(line.trim_comment());
 
-        let depth = line
-            .chars()
-            .take_while(|ch| matches!(ch, ' ' | '_'))
-            .count();
-
-        let line = &line[depth..];
-        let mut split = line.split(',');
-
-        if depth == 0 {
-            let Some(event_type) = split.next() else {
-                return Err(ParseStoryboardError::InvalidLine);
-            };
-
-            state.sprite.add(&mut state.storyboard);
-
-            return match event_type.parse()? {
-                EventType::Video => state.parse_video(&mut split),
-                EventType::Sprite => state.parse_sprite(&mut split),
-                EventType::Animation => state.parse_animation(&mut split),
-                EventType::Sample => state.parse_sample(&mut split),
-                EventType::Background => state.parse_background(&mut split),
-                EventType::Break => state.parse_break(&mut split),
-                EventType::Color => Ok(()),
-            };
-        }
-
-        if depth < 2 {
-            if let Some(sprite) = state.sprite.inner() {
-                state.timeline_group = Some(Rc::clone(&sprite.timeline_group));
-            }
-        }
-
-        let Some(command_type) = split.next() else {
-            return Err(ParseStoryboardError::InvalidLine);
-        };
-
-        match command_type {
-            "T" => return state.parse_trigger(&mut split),
-            "L" => return state.parse_loop(&mut split),
-            _ => {}
-        }
-
-        let Some(((easing, start_time), end_time)) =
-            split.next().zip(split.next()).zip(split.next())
-        else {
-            return Err(ParseStoryboardError::InvalidLine);
-        };
-
-        let easing = Easing::from(i32::parse(easing)?);
-        let start_time = f64::parse(start_time)?;
-
-        let end_time = if end_time.is_empty() {
-            start_time
-        } else {
-            f64::parse(end_time)?
-        };
-
-        match command_type {
-            "F" => state.parse_alpha(&mut split, easing, start_time, end_time),
-            "S" => state.parse_scale(&mut split, easing, start_time, end_time),
-            "V" => state.parse_vector_scale(&mut split, easing, start_time, end_time),
-            "R" => state.parse_rotation(&mut split, easing, start_time, end_time),
-            "M" => state.parse_pos(&mut split, easing, start_time, end_time),
-            "MX" => state.parse_x(&mut split, easing, start_time, end_time),
-            "MY" => state.parse_y(&mut split, easing, start_time, end_time),
-            "C" => state.parse_color(&mut split, easing, start_time, end_time),
-            "P" => {
-                let Some(kind) = split.next() else {
-                    return Err(ParseStoryboardError::InvalidLine);
-                };
-
-                match kind {
-                    "A" => state.add_blending(easing, start_time, end_time),
-                    "H" => state.add_flip_h(easing, start_time, end_time),
-                    "V" => state.add_flip_v(easing, start_time, end_time),
-                    _ => {}
-                }
-
-                Ok(())
-            }
-            _ => Err(ParseStoryboardError::UnknownCommandType),
-        }
+        state.parse_event(&line)
     }
 
     fn parse_timing_points(_: &mut Self::State, _: &str) -> Result<(), Self::Error> {
@@ -837,84 +978,3 @@ impl DecodeBeatmap for Storyboard {
         Ok(())
     }
 }
-
-// Prevent access of fields by abstracting through a module
-mod pending {
-    use crate::{
-        element::{AnimationInternal, ElementInternal, ElementKindInternal, SpriteInternal},
-        layer::StoryLayer,
-        storyboard::StoryboardInternal,
-    };
-
-    #[derive(Default)]
-    pub struct PendingSprite(Option<PendingSpriteInner>);
-
-    impl PendingSprite {
-        pub fn set_sprite(&mut self, path: String, layer: &StoryLayer<'_>, sprite: SpriteInternal) {
-            self.0 = Some(PendingSpriteInner {
-                path,
-                layer: layer.as_str().into(),
-                kind: PendingSpriteKind::Sprite(sprite),
-            });
-        }
-
-        pub fn set_animation(
-            &mut self,
-            path: String,
-            layer: &StoryLayer<'_>,
-            animation: AnimationInternal,
-        ) {
-            self.0 = Some(PendingSpriteInner {
-                path,
-                layer: layer.as_str().into(),
-                kind: PendingSpriteKind::Animation(animation),
-            });
-        }
-
-        pub fn add(&mut self, storyboard: &mut StoryboardInternal) {
-            let Some(inner) = self.0.take() else { return };
-
-            match inner.kind {
-                PendingSpriteKind::Animation(animation) => storyboard
-                    .get_layer(inner.layer.as_ref())
-                    .elements
-                    .push(ElementInternal {
-                        path: inner.path,
-                        kind: ElementKindInternal::Animation(animation),
-                    }),
-                PendingSpriteKind::Sprite(sprite) => storyboard
-                    .get_layer(inner.layer.as_ref())
-                    .elements
-                    .push(ElementInternal {
-                        path: inner.path,
-                        kind: ElementKindInternal::Sprite(sprite),
-                    }),
-            }
-        }
-
-        pub fn inner(&self) -> Option<&SpriteInternal> {
-            self.0.as_ref().map(|inner| match inner.kind {
-                PendingSpriteKind::Animation(ref animation) => &animation.sprite,
-                PendingSpriteKind::Sprite(ref sprite) => sprite,
-            })
-        }
-
-        pub fn inner_mut(&mut self) -> Option<&mut SpriteInternal> {
-            self.0.as_mut().map(|inner| match inner.kind {
-                PendingSpriteKind::Animation(ref mut animation) => &mut animation.sprite,
-                PendingSpriteKind::Sprite(ref mut sprite) => sprite,
-            })
-        }
-    }
-
-    struct PendingSpriteInner {
-        path: String,
-        layer: Box<str>,
-        kind: PendingSpriteKind,
-    }
-
-    enum PendingSpriteKind {
-        Animation(AnimationInternal),
-        Sprite(SpriteInternal),
-    }
-}
@@ -0,0 +1,13 @@
+pub use self::{
+    structural::{
+        BackgroundChange, CommandChange, CommandDiff, CommandSnapshot, CommandValue, ElementChange,
+        ElementDiff, GroupId, LayerDiff, LoopInfo, StoryboardDiff, TimelineId, TriggerInfo,
+    },
+    visual::{
+        ElementMismatch, MismatchRange, StateProperty, VisualDiff, VisualDiffOptions,
+        VisualTolerance,
+    },
+};
+
+mod structural;
+mod visual;
@@ -0,0 +1,949 @@
+// Differences are exact, consistent with the `PartialEq` implementations
+#![allow(clippy::float_cmp)]
+
+use std::{
+    collections::{BTreeSet, HashMap, VecDeque},
+    fmt::{Display, Formatter, Result as FmtResult},
+    hash::Hash,
+    mem,
+};
+
+use rosu_map::{section::colors::Color, util::Pos};
+
+use crate::{
+    command::{CommandLoop, CommandTimeline, CommandTimelineGroup, CommandTrigger, TypedCommand},
+    element::{AnimationLoopType, Element, ElementKind, Sprite},
+    visual::{Anchor, BlendingParameters, Easing, Origins},
+    BackgroundColor, Layer, LayerId, Storyboard,
+};
+
+impl Storyboard {
+    /// Compare the storyboard with a newer revision of itself.
+    ///
+    /// Elements of a layer are matched by kind and path, preferring elements
+    /// with the same position and timing. Elements that could not be matched
+    /// are considered added or removed.
+    ///
+    /// Besides the layers, only the background offset and background colors
+    /// are compared, i.e. changes of the background file, breaks, or general
+    /// settings are not reported.
+    pub fn diff(&self, new: &Self) -> StoryboardDiff {
+        let names: BTreeSet<_> = self.layers.keys().chain(new.layers.keys()).collect();
+
+        let layers = names
+            .into_iter()
+            .filter_map(|name| {
+                let old = self.layers.get(name).map_or(&[][..], elements);
+                let new = new.layers.get(name).map_or(&[][..], elements);
+
+                let elements = diff_elements(old, new);
+
+                (!elements.is_empty()).then(|| LayerDiff {
+                    name: name.clone(),
+                    elements,
+                })
+            })
+            .collect();
+
+        let mut background = Vec::new();
+
+        if self.background_offset != new.background_offset {
+            background.push(BackgroundChange::Offset {
+                old: self.background_offset,
+                new: new.background_offset,
+            });
+        }
+
+        if self.background_colors != new.background_colors {
+            background.push(BackgroundChange::Colors {
+                old: self.background_colors.clone(),
+                new: new.background_colors.clone(),
+            });
+        }
+
+        StoryboardDiff { background, layers }
+    }
+}
+
+fn elements(layer: &Layer) -> &[Element] {
+    &layer.elements
+}
+
+/// All differences between two [`Storyboard`]s as returned by
+/// [`Storyboard::diff`].
+///
+/// The [`Display`] implementation produces a human-readable summary.
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct StoryboardDiff {
+    pub background: Vec<BackgroundChange>,
+    /// Layers that contain differences, sorted by name.
+    pub layers: Vec<LayerDiff>,
+}
+
+impl StoryboardDiff {
+    /// Whether both storyboards are structurally equal.
+    pub const fn is_empty(&self) -> bool {
+        self.background.is_empty() && self.layers.is_empty()
+    }
+}
+
+/// A change of the background of a [`Storyboard`].
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum BackgroundChange {
+    Offset {
+        #[cfg_attr(feature = "serde", serde(with = "crate::serde_util::pos"))]
+        old: Pos,
+        #[cfg_attr(feature = "serde", serde(with = "crate::serde_util::pos"))]
+        new: Pos,
+    },
+    /// The background color events differ in any way.
+    Colors {
+        old: Vec<BackgroundColor>,
+        new: Vec<BackgroundColor>,
+    },
+}
+
+/// Differences within a [`Layer`].
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct LayerDiff {
+    pub name: LayerId,
+    pub elements: Vec<ElementDiff>,
+}
+
+/// Difference of a single [`Element`].
+///
+/// Indices refer to the element's position within its layer.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ElementDiff {
+    /// The element only exists in the new storyboard.
+    Added { index: usize, path: String },
+    /// The element only exists in the old storyboard.
+    Removed { index: usize, path: String },
+    /// The element exists in both storyboards but differs.
+    Changed {
+        old_index: usize,
+        new_index: usize,
+        path: String,
+        changes: Vec<ElementChange>,
+    },
+}
+
+/// A single change of an [`Element`] that exists in both storyboards.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ElementChange {
+    Origin {
+        old: Anchor,
+        new: Anchor,
+    },
+    InitialPos {
+        #[cfg_attr(feature = "serde", serde(with = "crate::serde_util::pos"))]
+        old: Pos,
+        #[cfg_attr(feature = "serde", serde(with = "crate::serde_util::pos"))]
+        new: Pos,
+    },
+    FrameCount {
+        old: i32,
+        new: i32,
+    },
+    FrameDelay {
+        old: f64,
+        new: f64,
+    },
+    LoopKind {
+        old: AnimationLoopType,
+        new: AnimationLoopType,
+    },
+    /// Offset of a video.
+    Offset {
+        #[cfg_attr(feature = "serde", serde(with = "crate::serde_util::pos"))]
+        old: Pos,
+        #[cfg_attr(feature = "serde", serde(with = "crate::serde_util::pos"))]
+        new: Pos,
+    },
+    /// Start time of a sample or video.
+    StartTime {
+        old: f64,
+        new: f64,
+    },
+    Volume {
+        old: f32,
+        new: f32,
+    },
+    LoopAdded {
+        index: usize,
+    },
+    LoopRemoved {
+        index: usize,
+    },
+    Loop {
+        index: usize,
+        old: LoopInfo,
+        new: LoopInfo,
+    },
+    TriggerAdded {
+        index: usize,
+    },
+    TriggerRemoved {
+        index: usize,
+    },
+    Trigger {
+        index: usize,
+        old: TriggerInfo,
+        new: TriggerInfo,
+    },
+    Command(CommandDiff),
+}
+
+/// The settings of a [`CommandLoop`].
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct LoopInfo {
+    pub start_time: f64,
+    pub total_iterations: u32,
+}
+
+impl From<&CommandLoop> for LoopInfo {
+    fn from(l: &CommandLoop) -> Self {
+        Self {
+            start_time: l.loop_start_time,
+            total_iterations: l.total_iterations,
+        }
+    }
+}
+
+/// The settings of a [`CommandTrigger`].
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TriggerInfo {
+    pub name: String,
+    pub start_time: f64,
+    pub end_time: f64,
+    pub group_num: i32,
+}
+
+impl From<&CommandTrigger> for TriggerInfo {
+    fn from(trigger: &CommandTrigger) -> Self {
+        Self {
+            name: trigger.name.clone(),
+            start_time: trigger.start_time,
+            end_time: trigger.end_time,
+            group_num: trigger.group_num,
+        }
+    }
+}
+
+/// A changed command within one of the timelines of a sprite.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CommandDiff {
+    pub group: GroupId,
+    pub timeline: TimelineId,
+    pub change: CommandChange,
+}
+
+/// Identifies a [`CommandTimelineGroup`] of a sprite.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum GroupId {
+    /// Commands outside of loops and triggers.
+    Main,
+    /// Commands of the loop at the given index.
+    Loop(usize),
+    /// Commands of the trigger at the given index.
+    Trigger(usize),
+}
+
+/// Identifies a [`CommandTimeline`] of a [`CommandTimelineGroup`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum TimelineId {
+    X,
+    Y,
+    Scale,
+    VectorScale,
+    Rotation,
+    Color,
+    Alpha,
+    BlendingParameters,
+    FlipH,
+    FlipV,
+}
+
+impl TimelineId {
+    /// The command as it appears in `.osb` files.
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            TimelineId::X => "MX",
+            TimelineId::Y => "MY",
+            TimelineId::Scale => "S",
+            TimelineId::VectorScale => "V",
+            TimelineId::Rotation => "R",
+            TimelineId::Color => "C",
+            TimelineId::Alpha => "F",
+            TimelineId::BlendingParameters => "P",
+            TimelineId::FlipH => "P,H",
+            TimelineId::FlipV => "P,V",
+        }
+    }
+}
+
+/// How a command changed.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum CommandChange {
+    Inserted(CommandSnapshot),
+    Deleted(CommandSnapshot),
+    Changed {
+        old: CommandSnapshot,
+        new: CommandSnapshot,
+    },
+}
+
+/// A command independent of its value type.
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CommandSnapshot {
+    pub easing: Easing,
+    pub start_time: f64,
+    pub end_time: f64,
+    pub start_value: CommandValue,
+    pub end_value: CommandValue,
+}
+
+/// The value of a command.
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum CommandValue {
+    Float(f32),
+    Pos(#[cfg_attr(feature = "serde", serde(with = "crate::serde_util::pos"))] Pos),
+    Color(#[cfg_attr(feature = "serde", serde(with = "crate::serde_util::color"))] Color),
+    Blending(BlendingParameters),
+    Bool(bool),
+}
+
+macro_rules! command_value {
+    ( $( $ty:ty => $variant:ident, )* ) => {
+        $(
+            impl From<$ty> for CommandValue {
+                fn from(value: $ty) -> Self {
+                    Self::$variant(value)
+                }
+            }
+        )*
+    };
+}
+
+command_value! {
+    f32 => Float,
+    Pos => Pos,
+    Color => Color,
+    BlendingParameters => Blending,
+    bool => Bool,
+}
+
+fn diff_elements(old: &[Element], new: &[Element]) -> Vec<ElementDiff> {
+    let mut identical: HashMap<_, VecDeque<usize>> = HashMap::new();
+    let mut similar: HashMap<_, VecDeque<usize>> = HashMap::new();
+
+    for (j, elem) in new.iter().enumerate() {
+        let props = props(elem);
+        identical
+            .entry((key(elem), props))
+            .or_default()
+            .push_back(j);
+
+        for &mask in SIMILARITY_MASKS.into_iter().flatten() {
+            if let Some(props) = masked(props, mask) {
+                similar.entry((key(elem), props)).or_default().push_back(j);
+            }
+        }
+    }
+
+    let mut matched_new = vec![false; new.len()];
+    let mut pairs: Vec<Option<usize>> = vec![None; old.len()];
+
+    // Identical elements first so they don't get paired with similar ones
+    for (i, elem) in old.iter().enumerate() {
+        let candidate = first_candidate(
+            &mut identical,
+            &(key(elem), props(elem)),
+            &matched_new,
+            |j| new[j] == *elem,
+        );
+
+        if let Some(j) = candidate {
+            matched_new[j] = true;
+            pairs[i] = Some(j);
+        }
+    }
+
+    for (i, elem) in old.iter().enumerate() {
+        if pairs[i].is_some() {
+            continue;
+        }
+
+        let props = props(elem);
+
+        // Try the most similar elements first and prefer the lowest index
+        // among equally similar ones
+        let best = SIMILARITY_MASKS.into_iter().find_map(|masks| {
+            masks
+                .iter()
+                .filter_map(|&mask| masked(props, mask))
+                .filter_map(|props| {
+                    first_candidate(&mut similar, &(key(elem), props), &matched_new, |_| true)
+                })
+                .min()
+        });
+
+        if let Some(j) = best {
+            matched_new[j] = true;
+            pairs[i] = Some(j);
+        }
+    }
+
+    let mut diffs = Vec::new();
+
+    for (i, pair) in pairs.into_iter().enumerate() {
+        match pair {
+            Some(j) => {
+                let changes = diff_element(&old[i], &new[j]);
+
+                if !changes.is_empty() {
+                    diffs.push(ElementDiff::Changed {
+                        old_index: i,
+                        new_index: j,
+                        path: new[j].path.clone(),
+                        changes,
+                    });
+                }
+            }
+            None => diffs.push(ElementDiff::Removed {
+                index: i,
+                path: old[i].path.clone(),
+            }),
+        }
+    }
+
+    let added = matched_new
+        .into_iter()
+        .enumerate()
+        .filter(|(_, matched)| !matched)
+        .map(|(j, _)| ElementDiff::Added {
+            index: j,
+            path: new[j].path.clone(),
+        });
+
+    diffs.extend(added);
+
+    diffs
+}
+
+/// Elements can only be matched if their kind and path are the same.
+fn key(elem: &Element) -> (mem::Discriminant<ElementKind>, &str) {
+    (mem::discriminant(&elem.kind), &elem.path)
+}
+
+/// Position, start time, and end time of an element.
+///
+/// Values that never compare equal, i.e. NaN or a missing position, are
+/// `None`.
+type Props = [Option<(u64, u64)>; 3];
+
+/// Combinations of [`Props`] that must be equal, grouped by how similar
+/// they make two elements.
+const SIMILARITY_MASKS: [&[u8]; 3] = [&[0b111], &[0b011, 0b101, 0b110], &[0b001, 0b010, 0b100]];
+
+fn props(elem: &Element) -> Props {
+    let pos = match elem.kind {
+        ElementKind::Animation(ref elem) => Some(elem.sprite.initial_pos),
+        ElementKind::Sprite(ref elem) => Some(elem.initial_pos),
+        _ => None,
+    };
+
+    [
+        pos.and_then(|pos| Some((bits(f64::from(pos.x))?, bits(f64::from(pos.y))?))),
+        bits(elem.start_time()).map(|bits| (bits, 0)),
+        bits(elem.end_time()).map(|bits| (bits, 0)),
+    ]
+}
+
+/// Keep only the masked [`Props`] or return `None` if any of them is missing.
+fn masked(mut props: Props, mask: u8) -> Option<Props> {
+    for (i, prop) in props.iter_mut().enumerate() {
+        if mask & (1 << i) == 0 {
+            *prop = Some((0, 0));
+        } else if prop.is_none() {
+            return None;
+        }
+    }
+
+    Some(props)
+}
+
+/// Bits of the float such that equal floats have equal bits.
+fn bits(value: f64) -> Option<u64> {
+    // Adding zero turns `-0.0` into `0.0`
+    (!value.is_nan()).then(|| (value + 0.0).to_bits())
+}
+
+/// Return the lowest unmatched index of the bucket that satisfies `f`.
+///
+/// Matched indices at the front of the bucket are dropped along the way so
+/// that consuming a bucket in order stays linear.
+fn first_candidate<K: Hash + Eq>(
+    buckets: &mut HashMap<K, VecDeque<usize>>,
+    key: &K,
+    matched: &[bool],
+    f: impl Fn(usize) -> bool,
+) -> Option<usize> {
+    let bucket = buckets.get_mut(key)?;
+
+    while bucket.front().is_some_and(|&j| matched[j]) {
+        bucket.pop_front();
+    }
+
+    bucket.iter().copied().find(|&j| !matched[j] && f(j))
+}
+
+fn diff_element(old: &Element, new: &Element) -> Vec<ElementChange> {
+    let mut changes = Vec::new();
+
+    match (&old.kind, &new.kind) {
+        (ElementKind::Animation(old), ElementKind::Animation(new)) => {
+            diff_sprite(&old.sprite, &new.sprite, &mut changes);
+
+            if old.frame_count != new.frame_count {
+                changes.push(ElementChange::FrameCount {
+                    old: old.frame_count,
+                    new: new.frame_count,
+                });
+            }
+
+            if old.frame_delay != new.frame_delay {
+                changes.push(ElementChange::FrameDelay {
+                    old: old.frame_delay,
+                    new: new.frame_delay,
+                });
+            }
+
+            if old.loop_kind != new.loop_kind {
+                changes.push(ElementChange::LoopKind {
+                    old: old.loop_kind,
+                    new: new.loop_kind,
+                });
+            }
+        }
+        (ElementKind::Sprite(old), ElementKind::Sprite(new)) => {
+            diff_sprite(old, new, &mut changes);
+        }
+        (ElementKind::Sample(old), ElementKind::Sample(new)) => {
+            if old.start_time != new.start_time {
+                changes.push(ElementChange::StartTime {
+                    old: old.start_time,
+                    new: new.start_time,
+                });
+            }
+
+            if old.volume != new.volume {
+                changes.push(ElementChange::Volume {
+                    old: old.volume,
+                    new: new.volume,
+                });
+            }
+        }
+        (ElementKind::Video(old), ElementKind::Video(new)) => {
+            if old.start_time != new.start_time {
+                changes.push(ElementChange::StartTime {
+                    old: old.start_time,
+                    new: new.start_time,
+                });
+            }
+
+            if old.offset != new.offset {
+                changes.push(ElementChange::Offset {
+                    old: old.offset,
+                    new: new.offset,
+                });
+            }
+        }
+        _ => unreachable!("only elements of the same kind are matched"),
+    }
+
+    changes
+}
+
+fn diff_sprite(old: &Sprite, new: &Sprite, changes: &mut Vec<ElementChange>) {
+    if old.origin != new.origin {
+        changes.push(ElementChange::Origin {
+            old: old.origin,
+            new: new.origin,
+        });
+    }
+
+    if old.initial_pos != new.initial_pos {
+        changes.push(ElementChange::InitialPos {
+            old: old.initial_pos,
+            new: new.initial_pos,
+        });
+    }
+
+    diff_group(
+        GroupId::Main,
+        &old.timeline_group,
+        &new.timeline_group,
+        changes,
+    );
+
+    for (index, pair) in zip_longest(&old.loops, &new.loops).enumerate() {
+        match pair {
+            (Some(old), Some(new)) => {
+                let (old_info, new_info) = (LoopInfo::from(old), LoopInfo::from(new));
+
+                if old_info != new_info {
+                    changes.push(ElementChange::Loop {
+                        index,
+                        old: old_info,
+                        new: new_info,
+                    });
+                }
+
+                diff_group(GroupId::Loop(index), &old.group, &new.group, changes);
+            }
+            (Some(_), None) => changes.push(ElementChange::LoopRemoved { index }),
+            (None, Some(_)) => changes.push(ElementChange::LoopAdded { index }),
+            (None, None) => unreachable!(),
+        }
+    }
+
+    for (index, pair) in zip_longest(&old.triggers, &new.triggers).enumerate() {
+        match pair {
+            (Some(old), Some(new)) => {
+                let (old_info, new_info) = (TriggerInfo::from(old), TriggerInfo::from(new));
+
+                if old_info != new_info {
+                    changes.push(ElementChange::Trigger {
+                        index,
+                        old: old_info,
+                        new: new_info,
+                    });
+                }
+
+                diff_group(GroupId::Trigger(index), &old.group, &new.group, changes);
+            }
+            (Some(_), None) => changes.push(ElementChange::TriggerRemoved { index }),
+            (None, Some(_)) => changes.push(ElementChange::TriggerAdded { index }),
+            (None, None) => unreachable!(),
+        }
+    }
+}
+
+fn zip_longest<'a, T>(
+    old: &'a [T],
+    new: &'a [T],
+) -> impl Iterator<Item = (Option<&'a T>, Option<&'a T>)> {
+    (0..old.len().max(new.len())).map(|i| (old.get(i), new.get(i)))
+}
+
+fn diff_group(
+    group: GroupId,
+    old: &CommandTimelineGroup,
+    new: &CommandTimelineGroup,
+    changes: &mut Vec<ElementChange>,
+) {
+    let mut diff = |timeline, commands: Vec<CommandChange>| {
+        changes.extend(commands.into_iter().map(|change| {
+            ElementChange::Command(CommandDiff {
+                group,
+                timeline,
+                change,
+            })
+        }));
+    };
+
+    diff(TimelineId::X, diff_timeline(&old.x, &new.x));
+    diff(TimelineId::Y, diff_timeline(&old.y, &new.y));
+    diff(TimelineId::Scale, diff_timeline(&old.scale, &new.scale));
+    diff(
+        TimelineId::VectorScale,
+        diff_timeline(&old.vector_scale, &new.vector_scale),
+    );
+    diff(
+        TimelineId::Rotation,
+        diff_timeline(&old.rotation, &new.rotation),
+    );
+    diff(TimelineId::Color, diff_timeline(&old.color, &new.color));
+    diff(TimelineId::Alpha, diff_timeline(&old.alpha, &new.alpha));
+    diff(
+        TimelineId::BlendingParameters,
+        diff_timeline(&old.blending_parameters, &new.blending_parameters),
+    );
+    diff(TimelineId::FlipH, diff_timeline(&old.flip_h, &new.flip_h));
+    diff(TimelineId::FlipV, diff_timeline(&old.flip_v, &new.flip_v));
+}
+
+/// Commands are paired up if they are identical, then if they share both
+/// start and end time, and lastly if they share their start time. Remaining
+/// commands are considered inserted or deleted.
+fn diff_timeline<T>(old: &CommandTimeline<T>, new: &CommandTimeline<T>) -> Vec<CommandChange>
+where
+    T: Copy + PartialEq + Into<CommandValue>,
+{
+    let mut by_times: HashMap<_, VecDeque<usize>> = HashMap::new();
+    let mut by_start: HashMap<_, VecDeque<usize>> = HashMap::new();
+
+    for (j, command) in new.commands.iter().enumerate() {
+        if let Some(start) = bits(command.start_time) {
+            by_start.entry(start).or_default().push_back(j);
+
+            if let Some(end) = bits(command.end_time) {
+                by_times.entry((start, end)).or_default().push_back(j);
+            }
+        }
+    }
+
+    let mut matched_new = vec![false; new.commands.len()];
+    let mut pairs: Vec<Option<usize>> = vec![None; old.commands.len()];
+
+    for pass in 0..3 {
+        for (i, command) in old.commands.iter().enumerate() {
+            if pairs[i].is_some() {
+                continue;
+            }
+
+            let Some(start) = bits(command.start_time) else {
+                continue;
+            };
+
+            let end = bits(command.end_time);
+
+            let candidate = match (pass, end) {
+                (0, Some(end)) => {
+                    first_candidate(&mut by_times, &(start, end), &matched_new, |j| {
+                        new.commands[j] == *command
+                    })
+                }
+                (1, Some(end)) => {
+                    first_candidate(&mut by_times, &(start, end), &matched_new, |_| true)
+                }
+                (2, _) => first_candidate(&mut by_start, &start, &matched_new, |_| true),
+                _ => None,
+            };
+
+            if let Some(j) = candidate {
+                matched_new[j] = true;
+                pairs[i] = Some(j);
+            }
+        }
+    }
+
+    let mut changes = Vec::new();
+
+    for (old_command, pair) in old.commands.iter().zip(pairs) {
+        match pair {
+            Some(j) => {
+                let new_command = &new.commands[j];
+
+                if old_command != new_command {
+                    changes.push(CommandChange::Changed {
+                        old: snapshot(old_command),
+                        new: snapshot(new_command),
+                    });
+                }
+            }
+            None => changes.push(CommandChange::Deleted(snapshot(old_command))),
+        }
+    }
+
+    changes.extend(
+        new.commands
+            .iter()
+            .zip(matched_new)
+            .filter(|(_, matched)| !matched)
+            .map(|(command, _)| CommandChange::Inserted(snapshot(command))),
+    );
+
+    changes.sort_by(|a, b| a.start_time().total_cmp(&b.start_time()));
+
+    changes
+}
+
+fn snapshot<T: Copy + Into<CommandValue>>(command: &TypedCommand<T>) -> CommandSnapshot {
+    CommandSnapshot {
+        easing: command.easing,
+        start_time: command.start_time,
+        end_time: command.end_time,
+        start_value: command.start_value.into(),
+        end_value: command.end_value.into(),
+    }
+}
+
+impl CommandChange {
+    const fn start_time(&self) -> f64 {
+        match self {
+            CommandChange::Inserted(command)
+            | CommandChange::Deleted(command)
+            | CommandChange::Changed { new: command, .. } => command.start_time,
+        }
+    }
+}
+
+impl Display for StoryboardDiff {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        if !self.background.is_empty() {
+            writeln!(f, "Background:")?;
+
+            for change in self.background.iter() {
+                writeln!(f, "  ~ {change}")?;
+            }
+        }
+
+        for layer in self.layers.iter() {
+            writeln!(f, "{}:", layer.name)?;
+
+            for elem in layer.elements.iter() {
+                writeln!(f, "{elem}")?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl Display for BackgroundChange {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        match self {
+            BackgroundChange::Offset { old, new } => write!(f, "offset: {old} -> {new}"),
+            BackgroundChange::Colors { old, new } => {
+                write!(f, "colors: {} -> {} events", old.len(), new.len())
+            }
+        }
+    }
+}
+
+impl Display for ElementDiff {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        match self {
+            ElementDiff::Added { index, path } => write!(f, "  + [{index}] \"{path}\""),
+            ElementDiff::Removed { index, path } => write!(f, "  - [{index}] \"{path}\""),
+            ElementDiff::Changed {
+                old_index,
+                new_index,
+                path,
+                changes,
+            } => {
+                write!(f, "  ~ [{old_index} -> {new_index}] \"{path}\"")?;
+
+                for change in changes {
+                    write!(f, "\n      {change}")?;
+                }
+
+                Ok(())
+            }
+        }
+    }
+}
+
+impl Display for ElementChange {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        match self {
+            ElementChange::Origin { old, new } => write!(
+                f,
+                "origin: {} -> {}",
+                Origins::from(*old).as_str(),
+                Origins::from(*new).as_str()
+            ),
+            ElementChange::InitialPos { old, new } => write!(f, "position: {old} -> {new}"),
+            ElementChange::Offset { old, new } => write!(f, "offset: {old} -> {new}"),
+            ElementChange::FrameCount { old, new } => write!(f, "frame count: {old} -> {new}"),
+            ElementChange::FrameDelay { old, new } => write!(f, "frame delay: {old} -> {new}"),
+            ElementChange::LoopKind { old, new } => write!(f, "loop type: {old:?} -> {new:?}"),
+            ElementChange::StartTime { old, new } => write!(f, "start time: {old} -> {new}"),
+            ElementChange::Volume { old, new } => write!(f, "volume: {old} -> {new}"),
+            ElementChange::LoopAdded { index } => write!(f, "+ loop #{index}"),
+            ElementChange::LoopRemoved { index } => write!(f, "- loop #{index}"),
+            ElementChange::Loop { index, old, new } => write!(
+                f,
+                "loop #{index}: {} ({}x) -> {} ({}x)",
+                old.start_time, old.total_iterations, new.start_time, new.total_iterations
+            ),
+            ElementChange::TriggerAdded { index } => write!(f, "+ trigger #{index}"),
+            ElementChange::TriggerRemoved { index } => write!(f, "- trigger #{index}"),
+            ElementChange::Trigger { index, old, new } => {
+                write!(f, "trigger #{index}: {old} -> {new}")
+            }
+            ElementChange::Command(command) => command.fmt(f),
+        }
+    }
+}
+
+impl Display for TriggerInfo {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        write!(f, "{}", self.name)?;
+
+        if self.start_time > f64::MIN || self.end_time < f64::MAX {
+            write!(f, " {}..{}", self.start_time, self.end_time)?;
+        }
+
+        if self.group_num != 0 {
+            write!(f, " (group {})", self.group_num)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Display for CommandDiff {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        match self.group {
+            GroupId::Main => {}
+            GroupId::Loop(index) => write!(f, "loop #{index} ")?,
+            GroupId::Trigger(index) => write!(f, "trigger #{index} ")?,
+        }
+
+        write!(f, "{}: ", self.timeline.as_str())?;
+
+        match self.change {
+            CommandChange::Inserted(ref command) => write!(f, "+ {command}"),
+            CommandChange::Deleted(ref command) => write!(f, "- {command}"),
+            CommandChange::Changed { ref old, ref new } => write!(f, "{old} => {new}"),
+        }
+    }
+}
+
+impl Display for CommandSnapshot {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        write!(f, "{}..{} ", self.start_time, self.end_time)?;
+
+        if self.easing != Easing::None {
+            write!(f, "{:?} ", self.easing)?;
+        }
+
+        write!(f, "{} -> {}", self.start_value, self.end_value)
+    }
+}
+
+impl Display for CommandValue {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        match self {
+            CommandValue::Float(value) => write!(f, "{value}"),
+            CommandValue::Pos(pos) => write!(f, "{pos}"),
+            CommandValue::Color(Color([red, green, blue, alpha])) => {
+                write!(f, "rgba({red}, {green}, {blue}, {alpha})")
+            }
+            CommandValue::Blending(blending) if *blending == BlendingParameters::ADDITIVE => {
+                f.write_str("additive")
+            }
+            CommandValue::Blending(blending) if *blending == BlendingParameters::INHERIT => {
+                f.write_str("inherit")
+            }
+            CommandValue::Blending(blending) => write!(f, "{blending:?}"),
+            CommandValue::Bool(value) => write!(f, "{value}"),
+        }
+    }
+}
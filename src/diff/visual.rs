@@ -0,0 +1,454 @@
+use std::{
+    collections::{BTreeSet, HashMap},
+    fmt::{Display, Formatter, Result as FmtResult},
+};
+
+use crate::{
+    element::{Element, ElementKind, SpriteState},
+    LayerId, Storyboard,
+};
+
+/// Options for [`Storyboard::visual_diff`].
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct VisualDiffOptions {
+    /// Milliseconds between two samples.
+    pub interval: f64,
+    /// How much the evaluated states may differ before they are considered
+    /// different.
+    pub tolerance: VisualTolerance,
+    /// The maximum amount of samples.
+    ///
+    /// If the storyboards are too long for the interval, the interval is
+    /// increased so that at most this many samples are taken.
+    pub max_samples: usize,
+}
+
+impl Default for VisualDiffOptions {
+    fn default() -> Self {
+        Self {
+            interval: 1000.0 / 60.0,
+            tolerance: VisualTolerance::default(),
+            max_samples: 100_000,
+        }
+    }
+}
+
+/// Maximum differences between two states that are still considered equal.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct VisualTolerance {
+    /// Storyboard pixels along each axis.
+    pub position: f32,
+    /// Scale factor along each axis.
+    pub scale: f32,
+    /// Degrees.
+    pub rotation: f32,
+    /// Opacity between `0.0` and `1.0`.
+    ///
+    /// Sprites with an opacity up to this are considered invisible.
+    pub alpha: f32,
+    /// Each color component.
+    pub color: u8,
+}
+
+impl Default for VisualTolerance {
+    fn default() -> Self {
+        Self {
+            position: 0.01,
+            scale: 0.001,
+            rotation: 0.01,
+            alpha: 0.001,
+            color: 0,
+        }
+    }
+}
+
+/// Time ranges in which two storyboards render differently as returned by
+/// [`Storyboard::visual_diff`].
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct VisualDiff {
+    /// Sorted by time and non-overlapping.
+    pub ranges: Vec<MismatchRange>,
+}
+
+impl VisualDiff {
+    /// Whether both storyboards render the same at every sample.
+    pub const fn is_empty(&self) -> bool {
+        self.ranges.is_empty()
+    }
+}
+
+/// Consecutive samples at which the storyboards differ.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MismatchRange {
+    /// Time of the first differing sample.
+    pub start_time: f64,
+    /// Time of the last differing sample.
+    pub end_time: f64,
+    /// The elements that differ in at least one sample of the range.
+    pub elements: Vec<ElementMismatch>,
+}
+
+/// An element that is rendered differently.
+///
+/// Indices refer to the element's position within its layer and are `None`
+/// if the element has no counterpart in that storyboard.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ElementMismatch {
+    pub layer: LayerId,
+    pub path: String,
+    pub old_index: Option<usize>,
+    pub new_index: Option<usize>,
+    /// The differing properties, sorted and deduplicated.
+    pub properties: Vec<StateProperty>,
+}
+
+/// A property of the evaluated state of an element.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum StateProperty {
+    /// The element is only visible in one of the storyboards.
+    Visibility,
+    Position,
+    Scale,
+    Rotation,
+    Color,
+    Alpha,
+    Blending,
+    FlipH,
+    FlipV,
+    /// The displayed frame of an animation.
+    Frame,
+}
+
+impl StateProperty {
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            StateProperty::Visibility => "visibility",
+            StateProperty::Position => "position",
+            StateProperty::Scale => "scale",
+            StateProperty::Rotation => "rotation",
+            StateProperty::Color => "color",
+            StateProperty::Alpha => "alpha",
+            StateProperty::Blending => "blending",
+            StateProperty::FlipH => "horizontal flip",
+            StateProperty::FlipV => "vertical flip",
+            StateProperty::Frame => "frame",
+        }
+    }
+}
+
+impl Storyboard {
+    /// Evaluate the storyboard and a different revision of it at regular
+    /// intervals and report when they render differently.
+    ///
+    /// Sprites and animations of a layer are paired up by their path in
+    /// order of appearance, i.e. the second sprite of a path in the old
+    /// storyboard is compared to the second sprite of that path in the new
+    /// storyboard. Elements without counterpart are compared against an
+    /// invisible element.
+    ///
+    /// Commands of triggers are not taken into account.
+    pub fn visual_diff(&self, new: &Self, options: &VisualDiffOptions) -> VisualDiff {
+        let pairs = pair_elements(self, new);
+
+        let start_time = [self.earliest_event_time(), new.earliest_event_time()]
+            .into_iter()
+            .flatten()
+            .min_by(f64::total_cmp);
+
+        // Loops keep elements displayed beyond their latest event
+        let end_time = pairs
+            .iter()
+            .flat_map(|pair| [pair.old, pair.new])
+            .flatten()
+            .map(|(_, elem)| elem.end_time_for_display())
+            .max_by(f64::total_cmp);
+
+        let (Some(start_time), Some(end_time)) = (start_time, end_time) else {
+            return VisualDiff::default();
+        };
+
+        // Storyboards with only command-less sprites have inverted bounds
+        let span = end_time - start_time;
+
+        if !span.is_finite() || span < 0.0 || options.max_samples == 0 {
+            return VisualDiff::default();
+        }
+
+        let max_interval = span / (options.max_samples - 1).max(1) as f64;
+        let interval = options.interval.max(1.0).max(max_interval);
+        let sample_count = (span / interval) as usize + 1;
+
+        let mut ranges = Vec::new();
+        let mut curr: Option<RangeBuilder> = None;
+
+        for i in 0..sample_count {
+            let time = start_time + i as f64 * interval;
+            let mut differing = Vec::new();
+
+            for (i, pair) in pairs.iter().enumerate() {
+                let old = pair.old.and_then(|(_, elem)| Evaluated::new(elem, time));
+                let new = pair.new.and_then(|(_, elem)| Evaluated::new(elem, time));
+
+                let properties = compare(old.as_ref(), new.as_ref(), &options.tolerance);
+
+                if !properties.is_empty() {
+                    differing.push((i, properties));
+                }
+            }
+
+            if differing.is_empty() {
+                if let Some(range) = curr.take() {
+                    ranges.push(range.build(&pairs));
+                }
+            } else {
+                let range = curr.get_or_insert_with(|| RangeBuilder::new(time));
+                range.end_time = time;
+
+                for (i, properties) in differing {
+                    range.elements.entry(i).or_default().extend(properties);
+                }
+            }
+        }
+
+        if let Some(range) = curr {
+            ranges.push(range.build(&pairs));
+        }
+
+        VisualDiff { ranges }
+    }
+}
+
+struct Pair<'a> {
+    layer: &'a LayerId,
+    old: Option<(usize, &'a Element)>,
+    new: Option<(usize, &'a Element)>,
+}
+
+fn pair_elements<'a>(old: &'a Storyboard, new: &'a Storyboard) -> Vec<Pair<'a>> {
+    let names: BTreeSet<_> = old.layers.keys().chain(new.layers.keys()).collect();
+    let mut pairs = Vec::new();
+
+    for name in names {
+        let drawable = |storyboard: &'a Storyboard| {
+            storyboard
+                .layers
+                .get(name)
+                .into_iter()
+                .flat_map(|layer| layer.elements.iter().enumerate())
+                .filter(|(_, elem)| {
+                    matches!(
+                        elem.kind,
+                        ElementKind::Sprite(_) | ElementKind::Animation(_)
+                    )
+                })
+        };
+
+        let mut by_path: HashMap<&str, (Vec<_>, Vec<_>)> = HashMap::new();
+
+        for (i, elem) in drawable(old) {
+            by_path.entry(&elem.path).or_default().0.push((i, elem));
+        }
+
+        for (i, elem) in drawable(new) {
+            by_path.entry(&elem.path).or_default().1.push((i, elem));
+        }
+
+        let mut layer_pairs: Vec<_> = by_path
+            .into_values()
+            .flat_map(|(old, new)| {
+                (0..old.len().max(new.len())).map(move |i| Pair {
+                    layer: name,
+                    old: old.get(i).copied(),
+                    new: new.get(i).copied(),
+                })
+            })
+            .collect();
+
+        // Deterministic order regardless of hashing
+        layer_pairs.sort_by_key(|pair| (pair.old.map(|(i, _)| i), pair.new.map(|(i, _)| i)));
+        pairs.append(&mut layer_pairs);
+    }
+
+    pairs
+}
+
+/// The evaluated state of a visible element.
+struct Evaluated {
+    state: SpriteState,
+    frame: Option<i32>,
+}
+
+impl Evaluated {
+    fn new(elem: &Element, time: f64) -> Option<Self> {
+        let (sprite, frame) = match elem.kind {
+            ElementKind::Animation(ref animation) => {
+                (&animation.sprite, Some(animation.frame_at(time)))
+            }
+            ElementKind::Sprite(ref sprite) => (sprite, None),
+            ElementKind::Sample(_) | ElementKind::Video(_) => return None,
+        };
+
+        sprite.is_active_at(time).then(|| Self {
+            state: sprite.state_at(time),
+            frame,
+        })
+    }
+}
+
+fn compare(
+    old: Option<&Evaluated>,
+    new: Option<&Evaluated>,
+    tolerance: &VisualTolerance,
+) -> Vec<StateProperty> {
+    let old = old.filter(|old| old.state.alpha > tolerance.alpha);
+    let new = new.filter(|new| new.state.alpha > tolerance.alpha);
+
+    let (old, new) = match (old, new) {
+        (Some(old), Some(new)) => (old, new),
+        (None, None) => return Vec::new(),
+        (Some(_), None) | (None, Some(_)) => return vec![StateProperty::Visibility],
+    };
+
+    let (a, b) = (&old.state, &new.state);
+    let mut properties = Vec::new();
+
+    let exceeds = |a: f32, b: f32, tolerance: f32| (a - b).abs() > tolerance;
+
+    if exceeds(a.position.x, b.position.x, tolerance.position)
+        || exceeds(a.position.y, b.position.y, tolerance.position)
+    {
+        properties.push(StateProperty::Position);
+    }
+
+    if exceeds(a.scale.x, b.scale.x, tolerance.scale)
+        || exceeds(a.scale.y, b.scale.y, tolerance.scale)
+    {
+        properties.push(StateProperty::Scale);
+    }
+
+    if exceeds(a.rotation, b.rotation, tolerance.rotation) {
+        properties.push(StateProperty::Rotation);
+    }
+
+    let color_differs = a
+        .color
+        .0
+        .iter()
+        .zip(b.color.0.iter())
+        .any(|(a, b)| a.abs_diff(*b) > tolerance.color);
+
+    if color_differs {
+        properties.push(StateProperty::Color);
+    }
+
+    if exceeds(a.alpha, b.alpha, tolerance.alpha) {
+        properties.push(StateProperty::Alpha);
+    }
+
+    if a.blending != b.blending {
+        properties.push(StateProperty::Blending);
+    }
+
+    if a.flip_h != b.flip_h {
+        properties.push(StateProperty::FlipH);
+    }
+
+    if a.flip_v != b.flip_v {
+        properties.push(StateProperty::FlipV);
+    }
+
+    if old.frame != new.frame {
+        properties.push(StateProperty::Frame);
+    }
+
+    properties
+}
+
+struct RangeBuilder {
+    start_time: f64,
+    end_time: f64,
+    /// Differing properties by index of the element pair.
+    elements: HashMap<usize, BTreeSet<StateProperty>>,
+}
+
+impl RangeBuilder {
+    fn new(time: f64) -> Self {
+        Self {
+            start_time: time,
+            end_time: time,
+            elements: HashMap::new(),
+        }
+    }
+
+    fn build(self, pairs: &[Pair<'_>]) -> MismatchRange {
+        let mut elements: Vec<_> = self.elements.into_iter().collect();
+        elements.sort_unstable_by_key(|(i, _)| *i);
+
+        let elements = elements
+            .into_iter()
+            .map(|(i, properties)| {
+                let pair = &pairs[i];
+                let (_, elem) = pair.old.or(pair.new).expect("pair is never empty");
+
+                ElementMismatch {
+                    layer: pair.layer.to_owned(),
+                    path: elem.path.clone(),
+                    old_index: pair.old.map(|(i, _)| i),
+                    new_index: pair.new.map(|(i, _)| i),
+                    properties: properties.into_iter().collect(),
+                }
+            })
+            .collect();
+
+        MismatchRange {
+            start_time: self.start_time,
+            end_time: self.end_time,
+            elements,
+        }
+    }
+}
+
+impl Display for VisualDiff {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        for range in self.ranges.iter() {
+            writeln!(f, "{}..{}:", range.start_time, range.end_time)?;
+
+            for elem in range.elements.iter() {
+                writeln!(f, "  {elem}")?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl Display for ElementMismatch {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        write!(f, "{} \"{}\" [", self.layer, self.path)?;
+
+        match self.old_index {
+            Some(i) => write!(f, "{i}")?,
+            None => f.write_str("-")?,
+        }
+
+        f.write_str(" -> ")?;
+
+        match self.new_index {
+            Some(i) => write!(f, "{i}")?,
+            None => f.write_str("-")?,
+        }
+
+        f.write_str("]:")?;
+
+        for (i, property) in self.properties.iter().enumerate() {
+            let sep = if i == 0 { " " } else { ", " };
+            write!(f, "{sep}{}", property.as_str())?;
+        }
+
+        Ok(())
+    }
+}
@@ -2,12 +2,13 @@ use rosu_map::util::Pos;
 
 use crate::visual::Anchor;
 
-use super::{Sprite, SpriteInternal};
+use super::{Sprite, SpriteState};
 
 /// An animation [`Element`].
 ///
 /// [`Element`]: crate::element::Element
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Animation {
     pub sprite: Sprite,
     pub frame_count: i32,
@@ -55,10 +56,46 @@ impl Animation {
     pub fn end_time_for_display(&self) -> f64 {
         self.sprite.end_time_for_display()
     }
+
+    /// Whether the animation is displayed at the given time.
+    pub fn is_active_at(&self, time: f64) -> bool {
+        self.sprite.is_active_at(time)
+    }
+
+    /// Evaluate all commands of the animation at the given time.
+    pub fn state_at(&self, time: f64) -> SpriteState {
+        self.sprite.state_at(time)
+    }
+
+    /// The index of the frame that is displayed at the given time.
+    pub fn frame_at(&self, time: f64) -> i32 {
+        if self.frame_count <= 0 || self.frame_delay <= 0.0 {
+            return 0;
+        }
+
+        let frame = ((time - self.start_time()) / self.frame_delay).max(0.0) as i32;
+
+        match self.loop_kind {
+            AnimationLoopType::LoopForever => frame % self.frame_count,
+            AnimationLoopType::LoopOnce => frame.min(self.frame_count - 1),
+        }
+    }
+
+    /// The path of the image file for the given frame.
+    ///
+    /// The frame index is inserted right before the file extension of the
+    /// animation's path, e.g. `"sb/star.png"` becomes `"sb/star3.png"`.
+    pub fn frame_path(path: &str, frame: i32) -> String {
+        match path.rsplit_once('.') {
+            Some((name, ext)) if !ext.contains('/') => format!("{name}{frame}.{ext}"),
+            _ => format!("{path}{frame}"),
+        }
+    }
 }
 
 /// The loop type of an [`Animation`].
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum AnimationLoopType {
     LoopForever = 0,
     LoopOnce = 1,
@@ -76,38 +113,3 @@ impl AnimationLoopType {
         }
     }
 }
-
-pub(crate) struct AnimationInternal {
-    pub sprite: SpriteInternal,
-    pub frame_count: i32,
-    pub frame_delay: f64,
-    pub loop_kind: AnimationLoopType,
-}
-
-impl From<AnimationInternal> for Animation {
-    fn from(animation: AnimationInternal) -> Self {
-        Self {
-            sprite: animation.sprite.into(),
-            frame_count: animation.frame_count,
-            frame_delay: animation.frame_delay,
-            loop_kind: animation.loop_kind,
-        }
-    }
-}
-
-impl AnimationInternal {
-    pub fn new(
-        origin: Anchor,
-        initial_pos: Pos,
-        frame_count: i32,
-        frame_delay: f64,
-        loop_kind: AnimationLoopType,
-    ) -> Self {
-        Self {
-            sprite: SpriteInternal::new(origin, initial_pos),
-            frame_count,
-            frame_delay,
-            loop_kind,
-        }
-    }
-}
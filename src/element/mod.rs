@@ -1,20 +1,22 @@
-pub(crate) use self::{animation::AnimationInternal, sprite::SpriteInternal};
 pub use self::{
     animation::{Animation, AnimationLoopType},
     sample::Sample,
     sprite::Sprite,
+    state::SpriteState,
     video::Video,
 };
 
 mod animation;
 mod sample;
 mod sprite;
+mod state;
 mod video;
 
 /// An element of a [`Storyboard`].
 ///
 /// [`Storyboard`]: crate::Storyboard
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Element {
     pub path: String,
     pub kind: ElementKind,
@@ -39,10 +41,15 @@ impl Element {
     pub fn end_time(&self) -> f64 {
         self.kind.end_time()
     }
+
+    pub fn end_time_for_display(&self) -> f64 {
+        self.kind.end_time_for_display()
+    }
 }
 
 /// Additional data for an [`Element`].
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum ElementKind {
     Animation(Animation),
     Sample(Sample),
@@ -91,30 +98,13 @@ impl ElementKind {
             ElementKind::Video(elem) => elem.start_time,
         }
     }
-}
 
-pub(crate) struct ElementInternal {
-    pub path: String,
-    pub kind: ElementKindInternal,
-}
-
-impl From<ElementInternal> for Element {
-    fn from(elem: ElementInternal) -> Self {
-        Self {
-            path: elem.path,
-            kind: match elem.kind {
-                ElementKindInternal::Animation(elem) => ElementKind::Animation(elem.into()),
-                ElementKindInternal::Sample(elem) => ElementKind::Sample(elem),
-                ElementKindInternal::Sprite(elem) => ElementKind::Sprite(elem.into()),
-                ElementKindInternal::Video(elem) => ElementKind::Video(elem),
-            },
+    pub fn end_time_for_display(&self) -> f64 {
+        match self {
+            ElementKind::Animation(elem) => elem.end_time_for_display(),
+            ElementKind::Sample(elem) => elem.start_time,
+            ElementKind::Sprite(elem) => elem.end_time_for_display(),
+            ElementKind::Video(elem) => elem.start_time,
         }
     }
 }
-
-pub(crate) enum ElementKindInternal {
-    Animation(AnimationInternal),
-    Sample(Sample),
-    Sprite(SpriteInternal),
-    Video(Video),
-}
@@ -2,13 +2,15 @@
 ///
 /// [`Element`]: crate::element::Element
 #[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Sample {
     pub start_time: f64,
-    pub volume: i32,
+    /// Volume between `0.0` and `100.0`.
+    pub volume: f32,
 }
 
 impl Sample {
-    pub const fn new(start_time: f64, volume: i32) -> Self {
+    pub const fn new(start_time: f64, volume: f32) -> Self {
         Self { start_time, volume }
     }
 }
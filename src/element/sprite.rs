@@ -1,21 +1,20 @@
-use std::{cell::RefCell, rc::Rc};
-
 use rosu_map::util::Pos;
 
 use crate::{
-    command::{
-        CommandLoop, CommandLoopInternal, CommandTimelineGroup, CommandTrigger,
-        CommandTriggerInternal,
-    },
+    command::{CommandLoop, CommandTimelineGroup, CommandTrigger},
     visual::Anchor,
 };
 
+use super::state::{self, SpriteState};
+
 /// A sprite [`Element`].
 ///
 /// [`Element`]: crate::element::Element
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Sprite {
     pub origin: Anchor,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_util::pos"))]
     pub initial_pos: Pos,
     pub timeline_group: CommandTimelineGroup,
     pub loops: Vec<CommandLoop>,
@@ -92,79 +91,68 @@ impl Sprite {
             })
     }
 
-    /// Add a [`CommandLoop`] to the sprite.
-    // false positive
-    #[allow(clippy::missing_panics_doc)]
-    pub fn add_loop(&mut self, start_time: f64, repeat_count: u32) -> &mut CommandLoop {
-        let new_loop = CommandLoop::new(start_time, repeat_count);
-        self.loops.push(new_loop);
-
-        self.loops.last_mut().unwrap()
+    /// Whether the sprite is displayed at the given time.
+    pub fn is_active_at(&self, time: f64) -> bool {
+        self.has_commands() && self.start_time() <= time && time <= self.end_time_for_display()
     }
 
-    /// Add a [`CommandTrigger`] to the sprite.
-    // false positive
-    #[allow(clippy::missing_panics_doc)]
-    pub fn add_trigger(
-        &mut self,
-        trigger_name: String,
-        start_time: f64,
-        end_time: f64,
-        group_num: i32,
-    ) -> &mut CommandTrigger {
-        let trigger = CommandTrigger::new(trigger_name, start_time, end_time, group_num);
-        self.triggers.push(trigger);
-
-        self.triggers.last_mut().unwrap()
-    }
-}
-
-pub(crate) struct SpriteInternal {
-    pub origin: Anchor,
-    pub initial_pos: Pos,
-    pub timeline_group: Rc<RefCell<CommandTimelineGroup>>,
-    pub loops: Vec<CommandLoopInternal>,
-    pub triggers: Vec<CommandTriggerInternal>,
-}
-
-impl From<SpriteInternal> for Sprite {
-    fn from(sprite: SpriteInternal) -> Self {
-        Self {
-            origin: sprite.origin,
-            initial_pos: sprite.initial_pos,
-            timeline_group: Rc::into_inner(sprite.timeline_group)
-                .expect("multiple strong references around")
-                .into_inner(),
-            loops: sprite.loops.into_iter().map(CommandLoop::from).collect(),
-            triggers: sprite
-                .triggers
-                .into_iter()
-                .map(CommandTrigger::from)
-                .collect(),
-        }
-    }
-}
+    /// Evaluate all commands of the sprite at the given time.
+    ///
+    /// Note that the state is evaluated regardless of whether the sprite is
+    /// [active] at that time.
+    ///
+    /// [active]: Sprite::is_active_at
+    pub fn state_at(&self, time: f64) -> SpriteState {
+        let group = &self.timeline_group;
+        let loops = self.loops.as_slice();
+        let default = SpriteState::default();
+
+        let x = state::evaluate(group, loops, time, true, |group| &group.x);
+        let y = state::evaluate(group, loops, time, true, |group| &group.y);
+        let scale = state::evaluate(group, loops, time, true, |group| &group.scale);
+        let vector_scale = state::evaluate(group, loops, time, true, |group| &group.vector_scale);
+
+        let scale = match (scale, vector_scale) {
+            (Some(scale), Some(vector_scale)) => vector_scale * scale,
+            (Some(scale), None) => Pos::new(scale, scale),
+            (None, Some(vector_scale)) => vector_scale,
+            (None, None) => default.scale,
+        };
 
-impl SpriteInternal {
-    pub fn new(origin: Anchor, initial_pos: Pos) -> Self {
-        Self {
-            origin,
-            initial_pos,
-            timeline_group: Rc::new(RefCell::new(CommandTimelineGroup::default())),
-            loops: Vec::new(),
-            triggers: Vec::new(),
+        SpriteState {
+            position: Pos::new(
+                x.unwrap_or(self.initial_pos.x),
+                y.unwrap_or(self.initial_pos.y),
+            ),
+            scale,
+            rotation: state::evaluate(group, loops, time, true, |group| &group.rotation)
+                .unwrap_or(default.rotation),
+            color: state::evaluate(group, loops, time, true, |group| &group.color)
+                .unwrap_or(default.color),
+            alpha: state::evaluate(group, loops, time, true, |group| &group.alpha)
+                .unwrap_or(default.alpha),
+            blending: state::evaluate(group, loops, time, false, |group| {
+                &group.blending_parameters
+            })
+            .unwrap_or(default.blending),
+            flip_h: state::evaluate(group, loops, time, false, |group| &group.flip_h)
+                .unwrap_or(default.flip_h),
+            flip_v: state::evaluate(group, loops, time, false, |group| &group.flip_v)
+                .unwrap_or(default.flip_v),
         }
     }
 
+    /// Add a [`CommandLoop`] to the sprite.
     // false positive
     #[allow(clippy::missing_panics_doc)]
-    pub fn add_loop(&mut self, start_time: f64, repeat_count: u32) -> &mut CommandLoopInternal {
-        let new_loop = CommandLoopInternal::new(start_time, repeat_count);
+    pub fn add_loop(&mut self, start_time: f64, repeat_count: u32) -> &mut CommandLoop {
+        let new_loop = CommandLoop::new(start_time, repeat_count);
         self.loops.push(new_loop);
 
         self.loops.last_mut().unwrap()
     }
 
+    /// Add a [`CommandTrigger`] to the sprite.
     // false positive
     #[allow(clippy::missing_panics_doc)]
     pub fn add_trigger(
@@ -173,8 +161,8 @@ impl SpriteInternal {
         start_time: f64,
         end_time: f64,
         group_num: i32,
-    ) -> &mut CommandTriggerInternal {
-        let trigger = CommandTriggerInternal::new(trigger_name, start_time, end_time, group_num);
+    ) -> &mut CommandTrigger {
+        let trigger = CommandTrigger::new(trigger_name, start_time, end_time, group_num);
         self.triggers.push(trigger);
 
         self.triggers.last_mut().unwrap()
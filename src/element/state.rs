@@ -0,0 +1,165 @@
+use rosu_map::{section::colors::Color, util::Pos};
+
+use crate::{
+    command::{CommandLoop, CommandTimeline, CommandTimelineGroup, Interpolate, TypedCommand},
+    visual::BlendingParameters,
+};
+
+/// The evaluated state of a [`Sprite`] at a point in time.
+///
+/// Commands of [`CommandTrigger`]s are not taken into account.
+///
+/// [`Sprite`]: crate::element::Sprite
+/// [`CommandTrigger`]: crate::command::CommandTrigger
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct SpriteState {
+    pub position: Pos,
+    /// Combined uniform and vector scale.
+    pub scale: Pos,
+    /// Rotation in degrees.
+    pub rotation: f32,
+    pub color: Color,
+    pub alpha: f32,
+    pub blending: BlendingParameters,
+    pub flip_h: bool,
+    pub flip_v: bool,
+}
+
+impl Default for SpriteState {
+    fn default() -> Self {
+        Self {
+            position: Pos::default(),
+            scale: Pos::new(1.0, 1.0),
+            rotation: 0.0,
+            color: Color::new(255, 255, 255, 255),
+            alpha: 1.0,
+            blending: BlendingParameters::INHERIT,
+            flip_h: false,
+            flip_v: false,
+        }
+    }
+}
+
+/// A command of a timeline, placed at absolute time.
+struct Placed<'a, T> {
+    command: &'a TypedCommand<T>,
+    offset: f64,
+}
+
+impl<T: Interpolate> Placed<'_, T> {
+    fn start_time(&self) -> f64 {
+        self.command.start_time + self.offset
+    }
+
+    fn end_time(&self) -> f64 {
+        self.command.end_time + self.offset
+    }
+
+    fn value_at(&self, time: f64) -> T {
+        self.command.value_at(time - self.offset)
+    }
+}
+
+/// Evaluate a timeline of a sprite's group and loops at the given time.
+///
+/// If no command started yet, the start value of the earliest command is
+/// used if `initialize` is set, otherwise `None` is returned.
+pub(crate) fn evaluate<T, F>(
+    group: &CommandTimelineGroup,
+    loops: &[CommandLoop],
+    time: f64,
+    initialize: bool,
+    select: F,
+) -> Option<T>
+where
+    T: Interpolate,
+    F: Fn(&CommandTimelineGroup) -> &CommandTimeline<T>,
+{
+    let mut latest = select(group).latest_command_at(time).map(|command| Placed {
+        command,
+        offset: 0.0,
+    });
+
+    for l in loops.iter() {
+        let timeline = select(&l.group);
+
+        let Some(placed) = latest_loop_command_at(l, timeline, time) else {
+            continue;
+        };
+
+        let is_later = latest.as_ref().is_none_or(|curr| {
+            placed
+                .start_time()
+                .total_cmp(&curr.start_time())
+                .then(placed.end_time().total_cmp(&curr.end_time()))
+                .is_ge()
+        });
+
+        if is_later {
+            latest = Some(placed);
+        }
+    }
+
+    if let Some(placed) = latest {
+        return Some(placed.value_at(time));
+    }
+
+    if !initialize {
+        return None;
+    }
+
+    let main = select(group).first_command().map(|command| Placed {
+        command,
+        offset: 0.0,
+    });
+
+    loops
+        .iter()
+        .filter_map(|l| {
+            select(&l.group).first_command().map(|command| Placed {
+                command,
+                offset: l.loop_start_time,
+            })
+        })
+        .chain(main)
+        .min_by(|a, b| a.start_time().total_cmp(&b.start_time()))
+        .map(|placed| placed.command.start_value)
+}
+
+fn latest_loop_command_at<'a, T>(
+    l: &CommandLoop,
+    timeline: &'a CommandTimeline<T>,
+    time: f64,
+) -> Option<Placed<'a, T>> {
+    if timeline.commands.is_empty() {
+        return None;
+    }
+
+    let duration = l.group.duration();
+    let first_start = l.loop_start_time + l.group.start_time();
+
+    let iteration = if duration > 0.0 {
+        ((time - first_start) / duration)
+            .floor()
+            .clamp(0.0, f64::from(l.total_iterations.saturating_sub(1)))
+    } else {
+        0.0
+    };
+
+    let offset = l.loop_start_time + iteration * duration;
+
+    if let Some(command) = timeline.latest_command_at(time - offset) {
+        return Some(Placed { command, offset });
+    }
+
+    // Nothing started in the current iteration yet so the previous
+    // iteration's last command is still in effect.
+    if iteration > 0.0 {
+        return timeline.latest_command_at(f64::MAX).map(|command| Placed {
+            command,
+            offset: offset - duration,
+        });
+    }
+
+    None
+}
@@ -1,13 +1,30 @@
+use rosu_map::util::Pos;
+
 /// A video [`Element`].
 ///
 /// [`Element`]: crate::element::Element
 #[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Video {
     pub start_time: f64,
+    /// Offset from the video's default position as specified by its event.
+    #[cfg_attr(feature = "serde", serde(default, with = "crate::serde_util::pos"))]
+    pub offset: Pos,
 }
 
 impl Video {
     pub const fn new(start_time: f64) -> Self {
-        Self { start_time }
+        Self {
+            start_time,
+            offset: Pos::new(0.0, 0.0),
+        }
+    }
+
+    /// Set the offset of the video.
+    #[must_use]
+    pub const fn with_offset(mut self, offset: Pos) -> Self {
+        self.offset = offset;
+
+        self
     }
 }
@@ -1,17 +1,21 @@
 use std::{
     fmt::{Display, Formatter, Result as FmtResult},
     fs::File,
-    io::{BufWriter, Error as IoError, ErrorKind, Result as IoResult, Write},
+    io::{BufWriter, Error as IoError, Result as IoResult, Write},
     path::Path,
 };
 
-use rosu_map::{section::events::EventType, util::StrExt};
+use rosu_map::{
+    section::events::{BreakPeriod, EventType},
+    util::{Pos, StrExt},
+};
 
 use crate::{
     command::{CommandTimelineGroup, TypedCommand},
-    element::ElementKind,
+    element::{Animation, Element, ElementKind, Sample, Sprite, Video},
+    layer::LayerId,
     visual::Origins,
-    Storyboard,
+    BackgroundColor, Storyboard,
 };
 
 impl Storyboard {
@@ -30,51 +34,28 @@ impl Storyboard {
         let mut writer = Vec::with_capacity(4096);
         self.encode(&mut writer)?;
 
-        String::from_utf8(writer).map_err(|e| IoError::new(ErrorKind::Other, e))
+        String::from_utf8(writer).map_err(IoError::other)
     }
 
     /// Encode a [`Storyboard`] into content of a `.osb` file.
-    pub fn encode<W: Write>(&self, mut writer: W) -> IoResult<()> {
-        writeln!(writer, "osu file format v{}", self.format_version)?;
-
-        writer.write_all(b"\n")?;
-        self.encode_general(&mut writer)?;
-
-        writer.write_all(b"\n")?;
-        self.encode_events(&mut writer)?;
+    pub fn encode<W: Write>(&self, writer: W) -> IoResult<()> {
+        let mut writer = OsbWriter::new(writer, self.format_version)?;
+        writer.use_skin_sprites(self.use_skin_sprites)?;
 
-        writer.flush()
-    }
+        self.encode_background_and_video(&mut writer)?;
+        self.encode_breaks(&mut writer)?;
+        self.encode_layers(&mut writer)?;
+        self.encode_samples(&mut writer)?;
+        self.encode_background_colors(&mut writer)?;
 
-    fn encode_general<W: Write>(&self, writer: &mut W) -> IoResult<()> {
-        writeln!(
-            writer,
-            "[General]\nUseSkinSprites: {}",
-            if self.use_skin_sprites { "1" } else { "0" }
-        )
+        writer.finish().map(|_| ())
     }
 
-    fn encode_events<W: Write>(&self, writer: &mut W) -> IoResult<()> {
-        writer.write_all(b"[Events]\n")?;
-
-        self.encode_background_and_video(writer)?;
-        self.encode_breaks(writer)?;
-        self.encode_layers(writer)?;
-        self.encode_samples(writer)?;
-
-        Ok(())
-    }
-
-    fn encode_background_and_video<W: Write>(&self, writer: &mut W) -> IoResult<()> {
-        writer.write_all(b"//Background and Video events\n")?;
+    fn encode_background_and_video<W: Write>(&self, writer: &mut OsbWriter<W>) -> IoResult<()> {
+        writer.comment("Background and Video events")?;
 
         if !self.background_file.is_empty() {
-            writeln!(
-                writer,
-                "{},0,\"{}\",0,0",
-                EventType::Background as i32,
-                self.background_file.to_standardized_path()
-            )?;
+            writer.background(&self.background_file, self.background_offset)?;
         }
 
         let video_elems = self
@@ -90,141 +71,69 @@ impl Storyboard {
             });
 
         for (path, video) in video_elems {
-            writeln!(
-                writer,
-                "{},{},\"{path}\"",
-                EventType::Video as i32,
-                video.start_time,
-            )?;
+            writer.video(path, video)?;
         }
 
         Ok(())
     }
 
-    fn encode_breaks<W: Write>(&self, writer: &mut W) -> IoResult<()> {
-        writer.write_all(b"//Break Periods\n")?;
+    fn encode_breaks<W: Write>(&self, writer: &mut OsbWriter<W>) -> IoResult<()> {
+        writer.comment("Break Periods")?;
 
         for b in self.breaks.iter() {
-            writeln!(
-                writer,
-                "{},{},{}",
-                EventType::Break as i32,
-                b.start_time,
-                b.end_time
-            )?;
+            writer.break_period(b)?;
         }
 
         Ok(())
     }
 
-    fn encode_layers<W: Write>(&self, writer: &mut W) -> IoResult<()> {
-        writer.write_all(b"//Storyboard layers\n")?;
+    fn encode_layers<W: Write>(&self, writer: &mut OsbWriter<W>) -> IoResult<()> {
+        writer.comment("Storyboard layers")?;
 
         let elems = self
             .layers
             .iter()
-            .filter_map(|(name, layer)| {
-                let int = match name.as_str() {
-                    "Background" => 0,
-                    "Fail" => 1,
-                    "Pass" => 2,
-                    "Foreground" => 3,
-                    _ => return None,
-                };
-
-                Some((layer, int))
-            })
-            .flat_map(|(layer, int)| layer.elements.iter().map(move |elem| (int, elem)));
+            .filter(|(id, _)| is_encoded(id))
+            .flat_map(|(id, layer)| layer.elements.iter().map(move |elem| (id, elem)));
 
         for (layer, elem) in elems {
-            let sprite = match elem.kind {
+            match elem.kind {
                 ElementKind::Animation(ref animation) => {
-                    writeln!(
-                        writer,
-                        "{},{layer},{},\"{}\",{},{},{},{},{}",
-                        EventType::Animation as i32,
-                        Origins::from(animation.sprite.origin) as u8,
-                        elem.path,
-                        animation.sprite.initial_pos.x,
-                        animation.sprite.initial_pos.y,
-                        animation.frame_count,
-                        animation.frame_delay,
-                        animation.loop_kind as u8,
-                    )?;
-
-                    &animation.sprite
-                }
-                ElementKind::Sprite(ref sprite) => {
-                    writeln!(
-                        writer,
-                        "{},{layer},{},\"{}\",{},{}",
-                        EventType::Sprite as i32,
-                        Origins::from(sprite.origin) as u8,
-                        elem.path,
-                        sprite.initial_pos.x,
-                        sprite.initial_pos.y
-                    )?;
-
-                    sprite
+                    writer.animation(layer, &elem.path, animation)?;
                 }
-                ElementKind::Sample(_) | ElementKind::Video(_) => continue,
-            };
-
-            write_group(writer, 1, &sprite.timeline_group)?;
-
-            for l in sprite.loops.iter() {
-                writeln!(writer, " L,{},{}", l.loop_start_time, l.total_iterations)?;
-                write_group(writer, 2, &l.group)?;
+                ElementKind::Sprite(ref sprite) => writer.sprite(layer, &elem.path, sprite)?,
+                ElementKind::Sample(_) | ElementKind::Video(_) => {}
             }
+        }
 
-            for trigger in sprite.triggers.iter() {
-                write!(writer, " T,{}", trigger.name)?;
-
-                if trigger.start_time > f64::MIN
-                    || trigger.end_time < f64::MAX
-                    || trigger.group_num != 0
-                {
-                    write!(writer, ",{}", trigger.start_time)?;
-                }
-
-                if trigger.end_time < f64::MAX || trigger.group_num != 0 {
-                    write!(writer, ",{}", trigger.end_time)?;
-                }
+        Ok(())
+    }
 
-                if trigger.group_num != 0 {
-                    write!(writer, ",{}", trigger.group_num)?;
-                }
+    fn encode_background_colors<W: Write>(&self, writer: &mut OsbWriter<W>) -> IoResult<()> {
+        if self.background_colors.is_empty() {
+            return Ok(());
+        }
 
-                writer.write_all(b"\n")?;
+        writer.comment("Background Colour Transformations")?;
 
-                write_group(writer, 2, &trigger.group)?;
-            }
+        for color in self.background_colors.iter() {
+            writer.background_color(color)?;
         }
 
         Ok(())
     }
 
-    fn encode_samples<W: Write>(&self, writer: &mut W) -> IoResult<()> {
-        writer.write_all(b"//Storyboard Sound Samples\n")?;
+    fn encode_samples<W: Write>(&self, writer: &mut OsbWriter<W>) -> IoResult<()> {
+        writer.comment("Storyboard Sound Samples")?;
 
         let samples = self
             .layers
             .iter()
-            .filter_map(|(name, layer)| {
-                let int = match name.as_str() {
-                    "Background" => 0,
-                    "Fail" => 1,
-                    "Pass" => 2,
-                    "Foreground" => 3,
-                    _ => return None,
-                };
-
-                Some((layer, int))
-            })
-            .flat_map(|(layer, int)| {
+            .filter(|(id, _)| is_encoded(id))
+            .flat_map(|(id, layer)| {
                 layer.elements.iter().filter_map(move |elem| {
                     if let ElementKind::Sample(ref sample) = elem.kind {
-                        Some((int, elem.path.as_str(), sample))
+                        Some((id, elem.path.as_str(), sample))
                     } else {
                         None
                     }
@@ -232,20 +141,306 @@ impl Storyboard {
             });
 
         for (layer, path, sample) in samples {
-            writeln!(
-                writer,
-                "{},{},{layer},\"{}\",{}",
-                EventType::Sample as i32,
-                sample.start_time,
-                path.to_standardized_path(),
-                sample.volume,
-            )?;
+            writer.sample(layer, path, sample)?;
         }
 
         Ok(())
     }
 }
 
+/// Writes the content of a `.osb` file progressively without requiring a
+/// [`Storyboard`].
+///
+/// Each method writes its line(s) immediately, starting a new section if
+/// necessary, so memory usage does not depend on the size of the storyboard.
+/// Elements may be written in any order; when decoding, they are grouped by
+/// their layer while keeping their relative order.
+///
+/// ```
+/// use rosu_storyboard::{element::Sprite, reexport::Pos, visual::{Anchor, Easing}, LayerId, OsbWriter, Storyboard};
+///
+/// let mut writer = OsbWriter::new(Vec::new(), 14).unwrap();
+/// writer.variable("$star", "\"sb/star.png\"").unwrap();
+///
+/// for i in 0..10 {
+///     let mut sprite = Sprite::new(Anchor::CENTER, Pos::new(320.0, 240.0));
+///     sprite.timeline_group.alpha.add(Easing::None, f64::from(i) * 100.0, 1000.0, 1.0, 0.0);
+///
+///     writer.sprite(&LayerId::Foreground, "$star", &sprite).unwrap();
+/// }
+///
+/// let bytes = writer.finish().unwrap();
+/// let storyboard = Storyboard::from_bytes(&bytes).unwrap();
+///
+/// assert_eq!(storyboard.layers[&LayerId::Foreground].elements.len(), 10);
+/// ```
+pub struct OsbWriter<W: Write> {
+    writer: W,
+    section: Option<WriterSection>,
+}
+
+#[derive(Copy, Clone, PartialEq, Eq)]
+enum WriterSection {
+    General,
+    Variables,
+    Events,
+}
+
+impl WriterSection {
+    const fn as_str(self) -> &'static str {
+        match self {
+            Self::General => "General",
+            Self::Variables => "Variables",
+            Self::Events => "Events",
+        }
+    }
+}
+
+impl OsbWriter<BufWriter<File>> {
+    /// Create a file at the given path and write the header.
+    pub fn create(path: impl AsRef<Path>, format_version: i32) -> IoResult<Self> {
+        let file = File::create(path)?;
+
+        Self::new(BufWriter::new(file), format_version)
+    }
+}
+
+impl<W: Write> OsbWriter<W> {
+    /// Create a new [`OsbWriter`] and write the header.
+    pub fn new(mut writer: W, format_version: i32) -> IoResult<Self> {
+        writeln!(writer, "osu file format v{format_version}")?;
+
+        Ok(Self {
+            writer,
+            section: None,
+        })
+    }
+
+    fn section(&mut self, section: WriterSection) -> IoResult<&mut W> {
+        if self.section != Some(section) {
+            writeln!(self.writer, "\n[{}]", section.as_str())?;
+            self.section = Some(section);
+        }
+
+        Ok(&mut self.writer)
+    }
+
+    /// Write whether skin sprites should be used into the `[General]` section.
+    pub fn use_skin_sprites(&mut self, use_skin_sprites: bool) -> IoResult<()> {
+        let writer = self.section(WriterSection::General)?;
+
+        writeln!(
+            writer,
+            "UseSkinSprites: {}",
+            if use_skin_sprites { "1" } else { "0" }
+        )
+    }
+
+    /// Write a variable into the `[Variables]` section.
+    ///
+    /// Occurrences of the name in subsequent events are replaced by the value
+    /// when decoding.
+    pub fn variable(&mut self, name: &str, value: &str) -> IoResult<()> {
+        let writer = self.section(WriterSection::Variables)?;
+
+        writeln!(writer, "{name}={value}")
+    }
+
+    /// Write a comment line into the `[Events]` section.
+    pub fn comment(&mut self, comment: &str) -> IoResult<()> {
+        let writer = self.section(WriterSection::Events)?;
+
+        writeln!(writer, "//{comment}")
+    }
+
+    /// Write the beatmap's background image with the given offset.
+    pub fn background(&mut self, path: &str, offset: Pos) -> IoResult<()> {
+        let writer = self.section(WriterSection::Events)?;
+
+        writeln!(
+            writer,
+            "{},0,\"{}\",{},{}",
+            EventType::Background as i32,
+            path.to_standardized_path(),
+            offset.x,
+            offset.y,
+        )
+    }
+
+    /// Write a [`Video`] with the given path.
+    pub fn video(&mut self, path: &str, video: &Video) -> IoResult<()> {
+        let writer = self.section(WriterSection::Events)?;
+
+        write!(
+            writer,
+            "{},{},\"{path}\"",
+            EventType::Video as i32,
+            video.start_time,
+        )?;
+
+        if video.offset != Pos::default() {
+            write!(writer, ",{},{}", video.offset.x, video.offset.y)?;
+        }
+
+        writer.write_all(b"\n")
+    }
+
+    /// Write a [`BreakPeriod`].
+    pub fn break_period(&mut self, period: &BreakPeriod) -> IoResult<()> {
+        let writer = self.section(WriterSection::Events)?;
+
+        writeln!(
+            writer,
+            "{},{},{}",
+            EventType::Break as i32,
+            period.start_time,
+            period.end_time
+        )
+    }
+
+    /// Write a [`BackgroundColor`] change.
+    pub fn background_color(&mut self, color: &BackgroundColor) -> IoResult<()> {
+        let writer = self.section(WriterSection::Events)?;
+        let [r, g, b, _] = color.color.0;
+
+        writeln!(
+            writer,
+            "{},{},{r},{g},{b}",
+            EventType::Color as i32,
+            color.time,
+        )
+    }
+
+    /// Write an [`Element`] of the given layer.
+    ///
+    /// The layer of a video is ignored.
+    pub fn element(&mut self, layer: &LayerId, element: &Element) -> IoResult<()> {
+        match element.kind {
+            ElementKind::Animation(ref animation) => {
+                self.animation(layer, &element.path, animation)
+            }
+            ElementKind::Sample(ref sample) => self.sample(layer, &element.path, sample),
+            ElementKind::Sprite(ref sprite) => self.sprite(layer, &element.path, sprite),
+            ElementKind::Video(ref video) => self.video(&element.path, video),
+        }
+    }
+
+    /// Write a [`Sprite`] including all of its commands.
+    pub fn sprite(&mut self, layer: &LayerId, path: &str, sprite: &Sprite) -> IoResult<()> {
+        let writer = self.section(WriterSection::Events)?;
+
+        writeln!(
+            writer,
+            "{},{},{},\"{path}\",{},{}",
+            EventType::Sprite as i32,
+            LayerName(layer),
+            Origins::from(sprite.origin) as u8,
+            sprite.initial_pos.x,
+            sprite.initial_pos.y
+        )?;
+
+        write_sprite_commands(writer, sprite)
+    }
+
+    /// Write an [`Animation`] including all of its commands.
+    pub fn animation(
+        &mut self,
+        layer: &LayerId,
+        path: &str,
+        animation: &Animation,
+    ) -> IoResult<()> {
+        let writer = self.section(WriterSection::Events)?;
+
+        writeln!(
+            writer,
+            "{},{},{},\"{path}\",{},{},{},{},{}",
+            EventType::Animation as i32,
+            LayerName(layer),
+            Origins::from(animation.sprite.origin) as u8,
+            animation.sprite.initial_pos.x,
+            animation.sprite.initial_pos.y,
+            animation.frame_count,
+            animation.frame_delay,
+            animation.loop_kind as u8,
+        )?;
+
+        write_sprite_commands(writer, &animation.sprite)
+    }
+
+    /// Write a [`Sample`] of the given layer.
+    pub fn sample(&mut self, layer: &LayerId, path: &str, sample: &Sample) -> IoResult<()> {
+        let writer = self.section(WriterSection::Events)?;
+
+        writeln!(
+            writer,
+            "{},{},{},\"{}\",{}",
+            EventType::Sample as i32,
+            sample.start_time,
+            LayerName(layer),
+            path.to_standardized_path(),
+            sample.volume,
+        )
+    }
+
+    /// Flush and return the underlying writer.
+    pub fn finish(mut self) -> IoResult<W> {
+        self.writer.flush()?;
+
+        Ok(self.writer)
+    }
+}
+
+/// Writes the layer's number if it has one, otherwise its name.
+struct LayerName<'a>(&'a LayerId);
+
+impl Display for LayerName<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        match self.0.number() {
+            Some(number) => write!(f, "{number}"),
+            None => f.write_str(self.0.as_str()),
+        }
+    }
+}
+
+/// Whether elements of the layer are encoded by [`Storyboard::encode`].
+const fn is_encoded(layer: &LayerId) -> bool {
+    matches!(
+        layer,
+        LayerId::Background | LayerId::Fail | LayerId::Pass | LayerId::Foreground
+    )
+}
+
+fn write_sprite_commands<W: Write>(writer: &mut W, sprite: &Sprite) -> IoResult<()> {
+    write_group(writer, 1, &sprite.timeline_group)?;
+
+    for l in sprite.loops.iter() {
+        writeln!(writer, " L,{},{}", l.loop_start_time, l.total_iterations)?;
+        write_group(writer, 2, &l.group)?;
+    }
+
+    for trigger in sprite.triggers.iter() {
+        write!(writer, " T,{}", trigger.name)?;
+
+        if trigger.start_time > f64::MIN || trigger.end_time < f64::MAX || trigger.group_num != 0 {
+            write!(writer, ",{}", trigger.start_time)?;
+        }
+
+        if trigger.end_time < f64::MAX || trigger.group_num != 0 {
+            write!(writer, ",{}", trigger.end_time)?;
+        }
+
+        if trigger.group_num != 0 {
+            write!(writer, ",{}", trigger.group_num)?;
+        }
+
+        writer.write_all(b"\n")?;
+
+        write_group(writer, 2, &trigger.group)?;
+    }
+
+    Ok(())
+}
+
 fn write_group<W: Write>(
     writer: &mut W,
     indent: usize,
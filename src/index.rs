@@ -0,0 +1,218 @@
+use std::ops::{Bound, RangeBounds};
+
+use crate::{LayerId, Storyboard};
+
+/// Index over the lifetimes of all [`Element`]s of a [`Storyboard`].
+///
+/// Elements are stored in an implicit interval tree based on their
+/// [`start_time`] and [`end_time_for_display`] so that all elements alive at a point in
+/// time or within a time range can be found without checking every element.
+///
+/// The index does not borrow the storyboard so it must be rebuilt whenever
+/// elements are added, removed, or modified.
+///
+/// [`Element`]: crate::element::Element
+/// [`start_time`]: crate::element::Element::start_time
+/// [`end_time_for_display`]: crate::element::Element::end_time_for_display
+#[derive(Clone, Debug, Default)]
+pub struct TimeIndex {
+    layers: Vec<LayerId>,
+    /// Sorted by start time.
+    entries: Vec<Entry>,
+    /// The maximum end time of the subtree rooted at each entry.
+    max_end: Vec<f64>,
+}
+
+#[derive(Copy, Clone, Debug)]
+struct Entry {
+    start_time: f64,
+    end_time: f64,
+    layer: u32,
+    index: u32,
+}
+
+/// An [`Element`] found through a [`TimeIndex`].
+///
+/// [`Element`]: crate::element::Element
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct ElementRef<'a> {
+    /// The element's layer.
+    pub layer: &'a LayerId,
+    /// Index of the element within its layer.
+    pub index: usize,
+    pub start_time: f64,
+    pub end_time: f64,
+}
+
+impl TimeIndex {
+    /// Build the index for the given [`Storyboard`].
+    pub fn new(storyboard: &Storyboard) -> Self {
+        let mut layers = Vec::with_capacity(storyboard.layers.len());
+        let mut entries = Vec::new();
+
+        for (name, layer) in storyboard.layers.iter() {
+            let layer_idx = layers.len() as u32;
+            layers.push(name.clone());
+
+            let iter = layer.elements.iter().enumerate().map(|(i, elem)| {
+                let start_time = elem.start_time();
+
+                Entry {
+                    start_time,
+                    end_time: elem.end_time_for_display().max(start_time),
+                    layer: layer_idx,
+                    index: i as u32,
+                }
+            });
+
+            entries.extend(iter);
+        }
+
+        entries.sort_by(|a, b| a.start_time.total_cmp(&b.start_time));
+
+        let mut max_end = vec![f64::MIN; entries.len()];
+        fill_max_end(&entries, &mut max_end, 0, entries.len());
+
+        Self {
+            layers,
+            entries,
+            max_end,
+        }
+    }
+
+    /// The amount of indexed elements.
+    pub const fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Whether no elements are indexed.
+    pub const fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// The earliest start time of all indexed elements.
+    pub fn start_time(&self) -> Option<f64> {
+        self.entries.first().map(|entry| entry.start_time)
+    }
+
+    /// The latest end time of all indexed elements.
+    pub fn end_time(&self) -> Option<f64> {
+        self.max_end
+            .get(self.entries.len() / 2)
+            .copied()
+            .filter(|_| !self.entries.is_empty())
+    }
+
+    /// All elements that are alive at the given time.
+    ///
+    /// The order of the elements is unspecified.
+    pub fn active_at(&self, time: f64) -> ActiveElements<'_> {
+        self.active_in(time..=time)
+    }
+
+    /// All elements whose lifetime overlaps with the given range.
+    ///
+    /// The order of the elements is unspecified.
+    pub fn active_in<R: RangeBounds<f64>>(&self, range: R) -> ActiveElements<'_> {
+        let mut stack = Vec::with_capacity(32);
+
+        if !self.entries.is_empty() {
+            stack.push((0, self.entries.len()));
+        }
+
+        ActiveElements {
+            index: self,
+            start: range.start_bound().cloned(),
+            end: range.end_bound().cloned(),
+            stack,
+        }
+    }
+}
+
+fn fill_max_end(entries: &[Entry], max_end: &mut [f64], lo: usize, hi: usize) -> f64 {
+    if lo >= hi {
+        return f64::MIN;
+    }
+
+    let mid = lo + (hi - lo) / 2;
+    let left = fill_max_end(entries, max_end, lo, mid);
+    let right = fill_max_end(entries, max_end, mid + 1, hi);
+    max_end[mid] = entries[mid].end_time.max(left).max(right);
+
+    max_end[mid]
+}
+
+/// Iterator over elements of a [`TimeIndex`] within a time range.
+#[derive(Clone, Debug)]
+pub struct ActiveElements<'a> {
+    index: &'a TimeIndex,
+    start: Bound<f64>,
+    end: Bound<f64>,
+    stack: Vec<(usize, usize)>,
+}
+
+impl ActiveElements<'_> {
+    fn ends_after_start(&self, end_time: f64) -> bool {
+        match self.start {
+            Bound::Included(start) => end_time >= start,
+            Bound::Excluded(start) => end_time > start,
+            Bound::Unbounded => true,
+        }
+    }
+
+    fn starts_before_end(&self, start_time: f64) -> bool {
+        match self.end {
+            Bound::Included(end) => start_time <= end,
+            Bound::Excluded(end) => start_time < end,
+            Bound::Unbounded => true,
+        }
+    }
+}
+
+impl<'a> Iterator for ActiveElements<'a> {
+    type Item = ElementRef<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while let Some((lo, hi)) = self.stack.pop() {
+            let mid = lo + (hi - lo) / 2;
+
+            // No interval in this subtree reaches the range
+            if !self.ends_after_start(self.index.max_end[mid]) {
+                continue;
+            }
+
+            if lo < mid {
+                self.stack.push((lo, mid));
+            }
+
+            let entry = self.index.entries[mid];
+
+            // Entries to the right start even later
+            if !self.starts_before_end(entry.start_time) {
+                continue;
+            }
+
+            if mid + 1 < hi {
+                self.stack.push((mid + 1, hi));
+            }
+
+            if self.ends_after_start(entry.end_time) {
+                return Some(ElementRef {
+                    layer: &self.index.layers[entry.layer as usize],
+                    index: entry.index as usize,
+                    start_time: entry.start_time,
+                    end_time: entry.end_time,
+                });
+            }
+        }
+
+        None
+    }
+}
+
+impl Storyboard {
+    /// Build a [`TimeIndex`] to efficiently query elements by time.
+    pub fn time_index(&self) -> TimeIndex {
+        TimeIndex::new(self)
+    }
+}
@@ -0,0 +1,628 @@
+use std::{cmp::Reverse, error, fmt};
+
+use rosu_map::{
+    section::{colors::Color, events::BreakPeriod},
+    util::Pos,
+    LATEST_FORMAT_VERSION,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    command::{CommandLoop, CommandTimeline, CommandTimelineGroup, CommandTrigger},
+    element::{Animation, AnimationLoopType, Element, ElementKind, Sample, Sprite, Video},
+    visual::{Easing, Origins},
+    BackgroundColor, LayerId, Storyboard,
+};
+
+/// The version of the JSON format written by [`Storyboard::to_json`].
+///
+/// [`Storyboard::from_json`] only accepts this version.
+pub const JSON_VERSION: u32 = 1;
+
+impl Storyboard {
+    /// Export the [`Storyboard`] into the versioned JSON interchange format.
+    ///
+    /// The format is described by `schema/storyboard.schema.json`.
+    pub fn to_json(&self) -> Result<String, JsonError> {
+        serde_json::to_string(&JsonStoryboard::from(self)).map_err(JsonError::Serde)
+    }
+
+    /// Same as [`Storyboard::to_json`] but the output is pretty-printed.
+    pub fn to_json_pretty(&self) -> Result<String, JsonError> {
+        serde_json::to_string_pretty(&JsonStoryboard::from(self)).map_err(JsonError::Serde)
+    }
+
+    /// Import a [`Storyboard`] from the versioned JSON interchange format.
+    ///
+    /// Layers that are not listed keep their default settings.
+    pub fn from_json(json: &str) -> Result<Self, JsonError> {
+        let storyboard: JsonStoryboard = serde_json::from_str(json).map_err(JsonError::Serde)?;
+
+        if storyboard.version != JSON_VERSION {
+            return Err(JsonError::UnsupportedVersion(storyboard.version));
+        }
+
+        Ok(storyboard.into())
+    }
+}
+
+/// Error when converting a [`Storyboard`] from or into JSON.
+#[derive(Debug)]
+pub enum JsonError {
+    Serde(serde_json::Error),
+    UnsupportedVersion(u32),
+}
+
+impl error::Error for JsonError {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            JsonError::Serde(err) => Some(err),
+            JsonError::UnsupportedVersion(_) => None,
+        }
+    }
+}
+
+impl fmt::Display for JsonError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Serde(_) => f.write_str("invalid storyboard json"),
+            Self::UnsupportedVersion(version) => {
+                write!(f, "unsupported storyboard json version {version}")
+            }
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct JsonStoryboard {
+    version: u32,
+    #[serde(default = "latest_format_version")]
+    format_version: i32,
+    #[serde(default)]
+    use_skin_sprites: bool,
+    #[serde(default)]
+    background_file: String,
+    #[serde(default, skip_serializing_if = "JsonPos::is_zero")]
+    background_offset: JsonPos,
+    #[serde(default)]
+    breaks: Vec<JsonBreak>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    background_colors: Vec<JsonBackgroundColor>,
+    #[serde(default)]
+    layers: Vec<JsonLayer>,
+}
+
+const fn latest_format_version() -> i32 {
+    LATEST_FORMAT_VERSION
+}
+
+#[derive(Serialize, Deserialize)]
+struct JsonBreak {
+    start_time: f64,
+    end_time: f64,
+}
+
+#[derive(Serialize, Deserialize)]
+struct JsonBackgroundColor {
+    time: f64,
+    color: JsonColor,
+}
+
+#[derive(Serialize, Deserialize)]
+struct JsonLayer {
+    name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    depth: Option<i32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    masking: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    visible_when_passing: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    visible_when_failing: Option<bool>,
+    #[serde(default)]
+    elements: Vec<JsonElement>,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum JsonElement {
+    Sprite {
+        path: String,
+        #[serde(flatten)]
+        sprite: JsonSprite,
+    },
+    Animation {
+        path: String,
+        #[serde(flatten)]
+        sprite: JsonSprite,
+        frame_count: i32,
+        frame_delay: f64,
+        #[serde(default = "default_loop_type")]
+        loop_type: AnimationLoopType,
+    },
+    Sample {
+        path: String,
+        start_time: f64,
+        #[serde(default = "default_volume")]
+        volume: f32,
+    },
+    Video {
+        path: String,
+        start_time: f64,
+        #[serde(default, skip_serializing_if = "JsonPos::is_zero")]
+        offset: JsonPos,
+    },
+}
+
+const fn default_loop_type() -> AnimationLoopType {
+    AnimationLoopType::LoopForever
+}
+
+const fn default_volume() -> f32 {
+    100.0
+}
+
+#[derive(Serialize, Deserialize)]
+struct JsonSprite {
+    #[serde(default = "default_origin")]
+    origin: String,
+    #[serde(default)]
+    position: JsonPos,
+    #[serde(default)]
+    commands: Vec<JsonCommand>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    loops: Vec<JsonLoop>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    triggers: Vec<JsonTrigger>,
+}
+
+fn default_origin() -> String {
+    Origins::TopLeft.as_str().to_owned()
+}
+
+#[derive(Copy, Clone, Default, Serialize, Deserialize)]
+struct JsonPos {
+    x: f32,
+    y: f32,
+}
+
+impl JsonPos {
+    // Signature required by `#[serde(skip_serializing_if = "...")]`
+    #[allow(clippy::trivially_copy_pass_by_ref)]
+    fn is_zero(&self) -> bool {
+        self.x == 0.0 && self.y == 0.0
+    }
+}
+
+#[derive(Copy, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+enum JsonColor {
+    Rgb([u8; 3]),
+    Rgba([u8; 4]),
+}
+
+#[derive(Serialize, Deserialize)]
+struct JsonLoop {
+    start_time: f64,
+    iterations: u32,
+    #[serde(default)]
+    commands: Vec<JsonCommand>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct JsonTrigger {
+    trigger: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    start_time: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    end_time: Option<f64>,
+    #[serde(default, skip_serializing_if = "is_zero")]
+    group: i32,
+    #[serde(default)]
+    commands: Vec<JsonCommand>,
+}
+
+#[allow(clippy::trivially_copy_pass_by_ref)]
+const fn is_zero(n: &i32) -> bool {
+    *n == 0
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum JsonCommand {
+    MoveX(JsonTypedCommand<f32>),
+    MoveY(JsonTypedCommand<f32>),
+    Scale(JsonTypedCommand<f32>),
+    VectorScale(JsonTypedCommand<JsonPos>),
+    /// Values are in degrees.
+    Rotate(JsonTypedCommand<f32>),
+    Color(JsonTypedCommand<JsonColor>),
+    Fade(JsonTypedCommand<f32>),
+    Additive(JsonParameterCommand),
+    FlipH(JsonParameterCommand),
+    FlipV(JsonParameterCommand),
+}
+
+#[derive(Copy, Clone, Serialize, Deserialize)]
+struct JsonTypedCommand<T> {
+    #[serde(default = "default_easing")]
+    easing: Easing,
+    start_time: f64,
+    end_time: Option<f64>,
+    start: T,
+    end: Option<T>,
+}
+
+#[derive(Copy, Clone, Serialize, Deserialize)]
+struct JsonParameterCommand {
+    #[serde(default = "default_easing")]
+    easing: Easing,
+    start_time: f64,
+    end_time: Option<f64>,
+}
+
+const fn default_easing() -> Easing {
+    Easing::None
+}
+
+impl From<Pos> for JsonPos {
+    fn from(pos: Pos) -> Self {
+        Self { x: pos.x, y: pos.y }
+    }
+}
+
+impl From<JsonPos> for Pos {
+    fn from(pos: JsonPos) -> Self {
+        Pos::new(pos.x, pos.y)
+    }
+}
+
+impl From<Color> for JsonColor {
+    fn from(color: Color) -> Self {
+        let [r, g, b, a] = color.0;
+
+        if a == 255 {
+            Self::Rgb([r, g, b])
+        } else {
+            Self::Rgba([r, g, b, a])
+        }
+    }
+}
+
+impl From<JsonColor> for Color {
+    fn from(color: JsonColor) -> Self {
+        match color {
+            JsonColor::Rgb([r, g, b]) => Color::new(r, g, b, 255),
+            JsonColor::Rgba(rgba) => Color(rgba),
+        }
+    }
+}
+
+impl From<&Storyboard> for JsonStoryboard {
+    fn from(storyboard: &Storyboard) -> Self {
+        let breaks = storyboard
+            .breaks
+            .iter()
+            .map(|b| JsonBreak {
+                start_time: b.start_time,
+                end_time: b.end_time,
+            })
+            .collect();
+
+        let mut layers: Vec<_> = storyboard.layers.iter().collect();
+        layers.sort_by(|(a_name, a), (b_name, b)| {
+            (Reverse(a.depth), a_name).cmp(&(Reverse(b.depth), b_name))
+        });
+
+        let layers = layers
+            .into_iter()
+            .map(|(name, layer)| JsonLayer {
+                name: name.to_string(),
+                depth: Some(layer.depth),
+                masking: Some(layer.masking),
+                visible_when_passing: Some(layer.visible_when_passing),
+                visible_when_failing: Some(layer.visible_when_failing),
+                elements: layer.elements.iter().map(JsonElement::from).collect(),
+            })
+            .collect();
+
+        Self {
+            version: JSON_VERSION,
+            format_version: storyboard.format_version,
+            use_skin_sprites: storyboard.use_skin_sprites,
+            background_file: storyboard.background_file.clone(),
+            background_offset: JsonPos::from(storyboard.background_offset),
+            breaks,
+            background_colors: storyboard
+                .background_colors
+                .iter()
+                .map(|color| JsonBackgroundColor {
+                    time: color.time,
+                    color: JsonColor::from(color.color),
+                })
+                .collect(),
+            layers,
+        }
+    }
+}
+
+impl From<JsonStoryboard> for Storyboard {
+    fn from(json: JsonStoryboard) -> Self {
+        let mut storyboard = Storyboard {
+            format_version: json.format_version,
+            use_skin_sprites: json.use_skin_sprites,
+            background_file: json.background_file,
+            background_offset: Pos::from(json.background_offset),
+            breaks: json
+                .breaks
+                .into_iter()
+                .map(|b| BreakPeriod {
+                    start_time: b.start_time,
+                    end_time: b.end_time,
+                })
+                .collect(),
+            background_colors: json
+                .background_colors
+                .into_iter()
+                .map(|color| BackgroundColor::new(color.time, Color::from(color.color)))
+                .collect(),
+            ..Default::default()
+        };
+
+        for json_layer in json.layers {
+            let layer = storyboard.get_or_insert_layer(LayerId::from(json_layer.name));
+
+            if let Some(depth) = json_layer.depth {
+                layer.depth = depth;
+            }
+
+            if let Some(masking) = json_layer.masking {
+                layer.masking = masking;
+            }
+
+            if let Some(visible) = json_layer.visible_when_passing {
+                layer.visible_when_passing = visible;
+            }
+
+            if let Some(visible) = json_layer.visible_when_failing {
+                layer.visible_when_failing = visible;
+            }
+
+            layer
+                .elements
+                .extend(json_layer.elements.into_iter().map(Element::from));
+        }
+
+        // Layers created afterwards must be placed below all custom depths
+        storyboard.min_layer_depth = storyboard
+            .layers
+            .values()
+            .map(|layer| layer.depth)
+            .filter(|&depth| depth != i32::MIN)
+            .fold(0, i32::min);
+
+        storyboard
+    }
+}
+
+impl From<&Element> for JsonElement {
+    fn from(elem: &Element) -> Self {
+        let path = elem.path.clone();
+
+        match elem.kind {
+            ElementKind::Animation(ref animation) => Self::Animation {
+                path,
+                sprite: JsonSprite::from(&animation.sprite),
+                frame_count: animation.frame_count,
+                frame_delay: animation.frame_delay,
+                loop_type: animation.loop_kind,
+            },
+            ElementKind::Sample(ref sample) => Self::Sample {
+                path,
+                start_time: sample.start_time,
+                volume: sample.volume,
+            },
+            ElementKind::Sprite(ref sprite) => Self::Sprite {
+                path,
+                sprite: JsonSprite::from(sprite),
+            },
+            ElementKind::Video(ref video) => Self::Video {
+                path,
+                start_time: video.start_time,
+                offset: JsonPos::from(video.offset),
+            },
+        }
+    }
+}
+
+impl From<JsonElement> for Element {
+    fn from(elem: JsonElement) -> Self {
+        match elem {
+            JsonElement::Sprite { path, sprite } => Element::new(path, Sprite::from(sprite)),
+            JsonElement::Animation {
+                path,
+                sprite,
+                frame_count,
+                frame_delay,
+                loop_type,
+            } => {
+                let animation = Animation {
+                    sprite: Sprite::from(sprite),
+                    frame_count,
+                    frame_delay,
+                    loop_kind: loop_type,
+                };
+
+                Element::new(path, animation)
+            }
+            JsonElement::Sample {
+                path,
+                start_time,
+                volume,
+            } => Element::new(path, Sample::new(start_time, volume)),
+            JsonElement::Video {
+                path,
+                start_time,
+                offset,
+            } => Element::new(path, Video::new(start_time).with_offset(Pos::from(offset))),
+        }
+    }
+}
+
+impl From<&Sprite> for JsonSprite {
+    fn from(sprite: &Sprite) -> Self {
+        let loops = sprite
+            .loops
+            .iter()
+            .map(|l| JsonLoop {
+                start_time: l.loop_start_time,
+                iterations: l.total_iterations,
+                commands: group_commands(&l.group),
+            })
+            .collect();
+
+        let triggers = sprite
+            .triggers
+            .iter()
+            .map(|trigger| JsonTrigger {
+                trigger: trigger.name.clone(),
+                start_time: (trigger.start_time > f64::MIN).then_some(trigger.start_time),
+                end_time: (trigger.end_time < f64::MAX).then_some(trigger.end_time),
+                group: trigger.group_num,
+                commands: group_commands(&trigger.group),
+            })
+            .collect();
+
+        Self {
+            origin: Origins::from(sprite.origin).as_str().to_owned(),
+            position: sprite.initial_pos.into(),
+            commands: group_commands(&sprite.timeline_group),
+            loops,
+            triggers,
+        }
+    }
+}
+
+impl From<JsonSprite> for Sprite {
+    fn from(json: JsonSprite) -> Self {
+        let mut sprite = Sprite::new(Origins::parse(&json.origin), json.position.into());
+        fill_group(&mut sprite.timeline_group, json.commands);
+
+        for json_loop in json.loops {
+            let mut l = CommandLoop {
+                loop_start_time: json_loop.start_time,
+                total_iterations: json_loop.iterations,
+                ..CommandLoop::new(0.0, 0)
+            };
+
+            fill_group(&mut l.group, json_loop.commands);
+            sprite.loops.push(l);
+        }
+
+        for json_trigger in json.triggers {
+            let mut trigger = CommandTrigger::new(
+                json_trigger.trigger,
+                json_trigger.start_time.unwrap_or(f64::MIN),
+                json_trigger.end_time.unwrap_or(f64::MAX),
+                json_trigger.group,
+            );
+
+            fill_group(&mut trigger.group, json_trigger.commands);
+            sprite.triggers.push(trigger);
+        }
+
+        sprite
+    }
+}
+
+fn group_commands(group: &CommandTimelineGroup) -> Vec<JsonCommand> {
+    fn typed<T, U, F>(
+        timeline: &CommandTimeline<T>,
+        variant: F,
+    ) -> impl Iterator<Item = JsonCommand> + '_
+    where
+        T: Copy + Into<U>,
+        F: Fn(JsonTypedCommand<U>) -> JsonCommand + 'static,
+    {
+        timeline.commands.iter().map(move |command| {
+            variant(JsonTypedCommand {
+                easing: command.easing,
+                start_time: command.start_time,
+                end_time: Some(command.end_time),
+                start: command.start_value.into(),
+                end: Some(command.end_value.into()),
+            })
+        })
+    }
+
+    fn parameter<T, F>(
+        timeline: &CommandTimeline<T>,
+        variant: F,
+    ) -> impl Iterator<Item = JsonCommand> + '_
+    where
+        F: Fn(JsonParameterCommand) -> JsonCommand + 'static,
+    {
+        timeline.commands.iter().map(move |command| {
+            variant(JsonParameterCommand {
+                easing: command.easing,
+                start_time: command.start_time,
+                end_time: Some(command.end_time),
+            })
+        })
+    }
+
+    typed(&group.x, JsonCommand::MoveX)
+        .chain(typed(&group.y, JsonCommand::MoveY))
+        .chain(typed(&group.scale, JsonCommand::Scale))
+        .chain(typed(&group.vector_scale, JsonCommand::VectorScale))
+        .chain(typed(&group.rotation, JsonCommand::Rotate))
+        .chain(typed(&group.color, JsonCommand::Color))
+        .chain(typed(&group.alpha, JsonCommand::Fade))
+        .chain(parameter(&group.blending_parameters, JsonCommand::Additive))
+        .chain(parameter(&group.flip_h, JsonCommand::FlipH))
+        .chain(parameter(&group.flip_v, JsonCommand::FlipV))
+        .collect()
+}
+
+fn fill_group(group: &mut CommandTimelineGroup, commands: Vec<JsonCommand>) {
+    fn add_typed<T, U>(timeline: &mut CommandTimeline<T>, command: JsonTypedCommand<U>)
+    where
+        T: Copy,
+        U: Copy + Into<T>,
+    {
+        timeline.add(
+            command.easing,
+            command.start_time,
+            command.end_time.unwrap_or(command.start_time),
+            command.start.into(),
+            command.end.unwrap_or(command.start).into(),
+        );
+    }
+
+    for command in commands {
+        match command {
+            JsonCommand::MoveX(command) => add_typed(&mut group.x, command),
+            JsonCommand::MoveY(command) => add_typed(&mut group.y, command),
+            JsonCommand::Scale(command) => add_typed(&mut group.scale, command),
+            JsonCommand::VectorScale(command) => add_typed(&mut group.vector_scale, command),
+            JsonCommand::Rotate(command) => add_typed(&mut group.rotation, command),
+            JsonCommand::Color(command) => add_typed(&mut group.color, command),
+            JsonCommand::Fade(command) => add_typed(&mut group.alpha, command),
+            JsonCommand::Additive(command) => group.add_additive(
+                command.easing,
+                command.start_time,
+                command.end_time.unwrap_or(command.start_time),
+            ),
+            JsonCommand::FlipH(command) => group.add_flip_h(
+                command.easing,
+                command.start_time,
+                command.end_time.unwrap_or(command.start_time),
+            ),
+            JsonCommand::FlipV(command) => group.add_flip_v(
+                command.easing,
+                command.start_time,
+                command.end_time.unwrap_or(command.start_time),
+            ),
+        }
+    }
+}
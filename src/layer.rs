@@ -1,9 +1,19 @@
-use crate::element::{Element, ElementInternal};
+use std::{
+    borrow::Borrow,
+    cmp::Ordering,
+    convert::Infallible,
+    fmt::{Display, Formatter, Result as FmtResult},
+    hash::{Hash, Hasher},
+    str::FromStr,
+};
+
+use crate::element::Element;
 
 /// A layer of a [`Storyboard`].
 ///
 /// [`Storyboard`]: crate::Storyboard
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Layer {
     pub depth: i32,
     pub masking: bool,
@@ -26,6 +36,15 @@ impl Layer {
     pub fn add(&mut self, element: Element) {
         self.elements.push(element);
     }
+
+    /// Whether the layer is visible while the player is passing or failing.
+    pub const fn is_visible(&self, passing: bool) -> bool {
+        if passing {
+            self.visible_when_passing
+        } else {
+            self.visible_when_failing
+        }
+    }
 }
 
 impl Default for Layer {
@@ -40,68 +59,183 @@ impl Default for Layer {
     }
 }
 
-pub(crate) struct StoryLayer<'a>(&'a str);
+/// Identifier of a [`Layer`].
+///
+/// Layers are specified either by number or by name in `.osb` files. Known
+/// names are always parsed into their variant so [`LayerId::Custom`] never
+/// holds the name of a known layer.
+#[derive(Clone, Debug)]
+pub enum LayerId {
+    Background,
+    Fail,
+    Pass,
+    Foreground,
+    Overlay,
+    Video,
+    /// A layer unknown to osu!.
+    ///
+    /// Elements of such layers are kept but never drawn by osu!.
+    Custom(CustomLayer),
+}
+
+/// The name of a [`LayerId::Custom`].
+///
+/// Can only be created by parsing a [`LayerId`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CustomLayer(String);
+
+impl CustomLayer {
+    /// The layer's name.
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    pub(crate) const fn capacity(&self) -> usize {
+        self.0.capacity()
+    }
+}
 
-impl<'a> StoryLayer<'a> {
-    pub(crate) fn parse(s: &'a str) -> Self {
+impl LayerId {
+    /// Parse a [`LayerId`] from either its number or its name.
+    ///
+    /// Unknown numbers and names result in [`LayerId::Custom`].
+    pub fn parse(s: &str) -> Self {
         match s.parse::<u8>() {
-            Ok(0) => Self("Background"),
-            Ok(1) => Self("Fail"),
-            Ok(2) => Self("Pass"),
-            Ok(3) => Self("Foreground"),
-            Ok(4) => Self("Overlay"),
-            Ok(5) => Self("Video"),
+            Ok(number) => Self::from_number(number).unwrap_or_else(|| Self::from_name(s)),
+            Err(_) => Self::from_name(s),
+        }
+    }
+
+    /// The [`LayerId`] of the given number.
+    pub const fn from_number(number: u8) -> Option<Self> {
+        match number {
+            0 => Some(Self::Background),
+            1 => Some(Self::Fail),
+            2 => Some(Self::Pass),
+            3 => Some(Self::Foreground),
+            4 => Some(Self::Overlay),
+            5 => Some(Self::Video),
+            _ => None,
+        }
+    }
+
+    /// The [`LayerId`] of the given name.
+    ///
+    /// Names are case-sensitive; unknown names result in [`LayerId::Custom`].
+    pub fn from_name(name: &str) -> Self {
+        match name {
+            "Background" => Self::Background,
+            "Fail" => Self::Fail,
+            "Pass" => Self::Pass,
+            "Foreground" => Self::Foreground,
+            "Overlay" => Self::Overlay,
+            "Video" => Self::Video,
             // lazer throws an error if the string doesn't match the variant
             // name but we'll accept it as is
-            _ => Self(s),
+            _ => Self::Custom(CustomLayer(name.to_owned())),
+        }
+    }
+
+    /// The layer's number as used in `.osb` files, if it has one.
+    pub const fn number(&self) -> Option<u8> {
+        match self {
+            Self::Background => Some(0),
+            Self::Fail => Some(1),
+            Self::Pass => Some(2),
+            Self::Foreground => Some(3),
+            Self::Overlay => Some(4),
+            Self::Video => Some(5),
+            Self::Custom(_) => None,
         }
     }
 
-    pub(crate) const fn as_str(&self) -> &str {
-        self.0
+    /// The layer's name.
+    pub fn as_str(&self) -> &str {
+        match self {
+            Self::Background => "Background",
+            Self::Fail => "Fail",
+            Self::Pass => "Pass",
+            Self::Foreground => "Foreground",
+            Self::Overlay => "Overlay",
+            Self::Video => "Video",
+            Self::Custom(name) => name.as_str(),
+        }
     }
 }
 
-pub(crate) struct LayerInternal {
-    pub depth: i32,
-    pub masking: bool,
-    pub visible_when_passing: bool,
-    pub visible_when_failing: bool,
-    pub elements: Vec<ElementInternal>,
+impl FromStr for LayerId {
+    type Err = Infallible;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(Self::parse(s))
+    }
 }
 
-impl From<LayerInternal> for Layer {
-    fn from(layer: LayerInternal) -> Self {
-        Self {
-            depth: layer.depth,
-            masking: layer.masking,
-            visible_when_passing: layer.visible_when_passing,
-            visible_when_failing: layer.visible_when_failing,
-            elements: layer.elements.into_iter().map(Element::from).collect(),
-        }
+impl From<&str> for LayerId {
+    fn from(s: &str) -> Self {
+        Self::parse(s)
     }
 }
 
-impl LayerInternal {
-    pub fn new(depth: i32, masking: bool) -> Self {
-        Self {
-            depth,
-            masking,
-            ..Default::default()
+impl From<String> for LayerId {
+    fn from(s: String) -> Self {
+        match Self::parse(&s) {
+            Self::Custom(_) => Self::Custom(CustomLayer(s)),
+            id => id,
         }
     }
 }
 
-impl Default for LayerInternal {
-    fn default() -> Self {
-        let layer = Layer::default();
+impl Display for LayerId {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        f.write_str(self.as_str())
+    }
+}
 
-        Self {
-            depth: layer.depth,
-            masking: layer.masking,
-            visible_when_passing: layer.visible_when_passing,
-            visible_when_failing: layer.visible_when_failing,
-            elements: Vec::new(),
-        }
+// Equality, ordering, and hashing are based on the name so that layers can
+// be looked up by `&str` through `Borrow`. Since custom layers never have a
+// known name, this is equivalent to comparing variants.
+
+impl PartialEq for LayerId {
+    fn eq(&self, other: &Self) -> bool {
+        self.as_str() == other.as_str()
+    }
+}
+
+impl Eq for LayerId {}
+
+impl PartialEq<str> for LayerId {
+    fn eq(&self, other: &str) -> bool {
+        self.as_str() == other
+    }
+}
+
+impl PartialEq<&str> for LayerId {
+    fn eq(&self, other: &&str) -> bool {
+        self.as_str() == *other
+    }
+}
+
+impl PartialOrd for LayerId {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for LayerId {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.as_str().cmp(other.as_str())
+    }
+}
+
+impl Hash for LayerId {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.as_str().hash(state);
+    }
+}
+
+impl Borrow<str> for LayerId {
+    fn borrow(&self) -> &str {
+        self.as_str()
     }
 }
@@ -6,16 +6,54 @@
 //! to decode `.osu` or `.osb` files.
 //!
 //! ```
-//! use rosu_storyboard::Storyboard;
+//! use rosu_storyboard::{LayerId, Storyboard};
 //! use rosu_storyboard::element::ElementKind;
 //!
 //! let path = "./resources/Himeringo - Yotsuya-san ni Yoroshiku (RLC) [Winber1's Extreme].osu";
 //! let storyboard = Storyboard::from_path(path).unwrap();
 //!
-//! let first_bg_elem = &storyboard.layers["Background"].elements[0];
+//! let first_bg_elem = &storyboard.layers[&LayerId::Background].elements[0];
 //! assert!(matches!(first_bg_elem.kind, ElementKind::Sprite(_)));
 //! ```
 //!
+//! # Features
+//!
+//! | Flag    | Description |
+//! | ------- | ----------- |
+//! | `serde` | Implements `serde::Serialize` and `serde::Deserialize` for the storyboard model |
+//! | `json`  | Enables `Storyboard::to_json` and `Storyboard::from_json`, implies `serde` |
+//! | `cli`   | Builds the `rosu-storyboard` binary, implies `json` |
+//! | `parallel` | Enables `Storyboard::from_path_parallel` and friends to decode events on multiple threads through `rayon` |
+//! | `audio` | Enables `MixPlan::mix` to mix WAV and Ogg Vorbis samples into a PCM buffer and write it as WAV through `hound` and `lewton` |
+//!
+//! ## Serde schema
+//!
+//! With the `serde` feature, all types of the model are (de)serialized through their fields
+//! with the following exceptions so that their format stays stable:
+//!
+//! - `Pos` is an object `{ "x": f32, "y": f32 }`.
+//! - `Color` is an array `[red, green, blue, alpha]` of `u8`.
+//! - `BreakPeriod` is an object `{ "start_time": f64, "end_time": f64 }`.
+//! - `Anchor` is its bitflags as a number.
+//! - `CommandTimeline` is a list of its commands, each being an object
+//!   `{ "easing": Easing, "start_time": f64, "end_time": f64, "start_value": T, "end_value": T }`.
+//!   The timeline's start and end are recomputed when deserializing.
+//! - Enums such as `ElementKind` or `Easing` use serde's default externally tagged format,
+//!   e.g. `{ "Sprite": { .. } }` or `"OutQuad"`.
+//!
+//! ## Command-line tool
+//!
+//! With the `cli` feature, the `rosu-storyboard` binary inspects and converts storyboards:
+//!
+//! ```sh
+//! cargo install rosu-storyboard --features cli
+//! rosu-storyboard stats storyboard.osb
+//! rosu-storyboard validate storyboard.osb
+//! rosu-storyboard extract storyboard.osb --layer Foreground --from 1000 --to 5000 -o out.osb
+//! ```
+//!
+//! Run `rosu-storyboard --help` for all commands and options.
+//!
 //! [osu!]: https://osu.ppy.sh/
 //! [`DecodeBeatmap`]: rosu_map::DecodeBeatmap
 //! [`Storyboard`]: crate::storyboard::Storyboard
@@ -37,25 +75,72 @@
 
 pub use self::{
     decode::{ParseStoryboardError, StoryboardState},
-    layer::Layer,
-    storyboard::Storyboard,
+    encode::OsbWriter,
+    layer::{CustomLayer, Layer, LayerId},
+    play_state::{PlayState, PlayStateTransition},
+    storyboard::{BackgroundColor, Storyboard},
+    stream::{StoryboardEvent, StoryboardEvents},
+    transform::{AspectRatio, WIDESCREEN_OFFSET},
+    validate::LineError,
 };
 
 mod decode;
 mod encode;
 mod layer;
+mod play_state;
+mod reader;
 mod storyboard;
+mod stream;
+mod transform;
+mod validate;
+
+#[cfg(feature = "parallel")]
+mod parallel;
+
+#[cfg(feature = "serde")]
+mod serde_util;
+
+/// Beat-based timing through timing points.
+pub mod beat;
+
+/// Fluent authoring of storyboards.
+pub mod builder;
 
 /// Command types.
 pub mod command;
 
+/// Compact in-memory representation of storyboards.
+pub mod compact;
+
+/// Structural and visual comparison of storyboards.
+pub mod diff;
+
 /// Storyboard elements.
 pub mod element;
 
+/// Time-based lookup of elements.
+pub mod index;
+
+/// JSON interchange format.
+#[cfg(feature = "json")]
+pub mod json;
+
+/// Storyboard load estimation.
+pub mod load;
+
+/// Audio mix planning of storyboard samples.
+pub mod mix;
+
+/// Resolution of command triggers.
+pub mod triggers;
+
 /// Visual elements.
 pub mod visual;
 
 /// Re-exported types of `rosu-map`.
 pub mod reexport {
-    pub use rosu_map::{section::colors::Color, util::Pos};
+    pub use rosu_map::{
+        section::{colors::Color, hit_objects::hit_samples::SampleBank},
+        util::Pos,
+    };
 }
@@ -0,0 +1,275 @@
+use std::cmp::Ordering;
+
+use rosu_map::util::Pos;
+
+use crate::{
+    element::{Animation, ElementKind, Sprite},
+    LayerId, PlayState, Storyboard,
+};
+
+/// Options for [`Storyboard::estimate_load`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct LoadOptions {
+    /// Milliseconds between two samples.
+    pub interval: f64,
+    /// Size of the screen in storyboard pixels.
+    pub screen_size: Pos,
+    /// Whether the player is passing or failing over time.
+    ///
+    /// Layers that are not visible in the current state are ignored.
+    pub play_state: PlayState,
+    /// How many of the most contributing elements should be listed.
+    pub top_count: usize,
+    /// The maximum amount of samples.
+    ///
+    /// If the storyboard is too long for the interval, the interval is
+    /// increased so that at most this many samples are taken.
+    pub max_samples: usize,
+}
+
+impl Default for LoadOptions {
+    fn default() -> Self {
+        Self {
+            interval: 100.0,
+            screen_size: Pos::new(640.0, 480.0),
+            play_state: PlayState::passing(),
+            top_count: 10,
+            max_samples: 100_000,
+        }
+    }
+}
+
+/// The estimated load of a [`Storyboard`].
+///
+/// A load of `1.0` means that sprites cover an area equal to the full screen.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct LoadReport {
+    /// Load of each sampled point in time.
+    pub samples: Vec<LoadSample>,
+    /// The highest sampled load.
+    pub peak: LoadSample,
+    /// The average load across all samples.
+    pub average: f64,
+    /// The elements with the highest average load, sorted descendingly.
+    pub top_elements: Vec<ElementLoad>,
+}
+
+/// The load at a point in time.
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct LoadSample {
+    pub time: f64,
+    pub load: f64,
+}
+
+/// The load that is caused by a single [`Element`].
+///
+/// [`Element`]: crate::element::Element
+#[derive(Clone, Debug, PartialEq)]
+pub struct ElementLoad {
+    /// The element's layer.
+    pub layer: LayerId,
+    /// Index of the element within its layer.
+    pub index: usize,
+    /// The element's path.
+    pub path: String,
+    /// The element's load averaged across all samples.
+    pub average: f64,
+    /// The element's highest load across all samples.
+    pub peak: f64,
+}
+
+impl Storyboard {
+    /// Estimate the load of the storyboard, i.e. how much of the screen is
+    /// covered by sprites over time.
+    ///
+    /// `image_size` returns the size in pixels for an image path. Elements
+    /// whose size is unknown are skipped. For animations, the path of the
+    /// current [frame] is tried before the animation's own path.
+    ///
+    /// [frame]: Animation::frame_path
+    pub fn estimate_load<F>(&self, mut image_size: F, options: &LoadOptions) -> LoadReport
+    where
+        F: FnMut(&str) -> Option<Pos>,
+    {
+        let (Some(start_time), Some(end_time)) =
+            (self.earliest_event_time(), self.latest_event_time())
+        else {
+            return LoadReport::default();
+        };
+
+        // Storyboards with only command-less sprites have inverted bounds
+        let span = end_time - start_time;
+
+        if !span.is_finite() || span < 0.0 || options.max_samples == 0 {
+            return LoadReport::default();
+        }
+
+        let max_interval = span / (options.max_samples - 1).max(1) as f64;
+        let interval = options.interval.max(1.0).max(max_interval);
+        let sample_count = (span / interval) as usize + 1;
+
+        let (mut elements, sources): (Vec<_>, Vec<_>) = self
+            .layers
+            .iter()
+            .filter(|(_, layer)| layer.visible_when_passing || layer.visible_when_failing)
+            .flat_map(|(name, layer)| {
+                layer
+                    .elements
+                    .iter()
+                    .enumerate()
+                    .map(move |(i, elem)| (name, layer, i, elem))
+            })
+            .filter(|(.., elem)| {
+                matches!(
+                    elem.kind,
+                    ElementKind::Sprite(_) | ElementKind::Animation(_)
+                )
+            })
+            .map(|(name, layer, index, elem)| {
+                let elem_load = ElementLoad {
+                    layer: name.clone(),
+                    index,
+                    path: elem.path.clone(),
+                    average: 0.0,
+                    peak: 0.0,
+                };
+
+                (elem_load, (layer, elem))
+            })
+            .unzip();
+
+        let screen_area = f64::from(options.screen_size.x * options.screen_size.y);
+        let mut samples = Vec::with_capacity(sample_count);
+
+        for i in 0..sample_count {
+            let time = start_time + i as f64 * interval;
+            let mut load = 0.0;
+            let passing = options.play_state.is_passing_at(time);
+
+            for (elem_load, &(layer, elem)) in elements.iter_mut().zip(sources.iter()) {
+                if !layer.is_visible(passing) {
+                    continue;
+                }
+
+                let covered = match elem.kind {
+                    ElementKind::Sprite(ref sprite) => image_size(&elem.path)
+                        .map_or(0.0, |size| covered_area(sprite, size, time, options)),
+                    ElementKind::Animation(ref animation) => {
+                        let frame = animation.frame_at(time);
+                        let frame_path = Animation::frame_path(&elem.path, frame);
+
+                        image_size(&frame_path)
+                            .or_else(|| image_size(&elem.path))
+                            .map_or(0.0, |size| {
+                                covered_area(&animation.sprite, size, time, options)
+                            })
+                    }
+                    ElementKind::Sample(_) | ElementKind::Video(_) => 0.0,
+                };
+
+                let elem_load_value = covered / screen_area;
+                elem_load.average += elem_load_value;
+                elem_load.peak = elem_load.peak.max(elem_load_value);
+                load += elem_load_value;
+            }
+
+            samples.push(LoadSample { time, load });
+        }
+
+        let sample_count = samples.len() as f64;
+
+        for elem_load in elements.iter_mut() {
+            elem_load.average /= sample_count;
+        }
+
+        elements.retain(|elem_load| elem_load.peak > 0.0);
+        elements.sort_unstable_by(|a, b| {
+            b.average
+                .total_cmp(&a.average)
+                .then_with(|| a.layer.cmp(&b.layer))
+                .then(a.index.cmp(&b.index))
+        });
+        elements.truncate(options.top_count);
+
+        let peak = samples
+            .iter()
+            .copied()
+            .max_by(|a, b| match a.load.total_cmp(&b.load) {
+                // prefer the earliest sample
+                Ordering::Equal => b.time.total_cmp(&a.time),
+                ordering => ordering,
+            })
+            .unwrap_or_default();
+
+        let average = samples.iter().map(|sample| sample.load).sum::<f64>() / sample_count;
+
+        LoadReport {
+            samples,
+            peak,
+            average,
+            top_elements: elements,
+        }
+    }
+}
+
+/// The area of the screen that is covered by the sprite at the given time.
+fn covered_area(sprite: &Sprite, size: Pos, time: f64, options: &LoadOptions) -> f64 {
+    if !sprite.is_active_at(time) {
+        return 0.0;
+    }
+
+    let state = sprite.state_at(time);
+
+    if state.alpha <= 0.0 {
+        return 0.0;
+    }
+
+    let width = f64::from(size.x * state.scale.x.abs());
+    let height = f64::from(size.y * state.scale.y.abs());
+    let area = width * height;
+
+    if area <= 0.0 {
+        return 0.0;
+    }
+
+    // Corners of the sprite relative to its origin
+    let (origin_x, origin_y) = sprite.origin.relative_position();
+    let left = -f64::from(origin_x) * width;
+    let top = -f64::from(origin_y) * height;
+    let corners = [
+        (left, top),
+        (left + width, top),
+        (left, top + height),
+        (left + width, top + height),
+    ];
+
+    let (sin, cos) = f64::from(state.rotation).to_radians().sin_cos();
+    let x = f64::from(state.position.x);
+    let y = f64::from(state.position.y);
+
+    let mut min_x = f64::MAX;
+    let mut min_y = f64::MAX;
+    let mut max_x = f64::MIN;
+    let mut max_y = f64::MIN;
+
+    for (corner_x, corner_y) in corners {
+        let rotated_x = x + corner_x * cos - corner_y * sin;
+        let rotated_y = y + corner_x * sin + corner_y * cos;
+
+        min_x = min_x.min(rotated_x);
+        min_y = min_y.min(rotated_y);
+        max_x = max_x.max(rotated_x);
+        max_y = max_y.max(rotated_y);
+    }
+
+    let bounds_area = (max_x - min_x) * (max_y - min_y);
+
+    // Widescreen storyboards extend equally to both sides
+    let screen_width = f64::from(options.screen_size.x);
+    let screen_left = (640.0 - screen_width) / 2.0;
+    let visible_width = (max_x.min(screen_left + screen_width) - min_x.max(screen_left)).max(0.0);
+    let visible_height = (max_y.min(f64::from(options.screen_size.y)) - min_y.max(0.0)).max(0.0);
+
+    // Exact for unrotated sprites, approximated by the bounding box otherwise
+    area * (visible_width * visible_height) / bounds_area
+}
@@ -0,0 +1,452 @@
+//! Command-line tool to inspect and convert osu! storyboards.
+//!
+//! Requires the `cli` feature.
+
+use std::{
+    env, error,
+    fmt::{self, Write as _},
+    fs,
+    io::{self, Read, Write},
+    process::ExitCode,
+};
+
+use rosu_storyboard::{
+    command::CommandTimelineGroup, element::ElementKind, json::JsonError, Layer, LayerId,
+    Storyboard,
+};
+
+const USAGE: &str = "\
+Usage: rosu-storyboard <COMMAND> <INPUT> [OPTIONS]
+
+Commands:
+  stats      Print element counts per layer, command counts per type, and the time span
+  validate   Print every line that fails to parse; exits with 1 if there are any
+  fmt        Decode and re-encode the storyboard
+  to-json    Convert the storyboard into JSON
+  from-json  Convert JSON into a storyboard
+  extract    Only keep the elements of a layer and/or time range
+
+INPUT is a file path or `-` for stdin.
+
+Options:
+  -o, --output <PATH>  Write to a file instead of stdout
+      --pretty         Pretty-print JSON (to-json)
+      --layer <NAME>   Only keep this layer (extract)
+      --from <MS>      Only keep elements that end at or after this time (extract)
+      --to <MS>        Only keep elements that start at or before this time (extract)
+  -h, --help           Print this message";
+
+fn main() -> ExitCode {
+    let args: Vec<_> = env::args().skip(1).collect();
+
+    match Args::parse(&args).and_then(|args| run(&args)) {
+        Ok(code) => code,
+        Err(CliError::Help) => {
+            println!("{USAGE}");
+
+            ExitCode::SUCCESS
+        }
+        Err(err @ CliError::Usage(_)) => {
+            eprintln!("error: {err}\n\n{USAGE}");
+
+            ExitCode::from(2)
+        }
+        Err(err) => {
+            eprint!("error: {err}");
+
+            let mut source = error::Error::source(&err);
+
+            while let Some(err) = source {
+                eprint!(": {err}");
+                source = err.source();
+            }
+
+            eprintln!();
+
+            ExitCode::FAILURE
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+enum Command {
+    Stats,
+    Validate,
+    Fmt,
+    ToJson,
+    FromJson,
+    Extract,
+}
+
+impl Command {
+    fn parse(s: &str) -> Option<Self> {
+        match s {
+            "stats" => Some(Self::Stats),
+            "validate" => Some(Self::Validate),
+            "fmt" => Some(Self::Fmt),
+            "to-json" => Some(Self::ToJson),
+            "from-json" => Some(Self::FromJson),
+            "extract" => Some(Self::Extract),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+struct Args {
+    command: Option<Command>,
+    input: String,
+    output: Option<String>,
+    pretty: bool,
+    layer: Option<LayerId>,
+    from: Option<f64>,
+    to: Option<f64>,
+}
+
+impl Args {
+    fn parse(args: &[String]) -> Result<Self, CliError> {
+        let mut parsed = Self::default();
+        let mut input = None;
+        let mut args = args.iter();
+
+        while let Some(arg) = args.next() {
+            let mut value = |name: &str| {
+                args.next()
+                    .cloned()
+                    .ok_or_else(|| CliError::Usage(format!("missing value for `{name}`")))
+            };
+
+            match arg.as_str() {
+                "-h" | "--help" => return Err(CliError::Help),
+                "-o" | "--output" => parsed.output = Some(value(arg)?),
+                "--pretty" => parsed.pretty = true,
+                "--layer" => parsed.layer = Some(LayerId::parse(&value(arg)?)),
+                "--from" => parsed.from = Some(parse_time(arg, &value(arg)?)?),
+                "--to" => parsed.to = Some(parse_time(arg, &value(arg)?)?),
+                "-" => input = Some(arg.clone()),
+                _ if arg.starts_with('-') => {
+                    return Err(CliError::Usage(format!("unknown option `{arg}`")))
+                }
+                _ if parsed.command.is_none() => {
+                    let command = Command::parse(arg)
+                        .ok_or_else(|| CliError::Usage(format!("unknown command `{arg}`")))?;
+
+                    parsed.command = Some(command);
+                }
+                _ if input.is_none() => input = Some(arg.clone()),
+                _ => return Err(CliError::Usage(format!("unexpected argument `{arg}`"))),
+            }
+        }
+
+        if parsed.command.is_none() {
+            return Err(CliError::Usage("missing command".to_owned()));
+        }
+
+        parsed.input = input.ok_or_else(|| CliError::Usage("missing input".to_owned()))?;
+
+        Ok(parsed)
+    }
+}
+
+fn parse_time(name: &str, value: &str) -> Result<f64, CliError> {
+    value
+        .parse()
+        .map_err(|_| CliError::Usage(format!("invalid time `{value}` for `{name}`")))
+}
+
+fn run(args: &Args) -> Result<ExitCode, CliError> {
+    let input = read_input(&args.input)?;
+
+    let output = match args.command {
+        Some(Command::Stats) => stats(&decode(&input)?),
+        Some(Command::Validate) => return validate(args, &input),
+        Some(Command::Fmt) => decode(&input)?.encode_to_string()?,
+        Some(Command::ToJson) if args.pretty => decode(&input)?.to_json_pretty()?,
+        Some(Command::ToJson) => decode(&input)?.to_json()?,
+        Some(Command::FromJson) => {
+            let json = String::from_utf8(input).map_err(|_| CliError::Utf8)?;
+
+            Storyboard::from_json(&json)?.encode_to_string()?
+        }
+        Some(Command::Extract) => extract(args, decode(&input)?)?.encode_to_string()?,
+        None => unreachable!("checked while parsing arguments"),
+    };
+
+    write_output(args.output.as_deref(), &output)?;
+
+    Ok(ExitCode::SUCCESS)
+}
+
+fn read_input(path: &str) -> Result<Vec<u8>, CliError> {
+    if path == "-" {
+        let mut bytes = Vec::new();
+        io::stdin().read_to_end(&mut bytes)?;
+
+        Ok(bytes)
+    } else {
+        fs::read(path).map_err(CliError::from)
+    }
+}
+
+fn write_output(path: Option<&str>, output: &str) -> Result<(), CliError> {
+    match path {
+        Some(path) => fs::write(path, output)?,
+        None => io::stdout().write_all(output.as_bytes())?,
+    }
+
+    Ok(())
+}
+
+fn decode(input: &[u8]) -> Result<Storyboard, CliError> {
+    Storyboard::from_bytes(input).map_err(CliError::from)
+}
+
+fn validate(args: &Args, input: &[u8]) -> Result<ExitCode, CliError> {
+    let errors = Storyboard::validate_bytes(input);
+    let mut output = String::new();
+
+    for err in errors.iter() {
+        let _ = write!(output, "{}:{}: {}", args.input, err.line, err.error);
+
+        let mut source = error::Error::source(&err.error);
+
+        while let Some(err) = source {
+            let _ = write!(output, ": {err}");
+            source = err.source();
+        }
+
+        let _ = writeln!(output, "\n    {}", err.content);
+    }
+
+    write_output(args.output.as_deref(), &output)?;
+
+    if errors.is_empty() {
+        Ok(ExitCode::SUCCESS)
+    } else {
+        Ok(ExitCode::FAILURE)
+    }
+}
+
+fn stats(storyboard: &Storyboard) -> String {
+    let mut output = String::new();
+
+    match (
+        storyboard.earliest_event_time(),
+        storyboard.latest_event_time(),
+    ) {
+        (Some(start), Some(end)) => {
+            let _ = writeln!(output, "Time span: {start}ms - {end}ms ({}ms)", end - start);
+        }
+        _ => output.push_str("Time span: -\n"),
+    }
+
+    let mut layers: Vec<_> = storyboard
+        .layers
+        .iter()
+        .filter(|(_, layer)| !layer.elements.is_empty())
+        .collect();
+
+    // Back to front
+    layers.sort_by_key(|(_, layer)| -i64::from(layer.depth));
+
+    output.push_str("\nElements:\n");
+
+    for (name, layer) in layers {
+        let counts = ElementCounts::new(layer);
+
+        let _ = writeln!(
+            output,
+            "  {name}: {} ({} sprites, {} animations, {} samples, {} videos)",
+            layer.elements.len(),
+            counts.sprites,
+            counts.animations,
+            counts.samples,
+            counts.videos
+        );
+    }
+
+    let counts = CommandCounts::new(storyboard);
+
+    output.push_str("\nCommands:\n");
+
+    for (name, count) in counts.timelines {
+        if count > 0 {
+            let _ = writeln!(output, "  {name}: {count}");
+        }
+    }
+
+    let _ = writeln!(output, "  L: {}", counts.loops);
+    let _ = writeln!(output, "  T: {}", counts.triggers);
+
+    output
+}
+
+#[derive(Default)]
+struct ElementCounts {
+    sprites: usize,
+    animations: usize,
+    samples: usize,
+    videos: usize,
+}
+
+impl ElementCounts {
+    fn new(layer: &Layer) -> Self {
+        let mut counts = Self::default();
+
+        for elem in layer.elements.iter() {
+            match elem.kind {
+                ElementKind::Animation(_) => counts.animations += 1,
+                ElementKind::Sample(_) => counts.samples += 1,
+                ElementKind::Sprite(_) => counts.sprites += 1,
+                ElementKind::Video(_) => counts.videos += 1,
+            }
+        }
+
+        counts
+    }
+}
+
+struct CommandCounts {
+    timelines: [(&'static str, usize); 10],
+    loops: usize,
+    triggers: usize,
+}
+
+impl CommandCounts {
+    fn new(storyboard: &Storyboard) -> Self {
+        let mut counts = Self {
+            timelines: [
+                ("F", 0),
+                ("MX", 0),
+                ("MY", 0),
+                ("S", 0),
+                ("V", 0),
+                ("R", 0),
+                ("C", 0),
+                ("P", 0),
+                ("P,H", 0),
+                ("P,V", 0),
+            ],
+            loops: 0,
+            triggers: 0,
+        };
+
+        let sprites = storyboard
+            .layers
+            .values()
+            .flat_map(|layer| layer.elements.iter())
+            .filter_map(|elem| match elem.kind {
+                ElementKind::Animation(ref animation) => Some(&animation.sprite),
+                ElementKind::Sprite(ref sprite) => Some(sprite),
+                ElementKind::Sample(_) | ElementKind::Video(_) => None,
+            });
+
+        for sprite in sprites {
+            counts.loops += sprite.loops.len();
+            counts.triggers += sprite.triggers.len();
+
+            let groups = sprite
+                .loops
+                .iter()
+                .map(|l| &l.group)
+                .chain(sprite.triggers.iter().map(|trigger| &trigger.group))
+                .chain([&sprite.timeline_group]);
+
+            for group in groups {
+                counts.add(group);
+            }
+        }
+
+        counts
+    }
+
+    fn add(&mut self, group: &CommandTimelineGroup) {
+        let lens = [
+            group.alpha.len(),
+            group.x.len(),
+            group.y.len(),
+            group.scale.len(),
+            group.vector_scale.len(),
+            group.rotation.len(),
+            group.color.len(),
+            group.blending_parameters.len(),
+            group.flip_h.len(),
+            group.flip_v.len(),
+        ];
+
+        for ((_, count), len) in self.timelines.iter_mut().zip(lens) {
+            *count += len;
+        }
+    }
+}
+
+fn extract(args: &Args, mut storyboard: Storyboard) -> Result<Storyboard, CliError> {
+    if let Some(ref name) = args.layer {
+        if !storyboard.layers.contains_key(name) {
+            return Err(CliError::UnknownLayer(name.clone()));
+        }
+
+        // Keep the layers themselves so their settings remain intact
+        for (_, layer) in storyboard.layers.iter_mut().filter(|(n, _)| *n != name) {
+            layer.elements.clear();
+        }
+    }
+
+    let from = args.from.unwrap_or(f64::MIN);
+    let to = args.to.unwrap_or(f64::MAX);
+
+    for layer in storyboard.layers.values_mut() {
+        layer
+            .elements
+            .retain(|elem| elem.end_time_for_display() >= from && elem.start_time() <= to);
+    }
+
+    Ok(storyboard)
+}
+
+#[derive(Debug)]
+enum CliError {
+    Help,
+    Io(io::Error),
+    Json(JsonError),
+    UnknownLayer(LayerId),
+    Usage(String),
+    Utf8,
+}
+
+impl error::Error for CliError {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            CliError::Io(err) => Some(err),
+            CliError::Json(err) => Some(err),
+            CliError::Help | CliError::UnknownLayer(_) | CliError::Usage(_) | CliError::Utf8 => {
+                None
+            }
+        }
+    }
+}
+
+impl fmt::Display for CliError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CliError::Help => f.write_str("help requested"),
+            CliError::Io(_) => f.write_str("io error"),
+            CliError::Json(_) => f.write_str("json error"),
+            CliError::UnknownLayer(name) => write!(f, "unknown layer `{name}`"),
+            CliError::Usage(msg) => f.write_str(msg),
+            CliError::Utf8 => f.write_str("input is not valid UTF-8"),
+        }
+    }
+}
+
+impl From<io::Error> for CliError {
+    fn from(err: io::Error) -> Self {
+        Self::Io(err)
+    }
+}
+
+impl From<JsonError> for CliError {
+    fn from(err: JsonError) -> Self {
+        Self::Json(err)
+    }
+}
@@ -0,0 +1,105 @@
+use std::{
+    cmp::Reverse,
+    path::{Path, PathBuf},
+};
+
+use crate::{element::ElementKind, LayerId, PlayState, Storyboard};
+
+#[cfg(feature = "audio")]
+pub use self::pcm::{AudioError, MixOptions, MixedAudio, SkippedSample};
+
+#[cfg(feature = "audio")]
+mod pcm;
+
+/// All samples of a [`Storyboard`] as returned by [`Storyboard::mix_plan`].
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct MixPlan {
+    /// Sorted by start time.
+    pub samples: Vec<PlannedSample>,
+}
+
+/// A sample of a [`MixPlan`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct PlannedSample {
+    pub start_time: f64,
+    /// Path of the sample's file relative to the beatmap's directory.
+    pub path: String,
+    /// The sample's layer.
+    pub layer: LayerId,
+    /// Volume between `0.0` and `100.0`.
+    pub volume: f32,
+    /// Whether the sample plays while the player is passing.
+    pub plays_when_passing: bool,
+    /// Whether the sample plays while the player is failing.
+    pub plays_when_failing: bool,
+}
+
+impl PlannedSample {
+    /// Whether the sample plays for the given [`PlayState`] based on the
+    /// state at its start time.
+    pub fn plays(&self, play_state: &PlayState) -> bool {
+        if play_state.is_passing_at(self.start_time) {
+            self.plays_when_passing
+        } else {
+            self.plays_when_failing
+        }
+    }
+
+    /// The path of the sample's file within the given beatmap directory.
+    ///
+    /// Both `/` and `\` are treated as separators.
+    pub fn resolve(&self, dir: impl AsRef<Path>) -> PathBuf {
+        resolve(dir.as_ref(), &self.path)
+    }
+}
+
+fn resolve(dir: &Path, path: &str) -> PathBuf {
+    path.split(['/', '\\'])
+        .filter(|component| !component.is_empty())
+        .fold(dir.to_path_buf(), |path, component| path.join(component))
+}
+
+impl MixPlan {
+    /// All samples that play for the given [`PlayState`].
+    pub fn playing<'a>(
+        &'a self,
+        play_state: &'a PlayState,
+    ) -> impl Iterator<Item = &'a PlannedSample> {
+        self.samples
+            .iter()
+            .filter(move |sample| sample.plays(play_state))
+    }
+}
+
+impl Storyboard {
+    /// Collect all samples of the storyboard and determine whether they play
+    /// while passing or failing based on their layer's visibility.
+    pub fn mix_plan(&self) -> MixPlan {
+        let mut layers: Vec<_> = self.layers.iter().collect();
+        layers.sort_unstable_by_key(|(_, layer)| Reverse(layer.depth));
+
+        let mut samples: Vec<_> = layers
+            .into_iter()
+            .flat_map(|(name, layer)| {
+                layer.elements.iter().filter_map(move |elem| {
+                    let ElementKind::Sample(ref sample) = elem.kind else {
+                        return None;
+                    };
+
+                    Some(PlannedSample {
+                        start_time: sample.start_time,
+                        path: elem.path.clone(),
+                        layer: name.clone(),
+                        volume: sample.volume,
+                        plays_when_passing: layer.visible_when_passing,
+                        plays_when_failing: layer.visible_when_failing,
+                    })
+                })
+            })
+            .collect();
+
+        samples.sort_by(|a, b| a.start_time.total_cmp(&b.start_time));
+
+        MixPlan { samples }
+    }
+}
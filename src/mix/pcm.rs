@@ -0,0 +1,359 @@
+use std::{
+    collections::{hash_map::Entry, HashMap},
+    error, fmt,
+    fs::File,
+    io::{self, BufRead, BufReader, BufWriter, Read, Seek, Write},
+    path::Path,
+};
+
+use hound::{SampleFormat, WavReader, WavSpec, WavWriter};
+use lewton::{inside_ogg::OggStreamReader, VorbisError};
+
+use crate::PlayState;
+
+use super::MixPlan;
+
+/// Options for [`MixPlan::mix`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct MixOptions {
+    /// Sample rate of the mixed audio in Hz.
+    pub sample_rate: u32,
+    /// Whether the player is passing or failing over time.
+    ///
+    /// Samples that don't play in the state at their start time are skipped.
+    pub play_state: PlayState,
+    /// Path of the song relative to the beatmap's directory.
+    ///
+    /// If specified, the song is mixed in at full volume starting at `0ms`
+    /// so that samples can be heard in context. Note that only WAV and Ogg
+    /// Vorbis songs are supported.
+    pub song: Option<String>,
+    /// Maximum duration of the mixed audio in milliseconds.
+    ///
+    /// Samples that start later are skipped and samples that would end later
+    /// are cut off.
+    pub max_duration: f64,
+}
+
+impl Default for MixOptions {
+    fn default() -> Self {
+        Self {
+            sample_rate: 44_100,
+            play_state: PlayState::passing(),
+            song: None,
+            max_duration: 20.0 * 60.0 * 1000.0,
+        }
+    }
+}
+
+/// Stereo PCM audio as mixed by [`MixPlan::mix`].
+#[derive(Debug, Default)]
+pub struct MixedAudio {
+    pub sample_rate: u32,
+    /// Time in milliseconds of the first frame.
+    ///
+    /// This is `0.0` unless a sample starts at a negative time.
+    pub start_time: f64,
+    /// Interleaved values of the left and right channel.
+    ///
+    /// Values are not clamped so overlapping samples may exceed the range
+    /// from `-1.0` to `1.0`.
+    pub pcm: Vec<f32>,
+    /// Files that could not be loaded and samples that start after
+    /// [`MixOptions::max_duration`].
+    pub skipped: Vec<SkippedSample>,
+}
+
+impl MixedAudio {
+    /// The amount of stereo frames.
+    pub const fn frame_count(&self) -> usize {
+        self.pcm.len() / 2
+    }
+
+    /// The duration of the audio in milliseconds.
+    pub fn duration(&self) -> f64 {
+        if self.sample_rate == 0 {
+            return 0.0;
+        }
+
+        self.frame_count() as f64 * 1000.0 / f64::from(self.sample_rate)
+    }
+
+    /// Write the audio as 16-bit stereo WAV.
+    ///
+    /// Values are clamped to the range from `-1.0` to `1.0`. Fails if the
+    /// audio is too long to fit into a WAV file.
+    pub fn write_wav<W: Write + Seek>(&self, writer: W) -> Result<(), hound::Error> {
+        // The RIFF chunk's size includes 36 bytes of headers
+        let len = u32::try_from(self.pcm.len())
+            .ok()
+            .filter(|&len| u64::from(len) * 2 <= u64::from(u32::MAX) - 36)
+            .ok_or_else(|| {
+                hound::Error::IoError(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    "audio is too long for wav",
+                ))
+            })?;
+
+        let spec = WavSpec {
+            channels: 2,
+            sample_rate: self.sample_rate,
+            bits_per_sample: 16,
+            sample_format: SampleFormat::Int,
+        };
+
+        let mut writer = WavWriter::new(writer, spec)?;
+        let mut samples = writer.get_i16_writer(len);
+
+        for value in self.pcm.iter() {
+            samples.write_sample((value.clamp(-1.0, 1.0) * f32::from(i16::MAX)) as i16);
+        }
+
+        samples.flush()?;
+
+        writer.finalize()
+    }
+
+    /// Write the audio as 16-bit stereo WAV into a file.
+    ///
+    /// See [`MixedAudio::write_wav`].
+    pub fn save_wav(&self, path: impl AsRef<Path>) -> Result<(), hound::Error> {
+        let file = BufWriter::new(File::create(path)?);
+
+        self.write_wav(file)
+    }
+}
+
+/// A sample file that could not be loaded while mixing.
+#[derive(Debug)]
+pub struct SkippedSample {
+    /// Path of the file relative to the beatmap's directory.
+    pub path: String,
+    pub error: AudioError,
+}
+
+/// All the ways that loading a sample file can fail.
+#[derive(Debug)]
+pub enum AudioError {
+    Io(io::Error),
+    Wav(hound::Error),
+    Vorbis(VorbisError),
+    /// The file is neither WAV nor Ogg Vorbis.
+    UnsupportedFormat,
+    /// The sample starts after [`MixOptions::max_duration`].
+    TooLate,
+}
+
+impl error::Error for AudioError {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            AudioError::Io(err) => Some(err),
+            AudioError::Wav(err) => Some(err),
+            AudioError::Vorbis(err) => Some(err),
+            AudioError::UnsupportedFormat | AudioError::TooLate => None,
+        }
+    }
+}
+
+impl fmt::Display for AudioError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let s = match self {
+            Self::Io(_) => "failed to read file",
+            Self::Wav(_) => "failed to decode wav",
+            Self::Vorbis(_) => "failed to decode ogg",
+            Self::UnsupportedFormat => "unsupported audio format",
+            Self::TooLate => "sample starts after the maximum duration",
+        };
+
+        f.write_str(s)
+    }
+}
+
+impl From<io::Error> for AudioError {
+    fn from(err: io::Error) -> Self {
+        Self::Io(err)
+    }
+}
+
+impl From<hound::Error> for AudioError {
+    fn from(err: hound::Error) -> Self {
+        Self::Wav(err)
+    }
+}
+
+impl From<VorbisError> for AudioError {
+    fn from(err: VorbisError) -> Self {
+        Self::Vorbis(err)
+    }
+}
+
+impl MixPlan {
+    /// Mix the WAV and Ogg Vorbis files of all playing samples into a single
+    /// stereo PCM buffer, e.g. to preview the storyboard's audio.
+    ///
+    /// Files are resolved within the given beatmap directory and loaded only
+    /// once. Each sample is scaled by its volume.
+    ///
+    /// Use [`MixedAudio::save_wav`] to listen to the result.
+    pub fn mix(&self, dir: impl AsRef<Path>, options: &MixOptions) -> MixedAudio {
+        let dir = dir.as_ref();
+        let sample_rate = options.sample_rate;
+
+        let song = options.song.as_deref().map(|path| (0.0, path, 100.0));
+
+        let playing: Vec<_> = self
+            .playing(&options.play_state)
+            .map(|sample| (sample.start_time, sample.path.as_str(), sample.volume))
+            .collect();
+
+        let start_time = playing.first().map_or(0.0, |(time, ..)| time.min(0.0));
+        let max_frames = (options.max_duration.max(0.0) * f64::from(sample_rate) / 1000.0).floor();
+        let max_len = (2.0 * max_frames) as usize;
+
+        let mut files = HashMap::new();
+        let mut skipped = Vec::new();
+        let mut pcm = Vec::new();
+
+        for (time, path, volume) in song.into_iter().chain(playing) {
+            let frame = (time - start_time) * f64::from(sample_rate) / 1000.0;
+
+            if frame.round() >= max_frames {
+                skipped.push(SkippedSample {
+                    path: path.to_owned(),
+                    error: AudioError::TooLate,
+                });
+
+                continue;
+            }
+
+            let values = match files.entry(path) {
+                Entry::Occupied(entry) => entry.into_mut(),
+                Entry::Vacant(entry) => match load(&super::resolve(dir, path), sample_rate) {
+                    Ok(values) => entry.insert(Some(values)),
+                    Err(error) => {
+                        skipped.push(SkippedSample {
+                            path: path.to_owned(),
+                            error,
+                        });
+
+                        entry.insert(None)
+                    }
+                },
+            };
+
+            let Some(values) = values else {
+                continue;
+            };
+
+            let offset = 2 * frame.round() as usize;
+            let end = (offset + values.len()).min(max_len);
+
+            if pcm.len() < end {
+                pcm.resize(end, 0.0);
+            }
+
+            let volume = volume / 100.0;
+
+            for (mixed, value) in pcm[offset..end].iter_mut().zip(values.iter()) {
+                *mixed += value * volume;
+            }
+        }
+
+        MixedAudio {
+            sample_rate,
+            start_time,
+            pcm,
+            skipped,
+        }
+    }
+}
+
+/// Load a file as interleaved stereo values with the given sample rate.
+fn load(path: &Path, sample_rate: u32) -> Result<Vec<f32>, AudioError> {
+    let mut reader = BufReader::new(File::open(path)?);
+    let magic = reader.fill_buf()?;
+
+    let (channels, source_rate, values) = if magic.starts_with(b"RIFF") {
+        decode_wav(reader)?
+    } else if magic.starts_with(b"OggS") {
+        decode_ogg(reader)?
+    } else {
+        return Err(AudioError::UnsupportedFormat);
+    };
+
+    let stereo = to_stereo(&values, channels);
+
+    Ok(resample(&stereo, source_rate, sample_rate))
+}
+
+fn decode_wav<R: Read>(reader: R) -> Result<(usize, u32, Vec<f32>), AudioError> {
+    let reader = WavReader::new(reader)?;
+    let spec = reader.spec();
+
+    let values = match spec.sample_format {
+        SampleFormat::Float => reader.into_samples::<f32>().collect::<Result<_, _>>()?,
+        SampleFormat::Int => {
+            let scale = 1.0 / (1_i64 << (spec.bits_per_sample - 1)) as f32;
+
+            reader
+                .into_samples::<i32>()
+                .map(|value| value.map(|value| value as f32 * scale))
+                .collect::<Result<_, _>>()?
+        }
+    };
+
+    Ok((usize::from(spec.channels), spec.sample_rate, values))
+}
+
+fn decode_ogg<R: Read + Seek>(reader: R) -> Result<(usize, u32, Vec<f32>), AudioError> {
+    let mut reader = OggStreamReader::new(reader)?;
+    let channels = usize::from(reader.ident_hdr.audio_channels);
+    let sample_rate = reader.ident_hdr.audio_sample_rate;
+    let mut values = Vec::new();
+
+    while let Some(packet) = reader.read_dec_packet_itl()? {
+        values.extend(packet.into_iter().map(|value| f32::from(value) / 32_768.0));
+    }
+
+    Ok((channels, sample_rate, values))
+}
+
+/// Duplicate mono audio and drop all channels but the first two otherwise.
+fn to_stereo(values: &[f32], channels: usize) -> Vec<f32> {
+    match channels {
+        0 => Vec::new(),
+        1 => values.iter().flat_map(|&value| [value, value]).collect(),
+        2 => values.to_vec(),
+        _ => values
+            .chunks_exact(channels)
+            .flat_map(|frame| [frame[0], frame[1]])
+            .collect(),
+    }
+}
+
+/// Linearly interpolate stereo frames to a different sample rate.
+fn resample(stereo: &[f32], from: u32, to: u32) -> Vec<f32> {
+    if from == to || from == 0 || stereo.is_empty() {
+        return stereo.to_vec();
+    }
+
+    let frames = stereo.len() / 2;
+    let ratio = f64::from(from) / f64::from(to);
+    let len = (frames as f64 / ratio).round() as usize;
+    let mut resampled = Vec::with_capacity(len * 2);
+
+    for i in 0..len {
+        let pos = i as f64 * ratio;
+        let idx = (pos as usize).min(frames - 1);
+        let next = (idx + 1).min(frames - 1);
+        let progress = (pos - idx as f64) as f32;
+
+        for channel in 0..2 {
+            let start = stereo[idx * 2 + channel];
+            let end = stereo[next * 2 + channel];
+            resampled.push(start + (end - start) * progress);
+        }
+    }
+
+    resampled
+}
@@ -0,0 +1,180 @@
+use std::{borrow::Cow, cmp::Reverse, fs, io, ops::Range, path::Path};
+
+use rayon::prelude::*;
+use rosu_map::{
+    section::{events::EventType, Section},
+    util::StrExt,
+    DecodeBeatmap, DecodeState,
+};
+
+use crate::{reader::Encoding, validate::section_lines, Storyboard, StoryboardState};
+
+/// Minimum amount of event lines per chunk so that small storyboards are not
+/// split into tiny chunks.
+const MIN_CHUNK_LEN: usize = 1024;
+
+impl Storyboard {
+    /// Decode a [`Storyboard`] from a path, decoding events on multiple
+    /// threads.
+    ///
+    /// The resulting storyboard is the same as with [`Storyboard::from_path`].
+    pub fn from_path_parallel(path: impl AsRef<Path>) -> Result<Self, io::Error> {
+        fs::read(path).map(|bytes| Self::from_bytes_parallel(&bytes))
+    }
+
+    /// Decode a [`Storyboard`] from bytes, decoding events on multiple
+    /// threads.
+    ///
+    /// The resulting storyboard is the same as with [`Storyboard::from_bytes`].
+    pub fn from_bytes_parallel(bytes: &[u8]) -> Self {
+        let (encoding, bom_len) = Encoding::from_bom(bytes);
+
+        Self::from_str_parallel(&encoding.decode(&bytes[bom_len..]))
+    }
+
+    /// Decode a [`Storyboard`] from a string, decoding events on multiple
+    /// threads.
+    ///
+    /// The `[Events]` section is split at top-level sprites and animations
+    /// into chunks which are decoded concurrently after their variables have
+    /// been expanded. The resulting storyboard is the same as when parsing the
+    /// string through [`FromStr`].
+    ///
+    /// [`FromStr`]: std::str::FromStr
+    pub fn from_str_parallel(s: &str) -> Self {
+        let (version, lines) = section_lines(s);
+        let mut state = StoryboardState::create(version);
+        let mut events = Vec::new();
+        let mut sprite_starts = Vec::new();
+
+        for (_, section, line) in lines {
+            // Errors are ignored just like in regular decoding
+            let _ = match section {
+                Section::General => Self::parse_general(&mut state, line),
+                Section::Variables => Self::parse_variables(&mut state, line),
+                Section::Events => {
+                    let line = state.decode_variables(line.trim_comment());
+
+                    if starts_sprite(&line) {
+                        sprite_starts.push(events.len());
+                    }
+
+                    events.push(line);
+
+                    Ok(())
+                }
+                _ => Ok(()),
+            };
+        }
+
+        let mut storyboard = Self::from(state);
+        let chunks = split_chunks(events.len(), &sprite_starts);
+
+        let mut decoded: Vec<_> = chunks
+            .par_iter()
+            .map(|range| Chunk::decode(version, &events[range.clone()]))
+            .collect();
+
+        // Commands that would continue a group of the previous chunk can only
+        // be handled sequentially
+        if decoded.iter().skip(1).any(|chunk| chunk.continues_group) {
+            decoded = vec![Chunk::decode(version, &events)];
+        }
+
+        for chunk in decoded {
+            chunk.append_to(&mut storyboard);
+        }
+
+        storyboard
+    }
+}
+
+/// The decoded events of a range of event lines.
+struct Chunk {
+    storyboard: Storyboard,
+    explicit_background: bool,
+    /// Whether commands were skipped because no group was available.
+    continues_group: bool,
+}
+
+impl Chunk {
+    fn decode(version: i32, lines: &[Cow<'_, str>]) -> Self {
+        let mut state = StoryboardState::create(version);
+        let mut continues_group = false;
+
+        for line in lines {
+            let _ = state.parse_event(line);
+
+            continues_group |= line.starts_with([' ', '_']) && !state.has_timeline_group();
+        }
+
+        Self {
+            explicit_background: state.explicit_background(),
+            continues_group,
+            storyboard: state.into(),
+        }
+    }
+
+    fn append_to(self, storyboard: &mut Storyboard) {
+        let Self {
+            storyboard: chunk,
+            explicit_background,
+            ..
+        } = self;
+
+        // Sprites only set the background file if it's still empty
+        if explicit_background || storyboard.background_file.is_empty() {
+            storyboard.background_file = chunk.background_file;
+        }
+
+        if explicit_background {
+            storyboard.background_offset = chunk.background_offset;
+        }
+
+        storyboard.breaks.extend(chunk.breaks);
+        storyboard.background_colors.extend(chunk.background_colors);
+
+        // Adding layers in the order of their creation keeps their depth
+        let mut layers: Vec<_> = chunk.layers.into_iter().collect();
+        layers.sort_unstable_by_key(|(_, layer)| Reverse(layer.depth));
+
+        for (name, layer) in layers {
+            if !layer.elements.is_empty() {
+                storyboard
+                    .get_or_insert_layer(name)
+                    .elements
+                    .extend(layer.elements);
+            }
+        }
+    }
+}
+
+fn starts_sprite(line: &str) -> bool {
+    if line.starts_with([' ', '_']) {
+        return false;
+    }
+
+    matches!(
+        line.split(',').next().map(str::parse),
+        Some(Ok(EventType::Sprite | EventType::Animation))
+    )
+}
+
+/// Split event lines into ranges that each start with a sprite, except for
+/// the first one.
+fn split_chunks(len: usize, sprite_starts: &[usize]) -> Vec<Range<usize>> {
+    let chunk_len = MIN_CHUNK_LEN.max(len / (rayon::current_num_threads() * 4));
+    let mut chunks = Vec::new();
+    let mut start = 0;
+
+    for &sprite_start in sprite_starts {
+        if sprite_start - start >= chunk_len {
+            chunks.push(start..sprite_start);
+            start = sprite_start;
+        }
+    }
+
+    chunks.push(start..len);
+
+    chunks
+}
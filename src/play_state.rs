@@ -0,0 +1,147 @@
+use std::cmp::Reverse;
+
+use rosu_map::section::events::BreakPeriod;
+
+use crate::{Layer, LayerId, Storyboard};
+
+/// Whether the player is passing or failing over the course of a beatmap.
+///
+/// Determines which [`Layer`]s are visible, see
+/// [`Layer::visible_when_passing`] and [`Layer::visible_when_failing`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct PlayState {
+    initial_passing: bool,
+    /// Sorted by time.
+    transitions: Vec<PlayStateTransition>,
+}
+
+/// A switch between passing and failing.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct PlayStateTransition {
+    pub time: f64,
+    pub passing: bool,
+}
+
+impl PlayState {
+    /// Create a new [`PlayState`] from its initial state and a list of
+    /// transitions.
+    pub fn new<I>(initial_passing: bool, transitions: I) -> Self
+    where
+        I: IntoIterator<Item = PlayStateTransition>,
+    {
+        let mut transitions: Vec<_> = transitions.into_iter().collect();
+        transitions.sort_by(|a, b| a.time.total_cmp(&b.time));
+
+        Self {
+            initial_passing,
+            transitions,
+        }
+    }
+
+    /// A [`PlayState`] that is passing throughout.
+    pub const fn passing() -> Self {
+        Self {
+            initial_passing: true,
+            transitions: Vec::new(),
+        }
+    }
+
+    /// A [`PlayState`] that is failing throughout.
+    pub const fn failing() -> Self {
+        Self {
+            initial_passing: false,
+            transitions: Vec::new(),
+        }
+    }
+
+    /// Create a [`PlayState`] the way osu! does: the player starts off
+    /// passing and the state is only re-evaluated at the start of each break.
+    ///
+    /// `outcomes` specifies whether the player is passing for each break. If
+    /// it contains fewer items than there are breaks, the remaining breaks
+    /// keep the previous state.
+    pub fn from_breaks<I>(breaks: &[BreakPeriod], outcomes: I) -> Self
+    where
+        I: IntoIterator<Item = bool>,
+    {
+        let transitions = breaks
+            .iter()
+            .zip(outcomes)
+            .map(|(b, passing)| PlayStateTransition {
+                time: b.start_time,
+                passing,
+            });
+
+        Self::new(true, transitions)
+    }
+
+    /// Whether the player is passing at the given time.
+    pub fn is_passing_at(&self, time: f64) -> bool {
+        let idx = self.transitions.partition_point(|t| t.time <= time);
+
+        match idx.checked_sub(1) {
+            Some(idx) => self.transitions[idx].passing,
+            None => self.initial_passing,
+        }
+    }
+
+    /// Whether the player is passing before the first transition.
+    pub const fn initial_passing(&self) -> bool {
+        self.initial_passing
+    }
+
+    /// All transitions sorted by time.
+    pub fn transitions(&self) -> &[PlayStateTransition] {
+        &self.transitions
+    }
+}
+
+impl Default for PlayState {
+    fn default() -> Self {
+        Self::passing()
+    }
+}
+
+impl Storyboard {
+    /// The [`PlayState`] of a player that passes every break.
+    pub const fn passing_play_state(&self) -> PlayState {
+        PlayState::passing()
+    }
+
+    /// The [`PlayState`] of a player that fails every break.
+    ///
+    /// Since osu! only switches states at breaks, the player is considered
+    /// passing until the first break.
+    pub fn failing_play_state(&self) -> PlayState {
+        PlayState::from_breaks(&self.breaks, self.breaks.iter().map(|_| false))
+    }
+
+    /// The [`PlayState`] for the given outcomes of each break, see
+    /// [`PlayState::from_breaks`].
+    pub fn play_state<I>(&self, outcomes: I) -> PlayState
+    where
+        I: IntoIterator<Item = bool>,
+    {
+        PlayState::from_breaks(&self.breaks, outcomes)
+    }
+
+    /// All [`Layer`]s that are visible at the given time, sorted by their
+    /// depth in descending order i.e. in the order they are drawn.
+    pub fn visible_layers_at<'a>(
+        &'a self,
+        time: f64,
+        state: &PlayState,
+    ) -> impl Iterator<Item = (&'a LayerId, &'a Layer)> {
+        let passing = state.is_passing_at(time);
+
+        let mut layers: Vec<_> = self
+            .layers
+            .iter()
+            .filter(|(_, layer)| layer.is_visible(passing))
+            .collect();
+
+        layers.sort_by_key(|(_, layer)| Reverse(layer.depth));
+
+        layers.into_iter()
+    }
+}
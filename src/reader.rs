@@ -0,0 +1,101 @@
+use std::{
+    borrow::Cow,
+    io::{BufRead, ErrorKind, Result as IoResult},
+    slice,
+};
+
+/// Text encoding of an osu! file as indicated by its byte order mark.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub(crate) enum Encoding {
+    #[default]
+    Utf8,
+    Utf16LE,
+    Utf16BE,
+}
+
+impl Encoding {
+    /// Determine the encoding and the length of the byte order mark.
+    pub const fn from_bom(bytes: &[u8]) -> (Self, usize) {
+        match bytes {
+            [0xEF, 0xBB, 0xBF, ..] => (Self::Utf8, 3),
+            [0xFF, 0xFE, ..] => (Self::Utf16LE, 2),
+            [0xFE, 0xFF, ..] => (Self::Utf16BE, 2),
+            _ => (Self::Utf8, 0),
+        }
+    }
+
+    /// Decode bytes the same way as `rosu-map`, i.e. replacing invalid
+    /// characters.
+    pub fn decode(self, bytes: &[u8]) -> Cow<'_, str> {
+        fn decode_utf16(bytes: &[u8], from_bytes: fn([u8; 2]) -> u16) -> Cow<'_, str> {
+            let units = bytes
+                .chunks_exact(2)
+                .map(|pair| from_bytes([pair[0], pair[1]]));
+
+            char::decode_utf16(units)
+                .map(|ch| ch.unwrap_or(char::REPLACEMENT_CHARACTER))
+                .collect()
+        }
+
+        match self {
+            Self::Utf8 => String::from_utf8_lossy(bytes),
+            Self::Utf16LE => decode_utf16(bytes, u16::from_le_bytes),
+            Self::Utf16BE => decode_utf16(bytes, u16::from_be_bytes),
+        }
+    }
+}
+
+/// Reads lines of an osu! file one by one.
+pub(crate) struct LineReader<R> {
+    inner: R,
+    buf: Vec<u8>,
+    encoding: Encoding,
+}
+
+impl<R: BufRead> LineReader<R> {
+    pub fn new(mut inner: R) -> IoResult<Self> {
+        let bom = loop {
+            match inner.fill_buf() {
+                Ok(buf) => break buf,
+                Err(err) if err.kind() == ErrorKind::Interrupted => {}
+                Err(err) => return Err(err),
+            }
+        };
+
+        let (encoding, len) = Encoding::from_bom(bom);
+        inner.consume(len);
+
+        Ok(Self {
+            inner,
+            buf: Vec::new(),
+            encoding,
+        })
+    }
+
+    /// Read the next line without trailing whitespace.
+    pub fn read_line(&mut self) -> IoResult<Option<Cow<'_, str>>> {
+        self.buf.clear();
+
+        if self.inner.read_until(b'\n', &mut self.buf)? == 0 {
+            return Ok(None);
+        }
+
+        // Reading up to b'\n' misses the final b'\0' of UTF-16LE
+        if self.encoding == Encoding::Utf16LE && self.buf.ends_with(b"\n") {
+            let mut byte = 0;
+            self.inner.read_exact(slice::from_mut(&mut byte))?;
+            self.buf.push(byte);
+        }
+
+        let line = match self.encoding.decode(&self.buf) {
+            Cow::Borrowed(line) => Cow::Borrowed(line.trim_end()),
+            Cow::Owned(mut line) => {
+                line.truncate(line.trim_end().len());
+
+                Cow::Owned(line)
+            }
+        };
+
+        Ok(Some(line))
+    }
+}
@@ -0,0 +1,223 @@
+use rosu_map::{
+    section::{colors::Color, events::BreakPeriod},
+    util::Pos,
+};
+use serde::{
+    de::{DeserializeOwned, Deserializer},
+    ser::{SerializeSeq, Serializer},
+    Deserialize, Serialize,
+};
+
+use crate::{
+    command::{CommandTimeline, TypedCommand},
+    layer::LayerId,
+    visual::{BlendingParameters, Easing},
+};
+
+/// `{ "x": f32, "y": f32 }`
+#[derive(Serialize, Deserialize)]
+pub struct PosRepr {
+    x: f32,
+    y: f32,
+}
+
+impl From<Pos> for PosRepr {
+    fn from(pos: Pos) -> Self {
+        Self { x: pos.x, y: pos.y }
+    }
+}
+
+impl From<PosRepr> for Pos {
+    fn from(pos: PosRepr) -> Self {
+        Pos::new(pos.x, pos.y)
+    }
+}
+
+/// `{ "start_time": f64, "end_time": f64 }`
+#[derive(Serialize, Deserialize)]
+struct BreakPeriodRepr {
+    start_time: f64,
+    end_time: f64,
+}
+
+pub(crate) mod pos {
+    use super::{Deserialize, Deserializer, Pos, PosRepr, Serialize, Serializer};
+
+    // Signature required by `#[serde(with = "...")]`
+    #[allow(clippy::trivially_copy_pass_by_ref)]
+    pub fn serialize<S: Serializer>(pos: &Pos, s: S) -> Result<S::Ok, S::Error> {
+        PosRepr::from(*pos).serialize(s)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(d: D) -> Result<Pos, D::Error> {
+        PosRepr::deserialize(d).map(Pos::from)
+    }
+}
+
+/// `[red, green, blue, alpha]`
+pub(crate) mod color {
+    use super::{Color, Deserialize, Deserializer, Serialize, Serializer};
+
+    // Signature required by `#[serde(with = "...")]`
+    #[allow(clippy::trivially_copy_pass_by_ref)]
+    pub fn serialize<S: Serializer>(color: &Color, s: S) -> Result<S::Ok, S::Error> {
+        color.0.serialize(s)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(d: D) -> Result<Color, D::Error> {
+        <[u8; 4]>::deserialize(d).map(Color)
+    }
+}
+
+pub(crate) mod breaks {
+    use super::{
+        BreakPeriod, BreakPeriodRepr, Deserialize, Deserializer, SerializeSeq, Serializer,
+    };
+
+    pub fn serialize<S: Serializer>(breaks: &[BreakPeriod], s: S) -> Result<S::Ok, S::Error> {
+        let mut seq = s.serialize_seq(Some(breaks.len()))?;
+
+        for b in breaks {
+            seq.serialize_element(&BreakPeriodRepr {
+                start_time: b.start_time,
+                end_time: b.end_time,
+            })?;
+        }
+
+        seq.end()
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(d: D) -> Result<Vec<BreakPeriod>, D::Error> {
+        let breaks = Vec::<BreakPeriodRepr>::deserialize(d)?
+            .into_iter()
+            .map(|b| BreakPeriod {
+                start_time: b.start_time,
+                end_time: b.end_time,
+            })
+            .collect();
+
+        Ok(breaks)
+    }
+}
+
+/// Values of a [`CommandTimeline`] that can be (de)serialized.
+pub trait SerdeValue: Copy {
+    type Repr: Serialize + DeserializeOwned;
+
+    fn to_repr(self) -> Self::Repr;
+    fn from_repr(repr: Self::Repr) -> Self;
+}
+
+macro_rules! serde_value_identity {
+    ( $( $ty:ty ),* ) => {
+        $(
+            impl SerdeValue for $ty {
+                type Repr = Self;
+
+                fn to_repr(self) -> Self::Repr {
+                    self
+                }
+
+                fn from_repr(repr: Self::Repr) -> Self {
+                    repr
+                }
+            }
+        )*
+    };
+}
+
+serde_value_identity!(f32, bool, BlendingParameters);
+
+impl SerdeValue for Pos {
+    type Repr = PosRepr;
+
+    fn to_repr(self) -> Self::Repr {
+        self.into()
+    }
+
+    fn from_repr(repr: Self::Repr) -> Self {
+        repr.into()
+    }
+}
+
+/// `[red, green, blue, alpha]`
+impl SerdeValue for Color {
+    type Repr = [u8; 4];
+
+    fn to_repr(self) -> Self::Repr {
+        self.0
+    }
+
+    fn from_repr(repr: Self::Repr) -> Self {
+        Color(repr)
+    }
+}
+
+/// A layer id is represented as its name.
+impl Serialize for LayerId {
+    fn serialize<S: Serializer>(&self, s: S) -> Result<S::Ok, S::Error> {
+        s.serialize_str(self.as_str())
+    }
+}
+
+impl<'de> Deserialize<'de> for LayerId {
+    fn deserialize<D: Deserializer<'de>>(d: D) -> Result<Self, D::Error> {
+        String::deserialize(d).map(Self::from)
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct CommandRepr<T> {
+    easing: Easing,
+    start_time: f64,
+    end_time: f64,
+    start_value: T,
+    end_value: T,
+}
+
+/// A timeline is represented as the list of its commands. Cached fields are
+/// recomputed when deserializing.
+impl<T: SerdeValue> Serialize for CommandTimeline<T> {
+    fn serialize<S: Serializer>(&self, s: S) -> Result<S::Ok, S::Error> {
+        let mut seq = s.serialize_seq(Some(self.commands.len()))?;
+
+        for command in self.commands.iter() {
+            let TypedCommand {
+                easing,
+                start_time,
+                end_time,
+                start_value,
+                end_value,
+            } = *command;
+
+            seq.serialize_element(&CommandRepr {
+                easing,
+                start_time,
+                end_time,
+                start_value: start_value.to_repr(),
+                end_value: end_value.to_repr(),
+            })?;
+        }
+
+        seq.end()
+    }
+}
+
+impl<'de, T: SerdeValue + Default> Deserialize<'de> for CommandTimeline<T> {
+    fn deserialize<D: Deserializer<'de>>(d: D) -> Result<Self, D::Error> {
+        let commands = Vec::<CommandRepr<T::Repr>>::deserialize(d)?;
+        let mut timeline = Self::new();
+
+        for command in commands {
+            timeline.add(
+                command.easing,
+                command.start_time,
+                command.end_time,
+                T::from_repr(command.start_value),
+                T::from_repr(command.end_value),
+            );
+        }
+
+        Ok(timeline)
+    }
+}
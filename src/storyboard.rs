@@ -1,23 +1,37 @@
 use std::{collections::HashMap, io, path::Path, str::FromStr};
 
 use rosu_map::{
-    section::events::{BreakPeriod, Events},
+    section::{
+        colors::Color,
+        events::{BreakPeriod, Events},
+    },
+    util::Pos,
     LATEST_FORMAT_VERSION,
 };
 
 use crate::{
+    command::Interpolate,
     element::{Element, ElementKind},
-    layer::{Layer, LayerInternal},
+    layer::{Layer, LayerId},
 };
 
 /// The storyboard of a beatmap.
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Storyboard {
     pub format_version: i32,
     pub use_skin_sprites: bool,
     pub background_file: String,
+    /// Offset of the background as specified by its event.
+    #[cfg_attr(feature = "serde", serde(default, with = "crate::serde_util::pos"))]
+    pub background_offset: Pos,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_util::breaks"))]
     pub breaks: Vec<BreakPeriod>,
-    pub layers: HashMap<String, Layer>,
+    /// Legacy background color transitions in the order of their events.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub background_colors: Vec<BackgroundColor>,
+    pub layers: HashMap<LayerId, Layer>,
+    #[cfg_attr(feature = "serde", serde(default))]
     pub(crate) min_layer_depth: i32,
 }
 
@@ -30,29 +44,38 @@ impl Storyboard {
         rosu_map::from_path(path)
     }
 
-    /// Return the [`Layer`] with the given name.
+    /// Return the [`Layer`] with the given id.
     ///
-    /// If no layer has that name, a new one is created.
+    /// If the storyboard doesn't have that layer, return `None`. Layers are
+    /// never created this way so a misspelled custom layer can't add a new
+    /// one.
+    pub fn get_layer(&mut self, id: &LayerId) -> Option<&mut Layer> {
+        self.layers.get_mut(id)
+    }
+
+    /// Return the [`Layer`] with the given id.
+    ///
+    /// If the storyboard doesn't have that layer, return `None`.
+    pub fn try_get_layer(&self, id: &LayerId) -> Option<&Layer> {
+        self.layers.get(id)
+    }
+
+    /// Return the [`Layer`] with the given id.
+    ///
+    /// If the storyboard doesn't have that layer yet, a new one is created.
     // false positive
     #[allow(clippy::missing_panics_doc)]
-    pub fn get_layer(&mut self, name: &str) -> &mut Layer {
+    pub(crate) fn get_or_insert_layer(&mut self, id: LayerId) -> &mut Layer {
         // Workaround for NLL
         // See <https://github.com/rust-lang/rust/issues/43234>
-        if self.layers.contains_key(name) {
-            return self.layers.get_mut(name).unwrap();
+        if self.layers.contains_key(&id) {
+            return self.layers.get_mut(&id).unwrap();
         }
 
         self.min_layer_depth -= 1;
         let layer = Layer::new(self.min_layer_depth, true);
 
-        self.layers.entry(name.to_owned()).or_insert(layer)
-    }
-
-    /// Return the [`Layer`] with the given name.
-    ///
-    /// If no layer has that name, return `None`.
-    pub fn try_get_layer(&self, name: &str) -> Option<&Layer> {
-        self.layers.get(name)
+        self.layers.entry(id).or_insert(layer)
     }
 
     pub fn earliest_event_time(&self) -> Option<f64> {
@@ -87,30 +110,76 @@ impl Storyboard {
             .flat_map(|layer| layer.elements.iter())
             .any(Element::is_drawable)
     }
+
+    /// The background color at the given time based on the storyboard's
+    /// [`BackgroundColor`]s.
+    ///
+    /// The color transitions linearly from one event to the next. Before the
+    /// first event and after the last one, their color is used. Returns `None`
+    /// if there are no background color events.
+    pub fn background_color_at(&self, time: f64) -> Option<Color> {
+        let prev = self
+            .background_colors
+            .iter()
+            .filter(|event| event.time <= time)
+            .max_by(|a, b| a.time.total_cmp(&b.time));
+
+        let next = self
+            .background_colors
+            .iter()
+            .filter(|event| event.time > time)
+            .min_by(|a, b| a.time.total_cmp(&b.time));
+
+        match (prev, next) {
+            (Some(prev), Some(next)) => {
+                let progress = (time - prev.time) / (next.time - prev.time);
+
+                Some(Color::interpolate(prev.color, next.color, progress))
+            }
+            (Some(event), None) | (None, Some(event)) => Some(event.color),
+            (None, None) => None,
+        }
+    }
+}
+
+/// A legacy background color event of a [`Storyboard`].
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct BackgroundColor {
+    pub time: f64,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_util::color"))]
+    pub color: Color,
+}
+
+impl BackgroundColor {
+    /// Create a new [`BackgroundColor`].
+    pub const fn new(time: f64, color: Color) -> Self {
+        Self { time, color }
+    }
 }
 
 impl Default for Storyboard {
     fn default() -> Self {
         let mut layers = HashMap::new();
 
-        layers.insert("Video".to_owned(), Layer::new(4, false));
-        layers.insert("Background".to_owned(), Layer::new(3, true));
+        layers.insert(LayerId::Video, Layer::new(4, false));
+        layers.insert(LayerId::Background, Layer::new(3, true));
         layers.insert(
-            "Fail".to_owned(),
+            LayerId::Fail,
             Layer {
                 visible_when_passing: false,
                 ..Layer::new(2, true)
             },
         );
         layers.insert(
-            "Pass".to_owned(),
+            LayerId::Pass,
             Layer {
                 visible_when_failing: false,
                 ..Layer::new(1, true)
             },
         );
-        layers.insert("Foreground".to_owned(), Layer::new(0, true));
-        layers.insert("Overlay".to_owned(), Layer::new(i32::MIN, true));
+        layers.insert(LayerId::Foreground, Layer::new(0, true));
+        layers.insert(LayerId::Overlay, Layer::new(i32::MIN, true));
 
         let Events {
             background_file,
@@ -120,7 +189,9 @@ impl Default for Storyboard {
         Self {
             format_version: LATEST_FORMAT_VERSION,
             background_file,
+            background_offset: Pos::default(),
             breaks,
+            background_colors: Vec::new(),
             use_skin_sprites: Default::default(),
             min_layer_depth: 0,
             layers,
@@ -135,59 +206,3 @@ impl FromStr for Storyboard {
         rosu_map::from_str(s)
     }
 }
-
-pub(crate) struct StoryboardInternal {
-    pub layers: HashMap<String, LayerInternal>,
-    pub min_layer_depth: i32,
-}
-
-impl StoryboardInternal {
-    pub fn get_layer(&mut self, name: &str) -> &mut LayerInternal {
-        // Workaround for NLL
-        // See <https://github.com/rust-lang/rust/issues/43234>
-        if self.layers.contains_key(name) {
-            return self.layers.get_mut(name).unwrap();
-        }
-
-        self.min_layer_depth -= 1;
-        let layer = LayerInternal::new(self.min_layer_depth, true);
-
-        self.layers.entry(name.to_owned()).or_insert(layer)
-    }
-
-    pub fn convert_layers(self) -> HashMap<String, Layer> {
-        self.layers
-            .into_iter()
-            .map(|(name, layer)| (name, layer.into()))
-            .collect()
-    }
-}
-
-impl Default for StoryboardInternal {
-    fn default() -> Self {
-        let storyboard = Storyboard::default();
-
-        let layers = storyboard
-            .layers
-            .into_iter()
-            .map(|(name, layer)| {
-                debug_assert!(layer.elements.is_empty());
-
-                let layer = LayerInternal {
-                    depth: layer.depth,
-                    masking: layer.masking,
-                    visible_when_passing: layer.visible_when_passing,
-                    visible_when_failing: layer.visible_when_failing,
-                    elements: Vec::new(),
-                };
-
-                (name, layer)
-            })
-            .collect();
-
-        Self {
-            layers,
-            min_layer_depth: storyboard.min_layer_depth,
-        }
-    }
-}
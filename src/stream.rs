@@ -0,0 +1,225 @@
+use std::{
+    borrow::Cow,
+    collections::VecDeque,
+    fs::File,
+    io::{self, BufRead, BufReader},
+    path::Path,
+};
+
+use rosu_map::{
+    section::{events::BreakPeriod, Section},
+    util::{Pos, StrExt},
+    DecodeBeatmap, DecodeState, LATEST_FORMAT_VERSION,
+};
+
+use crate::{
+    element::Element, reader::LineReader, validate::format_version, BackgroundColor, LayerId,
+    Storyboard, StoryboardState,
+};
+
+/// An event of a storyboard as yielded by [`StoryboardEvents`].
+// Elements are the most common event so boxing them wouldn't help
+#[allow(clippy::large_enum_variant)]
+#[derive(Clone, Debug, PartialEq)]
+pub enum StoryboardEvent {
+    /// A fully decoded element of a layer, i.e. a sprite or animation with
+    /// all of its commands, a sample, or a video.
+    Element {
+        layer: LayerId,
+        element: Element,
+    },
+    /// A background as specified by a background event.
+    Background {
+        file: String,
+        offset: Pos,
+    },
+    Break(BreakPeriod),
+    BackgroundColor(BackgroundColor),
+}
+
+/// Pull-based decoder that yields the events of a storyboard one by one
+/// instead of collecting them into a [`Storyboard`].
+///
+/// A sprite or animation is yielded as soon as the next event starts so only
+/// one of them is held in memory at a time.
+///
+/// Unlike [`Storyboard`] decoding, commands cannot be added to an element
+/// that has already been yielded. Such commands, e.g. indented commands
+/// following a sample event, are skipped. Also, the first sprite is not
+/// considered as background.
+///
+/// ```
+/// use rosu_storyboard::{LayerId, StoryboardEvent, StoryboardEvents};
+///
+/// let path = "./resources/Himeringo - Yotsuya-san ni Yoroshiku (RLC) [Winber1's Extreme].osu";
+/// let mut sprites = 0;
+///
+/// for event in StoryboardEvents::from_path(path).unwrap() {
+///     if let StoryboardEvent::Element { layer, .. } = event.unwrap() {
+///         sprites += usize::from(layer == LayerId::Foreground);
+///     }
+/// }
+///
+/// assert!(sprites > 0);
+/// ```
+pub struct StoryboardEvents<R> {
+    reader: LineReader<R>,
+    state: StoryboardState,
+    section: Option<Section>,
+    /// Line that was read while looking for the format version.
+    first_line: Option<String>,
+    queue: VecDeque<StoryboardEvent>,
+    done: bool,
+}
+
+impl StoryboardEvents<BufReader<File>> {
+    /// Open the file at the given path and read its format version.
+    pub fn from_path(path: impl AsRef<Path>) -> Result<Self, io::Error> {
+        File::open(path).map(BufReader::new).and_then(Self::new)
+    }
+}
+
+impl<R: BufRead> StoryboardEvents<R> {
+    /// Create a new [`StoryboardEvents`] and read the format version.
+    pub fn new(reader: R) -> Result<Self, io::Error> {
+        let mut reader = LineReader::new(reader)?;
+        let mut version = LATEST_FORMAT_VERSION;
+        let mut first_line = None;
+
+        while let Some(line) = reader.read_line()? {
+            if line.is_empty() {
+                continue;
+            }
+
+            match format_version(&line) {
+                Some(line_version) => version = line_version,
+                None => first_line = Some(line.into_owned()),
+            }
+
+            break;
+        }
+
+        Ok(Self {
+            reader,
+            state: StoryboardState::create(version),
+            section: None,
+            first_line,
+            queue: VecDeque::new(),
+            done: false,
+        })
+    }
+
+    pub const fn format_version(&self) -> i32 {
+        self.state.storyboard().format_version
+    }
+
+    /// Whether skin sprites are used as specified in the `[General]` section
+    /// so far.
+    pub const fn use_skin_sprites(&self) -> bool {
+        self.state.storyboard().use_skin_sprites
+    }
+}
+
+impl<R: BufRead> Iterator for StoryboardEvents<R> {
+    type Item = Result<StoryboardEvent, io::Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(event) = self.queue.pop_front() {
+                return Some(Ok(event));
+            }
+
+            if self.done {
+                return None;
+            }
+
+            let line = match self.first_line.take() {
+                Some(line) => Cow::Owned(line),
+                None => match self.reader.read_line() {
+                    Ok(Some(line)) => line,
+                    Ok(None) => {
+                        self.done = true;
+                        push_elements(&mut self.state, &mut self.queue);
+
+                        continue;
+                    }
+                    Err(err) => {
+                        self.done = true;
+
+                        return Some(Err(err));
+                    }
+                },
+            };
+
+            process_line(&mut self.state, &mut self.section, &mut self.queue, &line);
+        }
+    }
+}
+
+fn process_line(
+    state: &mut StoryboardState,
+    section: &mut Option<Section>,
+    queue: &mut VecDeque<StoryboardEvent>,
+    line: &str,
+) {
+    if let Some(next) = Section::try_from_line(line) {
+        *section = Some(next);
+
+        return;
+    }
+
+    if <Storyboard as DecodeBeatmap>::should_skip_line(line) {
+        return;
+    }
+
+    // Errors are ignored just like in regular decoding
+    match section {
+        Some(Section::General) => {
+            let _ = Storyboard::parse_general(state, line);
+        }
+        Some(Section::Variables) => {
+            let _ = Storyboard::parse_variables(state, line);
+        }
+        Some(Section::Events) => {
+            let line = state.decode_variables(line.trim_comment());
+            let is_event = !line.starts_with([' ', '_']);
+
+            // The previous element is complete once the next event starts
+            if is_event {
+                push_elements(state, queue);
+            }
+
+            let _ = state.parse_event(&line);
+
+            if is_event {
+                if let Some((file, offset)) = state.take_background() {
+                    queue.push_back(StoryboardEvent::Background { file, offset });
+                }
+
+                queue.extend(state.drain_breaks().map(StoryboardEvent::Break));
+                queue.extend(
+                    state
+                        .drain_background_colors()
+                        .map(StoryboardEvent::BackgroundColor),
+                );
+
+                // Sprites still receive the following commands
+                if !state.has_sprite() {
+                    push_elements(state, queue);
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+fn push_elements(state: &mut StoryboardState, queue: &mut VecDeque<StoryboardEvent>) {
+    let elements = state
+        .drain_elements()
+        .map(|(layer, element)| StoryboardEvent::Element {
+            layer: layer.clone(),
+            element,
+        });
+
+    queue.extend(elements);
+}
@@ -0,0 +1,338 @@
+use rosu_map::util::Pos;
+
+use crate::{
+    command::{CommandTimeline, CommandTimelineGroup},
+    element::{ElementKind, Sprite},
+    visual::{Anchor, Easing},
+    Storyboard,
+};
+
+/// Horizontal distance between the left edge of a 16:9 screen and the left
+/// edge of the 4:3 area in its center, in storyboard coordinates.
+///
+/// Roughly `106.67`; storyboarders often round it to `107`.
+pub const WIDESCREEN_OFFSET: f32 = (480.0 * 16.0 / 9.0 - 640.0) / 2.0;
+
+const CENTER: Pos = Pos::new(320.0, 240.0);
+
+/// Aspect ratio of the coordinate system of a [`Storyboard`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum AspectRatio {
+    /// 4:3 with `x` ranging from `0` to `640`.
+    Standard,
+    /// 16:9 with `x` ranging from `0` to roughly `853`.
+    Widescreen,
+}
+
+impl Storyboard {
+    /// Move every time in the storyboard by the given amount of milliseconds.
+    ///
+    /// Times within loops and triggers are relative to the loop's start time
+    /// or the trigger's activation and thus remain unchanged.
+    pub fn shift(&mut self, offset: f64) {
+        self.map_times(|time| time + offset, |time| time, |delay| delay);
+    }
+
+    /// Adjust every time in the storyboard to a different playback rate,
+    /// e.g. `1.5` for DT or `0.75` for HT.
+    ///
+    /// All times and durations, including relative times within loops and
+    /// triggers as well as frame delays of animations, are divided by the
+    /// rate.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `rate` is not positive and finite.
+    pub fn rescale_time(&mut self, rate: f64) {
+        assert!(
+            rate > 0.0 && rate.is_finite(),
+            "rate must be positive and finite, got {rate}"
+        );
+
+        self.map_times(|time| time / rate, |time| time / rate, |delay| delay / rate);
+    }
+
+    /// Mirror the storyboard along the vertical line through the center of the
+    /// screen, similar to the HR mod.
+    ///
+    /// Positions and origins are mirrored, rotations negated, and the
+    /// horizontal flip of each sprite is toggled throughout its lifetime.
+    /// Flip commands within loops and triggers remain unchanged.
+    pub fn mirror_horizontal(&mut self) {
+        for sprite in self.sprites_mut() {
+            sprite.initial_pos.x = 2.0 * CENTER.x - sprite.initial_pos.x;
+            sprite.origin = mirror_anchor(sprite.origin, Anchor::X0, Anchor::X2);
+
+            for_each_group(sprite, |group| {
+                group.x.map_values(|x| 2.0 * CENTER.x - x);
+                group.rotation.map_values(|rotation| -rotation);
+            });
+
+            if let Some((start_time, end_time)) = lifetime(sprite) {
+                let group = &mut sprite.timeline_group;
+                let flipped = toggled_flip(&group.flip_h, start_time, end_time);
+                group.flip_h = CommandTimeline::default();
+
+                for (start_time, end_time) in flipped {
+                    group.add_flip_h(Easing::None, start_time, end_time);
+                }
+            }
+        }
+    }
+
+    /// Mirror the storyboard along the horizontal line through the center of
+    /// the screen.
+    ///
+    /// Positions and origins are mirrored, rotations negated, and the
+    /// vertical flip of each sprite is toggled throughout its lifetime.
+    /// Flip commands within loops and triggers remain unchanged.
+    pub fn mirror_vertical(&mut self) {
+        for sprite in self.sprites_mut() {
+            sprite.initial_pos.y = 2.0 * CENTER.y - sprite.initial_pos.y;
+            sprite.origin = mirror_anchor(sprite.origin, Anchor::Y0, Anchor::Y2);
+
+            for_each_group(sprite, |group| {
+                group.y.map_values(|y| 2.0 * CENTER.y - y);
+                group.rotation.map_values(|rotation| -rotation);
+            });
+
+            if let Some((start_time, end_time)) = lifetime(sprite) {
+                let group = &mut sprite.timeline_group;
+                let flipped = toggled_flip(&group.flip_v, start_time, end_time);
+                group.flip_v = CommandTimeline::default();
+
+                for (start_time, end_time) in flipped {
+                    group.add_flip_v(Easing::None, start_time, end_time);
+                }
+            }
+        }
+    }
+
+    /// Move all sprites and animations by the given offset.
+    pub fn translate(&mut self, offset: Pos) {
+        for sprite in self.sprites_mut() {
+            sprite.initial_pos += offset;
+
+            for_each_group(sprite, |group| {
+                group.x.map_values(|x| x + offset.x);
+                group.y.map_values(|y| y + offset.y);
+            });
+        }
+    }
+
+    /// Scale all sprites and animations uniformly around the given point.
+    ///
+    /// Positions are scaled as well as the effective size of each sprite,
+    /// i.e. its `V` commands if it has any and its `S` commands otherwise.
+    /// Sprites without either receive a constant `S` command.
+    pub fn scale_around(&mut self, origin: Pos, factor: f32) {
+        for sprite in self.sprites_mut() {
+            sprite.initial_pos = origin + (sprite.initial_pos - origin) * factor;
+
+            let (mut has_scale, mut has_vector_scale) = (false, false);
+
+            for_each_group(sprite, |group| {
+                group.x.map_values(|x| origin.x + (x - origin.x) * factor);
+                group.y.map_values(|y| origin.y + (y - origin.y) * factor);
+
+                has_scale |= !group.scale.is_empty();
+                has_vector_scale |= !group.vector_scale.is_empty();
+            });
+
+            // The effective scale is the product of both so only one of them
+            // must be scaled
+            if has_vector_scale {
+                for_each_group(sprite, |group| {
+                    group.vector_scale.map_values(|scale| scale * factor);
+                });
+            } else if has_scale {
+                for_each_group(sprite, |group| {
+                    group.scale.map_values(|scale| scale * factor);
+                });
+            } else if sprite.has_commands() {
+                let start_time = sprite.earliest_transform_time();
+
+                sprite.timeline_group.scale.add(
+                    Easing::None,
+                    start_time,
+                    start_time,
+                    factor,
+                    factor,
+                );
+            }
+        }
+    }
+
+    /// Move all sprites and animations horizontally so that content that was
+    /// centered in one aspect ratio stays centered in the other.
+    ///
+    /// osu! places the 4:3 area at the center of a 16:9 screen so a
+    /// storyboard that was authored with `x` starting at the left edge of a
+    /// 16:9 screen must be moved by [`WIDESCREEN_OFFSET`] to the left.
+    pub fn recenter(&mut self, from: AspectRatio, to: AspectRatio) {
+        let offset = match (from, to) {
+            (AspectRatio::Standard, AspectRatio::Widescreen) => WIDESCREEN_OFFSET,
+            (AspectRatio::Widescreen, AspectRatio::Standard) => -WIDESCREEN_OFFSET,
+            (AspectRatio::Standard, AspectRatio::Standard)
+            | (AspectRatio::Widescreen, AspectRatio::Widescreen) => return,
+        };
+
+        self.translate(Pos::new(offset, 0.0));
+    }
+
+    fn sprites_mut(&mut self) -> impl Iterator<Item = &mut Sprite> {
+        self.layers
+            .values_mut()
+            .flat_map(|layer| layer.elements.iter_mut())
+            .filter_map(|elem| match elem.kind {
+                ElementKind::Animation(ref mut animation) => Some(&mut animation.sprite),
+                ElementKind::Sprite(ref mut sprite) => Some(sprite),
+                ElementKind::Sample(_) | ElementKind::Video(_) => None,
+            })
+    }
+
+    /// Map all times while keeping cached fields consistent.
+    ///
+    /// - `absolute` maps times on the storyboard's timeline
+    /// - `relative` maps times within loops and triggers
+    /// - `frame_delay` maps the frame delay of animations
+    fn map_times<A, R, D>(&mut self, absolute: A, relative: R, frame_delay: D)
+    where
+        A: Fn(f64) -> f64,
+        R: Fn(f64) -> f64,
+        D: Fn(f64) -> f64,
+    {
+        for b in self.breaks.iter_mut() {
+            b.start_time = absolute(b.start_time);
+            b.end_time = absolute(b.end_time);
+        }
+
+        for color in self.background_colors.iter_mut() {
+            color.time = absolute(color.time);
+        }
+
+        let elems = self
+            .layers
+            .values_mut()
+            .flat_map(|layer| layer.elements.iter_mut());
+
+        for elem in elems {
+            let sprite = match elem.kind {
+                ElementKind::Animation(ref mut animation) => {
+                    animation.frame_delay = frame_delay(animation.frame_delay);
+
+                    &mut animation.sprite
+                }
+                ElementKind::Sprite(ref mut sprite) => sprite,
+                ElementKind::Sample(ref mut sample) => {
+                    sample.start_time = absolute(sample.start_time);
+
+                    continue;
+                }
+                ElementKind::Video(ref mut video) => {
+                    video.start_time = absolute(video.start_time);
+
+                    continue;
+                }
+            };
+
+            sprite.timeline_group.map_times(&absolute);
+
+            for l in sprite.loops.iter_mut() {
+                l.loop_start_time = absolute(l.loop_start_time);
+                l.group.map_times(&relative);
+            }
+
+            for trigger in sprite.triggers.iter_mut() {
+                // Unbounded windows remain unbounded
+                if trigger.start_time > f64::MIN {
+                    trigger.start_time = absolute(trigger.start_time);
+                }
+
+                if trigger.end_time < f64::MAX {
+                    trigger.end_time = absolute(trigger.end_time);
+                }
+
+                trigger.group.map_times(&relative);
+            }
+        }
+    }
+}
+
+fn for_each_group<F: FnMut(&mut CommandTimelineGroup)>(sprite: &mut Sprite, mut f: F) {
+    f(&mut sprite.timeline_group);
+
+    for l in sprite.loops.iter_mut() {
+        f(&mut l.group);
+    }
+
+    for trigger in sprite.triggers.iter_mut() {
+        f(&mut trigger.group);
+    }
+}
+
+const fn mirror_anchor(anchor: Anchor, low: u8, high: u8) -> Anchor {
+    if anchor.0 & low != 0 {
+        Anchor((anchor.0 & !low) | high)
+    } else if anchor.0 & high != 0 {
+        Anchor((anchor.0 & !high) | low)
+    } else {
+        anchor
+    }
+}
+
+/// The time span during which the sprite is displayed.
+fn lifetime(sprite: &Sprite) -> Option<(f64, f64)> {
+    let start_time = sprite.earliest_transform_time();
+    let end_time = sprite.end_time_for_display();
+
+    (start_time <= end_time).then_some((start_time, end_time))
+}
+
+/// The time spans within `start_time..end_time` during which a flip is
+/// *not* active.
+///
+/// A flip command without duration stays active indefinitely while other
+/// flip commands are only active during their duration.
+fn toggled_flip(
+    timeline: &CommandTimeline<bool>,
+    start_time: f64,
+    end_time: f64,
+) -> Vec<(f64, f64)> {
+    let mut active: Vec<_> = timeline
+        .commands
+        .iter()
+        .map(|command| {
+            if command.end_time > command.start_time {
+                (command.start_time, command.end_time)
+            } else {
+                (command.start_time, f64::INFINITY)
+            }
+        })
+        .collect();
+
+    active.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+    let mut inactive = Vec::new();
+    let mut curr = start_time;
+
+    for (start, end) in active {
+        if start > curr {
+            inactive.push((curr, start.min(end_time)));
+        }
+
+        curr = curr.max(end);
+
+        if curr >= end_time {
+            break;
+        }
+    }
+
+    if curr < end_time {
+        inactive.push((curr, end_time));
+    }
+
+    inactive.retain(|(start, end)| end > start);
+
+    inactive
+}
@@ -0,0 +1,317 @@
+use std::collections::HashMap;
+
+use rosu_map::{
+    section::hit_objects::{
+        hit_samples::{HitSampleDefaultName, HitSampleInfo, HitSampleInfoName, SampleBank},
+        CurveBuffers, HitObject, HitObjectKind,
+    },
+    Beatmap,
+};
+
+use crate::{
+    command::{CommandTrigger, HitSoundAddition, TriggerKind},
+    element::{ElementKind, Sprite},
+    LayerId, PlayState, Storyboard,
+};
+
+/// An event during gameplay that may activate [`CommandTrigger`]s.
+#[derive(Clone, Debug, PartialEq)]
+pub enum GameplayEvent {
+    /// A hit object was hit.
+    HitObjectHit { time: f64 },
+    /// A hit sound was played.
+    HitSound {
+        time: f64,
+        sample_set: SampleBank,
+        additions_sample_set: SampleBank,
+        additions: Vec<HitSoundAddition>,
+        custom_sample_set: i32,
+    },
+    /// The player switched between passing and failing.
+    PlayState { time: f64, passing: bool },
+}
+
+impl GameplayEvent {
+    /// Create a [`GameplayEvent::HitSound`] from the samples that are played
+    /// at the given time.
+    ///
+    /// Returns `None` if there are no samples.
+    pub fn from_samples(time: f64, samples: &[HitSampleInfo]) -> Option<Self> {
+        let normal = samples
+            .iter()
+            .find(|sample| sample.name == HitSampleInfoName::Default(HitSampleDefaultName::Normal))
+            .or_else(|| samples.first())?;
+
+        let mut additions = Vec::new();
+        let mut additions_sample_set = None;
+
+        for sample in samples.iter() {
+            let addition = match sample.name {
+                HitSampleInfoName::Default(HitSampleDefaultName::Whistle) => {
+                    HitSoundAddition::Whistle
+                }
+                HitSampleInfoName::Default(HitSampleDefaultName::Finish) => {
+                    HitSoundAddition::Finish
+                }
+                HitSampleInfoName::Default(HitSampleDefaultName::Clap) => HitSoundAddition::Clap,
+                HitSampleInfoName::Default(HitSampleDefaultName::Normal)
+                | HitSampleInfoName::File(_) => continue,
+            };
+
+            additions_sample_set.get_or_insert(sample.bank);
+            additions.push(addition);
+        }
+
+        Some(Self::HitSound {
+            time,
+            sample_set: normal.bank,
+            additions_sample_set: additions_sample_set.unwrap_or(normal.bank),
+            additions,
+            custom_sample_set: normal.custom_sample_bank,
+        })
+    }
+
+    /// The time at which the event occurs.
+    pub const fn time(&self) -> f64 {
+        match self {
+            Self::HitObjectHit { time }
+            | Self::HitSound { time, .. }
+            | Self::PlayState { time, .. } => *time,
+        }
+    }
+
+    /// Whether the event activates a trigger of the given kind.
+    pub fn activates(&self, kind: &TriggerKind) -> bool {
+        match (self, kind) {
+            (Self::HitObjectHit { .. }, TriggerKind::HitObjectHit) => true,
+            (
+                Self::HitSound {
+                    sample_set,
+                    additions_sample_set,
+                    additions,
+                    custom_sample_set,
+                    ..
+                },
+                TriggerKind::HitSound(trigger),
+            ) => trigger.matches(
+                *sample_set,
+                *additions_sample_set,
+                additions,
+                *custom_sample_set,
+            ),
+            (Self::PlayState { passing, .. }, TriggerKind::Passing) => *passing,
+            (Self::PlayState { passing, .. }, TriggerKind::Failing) => !*passing,
+            _ => false,
+        }
+    }
+}
+
+/// A single activation of a [`CommandTrigger`].
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct TriggerActivation {
+    /// Index of the trigger within [`Sprite::triggers`].
+    pub trigger: usize,
+    /// The time at which the trigger fired.
+    ///
+    /// The commands of the trigger's group are relative to this time.
+    pub time: f64,
+    /// The time at which the activation ends, either because all of its
+    /// commands finished or because another trigger of the same group fired.
+    pub end_time: f64,
+}
+
+/// The [`TriggerActivation`]s of a single [`Element`].
+///
+/// [`Element`]: crate::element::Element
+#[derive(Clone, Debug, PartialEq)]
+pub struct ElementTriggers {
+    /// The element's layer.
+    pub layer: LayerId,
+    /// Index of the element within its layer.
+    pub index: usize,
+    /// All activations sorted by time.
+    pub activations: Vec<TriggerActivation>,
+}
+
+/// Determines when [`CommandTrigger`]s fire based on [`GameplayEvent`]s.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct TriggerEngine {
+    /// Sorted by time.
+    events: Vec<GameplayEvent>,
+}
+
+impl TriggerEngine {
+    /// Create a new [`TriggerEngine`] for the given events.
+    pub fn new<I>(events: I) -> Self
+    where
+        I: IntoIterator<Item = GameplayEvent>,
+    {
+        let mut events: Vec<_> = events.into_iter().collect();
+        events.sort_by(|a, b| a.time().total_cmp(&b.time()));
+
+        Self { events }
+    }
+
+    /// Create a new [`TriggerEngine`] based on the hit objects and breaks of
+    /// a [`Beatmap`].
+    ///
+    /// Every hit object is assumed to be hit. Each hit circle, slider node,
+    /// spinner end, and hold note emits a [`GameplayEvent::HitObjectHit`] and
+    /// a [`GameplayEvent::HitSound`] with its samples. At the start of each
+    /// break, a [`GameplayEvent::PlayState`] is emitted based on the given
+    /// [`PlayState`].
+    pub fn from_beatmap(beatmap: &Beatmap, play_state: &PlayState) -> Self {
+        let mut events = Vec::with_capacity(beatmap.hit_objects.len() * 2);
+        let mut bufs = CurveBuffers::default();
+
+        for h in beatmap.hit_objects.iter() {
+            for (time, samples) in hit_object_samples(h, &mut bufs) {
+                events.push(GameplayEvent::HitObjectHit { time });
+                events.extend(GameplayEvent::from_samples(time, samples));
+            }
+        }
+
+        let play_state_events = beatmap.breaks.iter().map(|b| GameplayEvent::PlayState {
+            time: b.start_time,
+            passing: play_state.is_passing_at(b.start_time),
+        });
+
+        events.extend(play_state_events);
+
+        Self::new(events)
+    }
+
+    /// All events sorted by time.
+    pub fn events(&self) -> &[GameplayEvent] {
+        &self.events
+    }
+
+    /// Resolve the activations of the given triggers of a single sprite.
+    ///
+    /// A trigger only fires within its time window and firing a trigger ends
+    /// the activations of all triggers with the same group number.
+    pub fn resolve(&self, triggers: &[CommandTrigger]) -> Vec<TriggerActivation> {
+        let kinds: Vec<_> = triggers.iter().map(CommandTrigger::kind).collect();
+        let mut activations = Vec::new();
+
+        // Index of the current activation for each group number
+        let mut running: HashMap<i32, usize> = HashMap::new();
+
+        for event in self.events.iter() {
+            let time = event.time();
+
+            let fired =
+                triggers
+                    .iter()
+                    .zip(kinds.iter())
+                    .enumerate()
+                    .filter(|(_, (trigger, kind))| {
+                        trigger.start_time <= time
+                            && time <= trigger.end_time
+                            && event.activates(kind)
+                    });
+
+            for (i, (trigger, _)) in fired {
+                if let Some(prev) = running.insert(trigger.group_num, activations.len()) {
+                    let prev: &mut TriggerActivation = &mut activations[prev];
+                    prev.end_time = prev.end_time.min(time);
+                }
+
+                activations.push(TriggerActivation {
+                    trigger: i,
+                    time,
+                    end_time: time + trigger.group.end_time().max(0.0),
+                });
+            }
+        }
+
+        activations
+    }
+
+    /// Resolve the activations of all triggers of a [`Sprite`].
+    pub fn resolve_sprite(&self, sprite: &Sprite) -> Vec<TriggerActivation> {
+        self.resolve(&sprite.triggers)
+    }
+
+    /// Resolve the activations of all triggers in the [`Storyboard`].
+    ///
+    /// Elements without any activations are omitted.
+    pub fn resolve_storyboard(&self, storyboard: &Storyboard) -> Vec<ElementTriggers> {
+        storyboard
+            .layers
+            .iter()
+            .flat_map(|(name, layer)| {
+                layer
+                    .elements
+                    .iter()
+                    .enumerate()
+                    .map(move |(i, elem)| (name, i, elem))
+            })
+            .filter_map(|(name, index, elem)| {
+                let sprite = match elem.kind {
+                    ElementKind::Animation(ref animation) => &animation.sprite,
+                    ElementKind::Sprite(ref sprite) => sprite,
+                    ElementKind::Sample(_) | ElementKind::Video(_) => return None,
+                };
+
+                if sprite.triggers.is_empty() {
+                    return None;
+                }
+
+                let activations = self.resolve_sprite(sprite);
+
+                (!activations.is_empty()).then(|| ElementTriggers {
+                    layer: name.clone(),
+                    index,
+                    activations,
+                })
+            })
+            .collect()
+    }
+}
+
+/// The times at which a hit object plays samples alongside those samples.
+///
+/// Slider curves are calculated with the given buffers without storing them.
+fn hit_object_samples<'a>(
+    h: &'a HitObject,
+    bufs: &mut CurveBuffers,
+) -> Vec<(f64, &'a [HitSampleInfo])> {
+    match h.kind {
+        HitObjectKind::Circle(_) | HitObjectKind::Hold(_) => {
+            vec![(h.start_time, h.samples.as_slice())]
+        }
+        HitObjectKind::Slider(ref slider) => {
+            let span_count = f64::from(slider.span_count());
+
+            // Same as `HitObjectSlider::duration_with_bufs` without requiring
+            // a mutable slider
+            let dist = slider.path.borrowed_curve(bufs).dist();
+            let duration = span_count * dist / slider.velocity;
+
+            slider
+                .node_samples
+                .iter()
+                .enumerate()
+                .map(|(i, samples)| {
+                    let time = h.start_time + i as f64 * duration / span_count;
+
+                    (time, samples.as_slice())
+                })
+                .collect()
+        }
+        HitObjectKind::Spinner(ref spinner) => {
+            vec![(h.start_time + spinner.duration, h.samples.as_slice())]
+        }
+    }
+}
+
+impl Storyboard {
+    /// Resolve the activations of all triggers based on a [`Beatmap`],
+    /// assuming that the player hits every hit object and passes every break.
+    ///
+    /// See [`TriggerEngine::from_beatmap`] for more control.
+    pub fn resolve_triggers(&self, beatmap: &Beatmap) -> Vec<ElementTriggers> {
+        TriggerEngine::from_beatmap(beatmap, &PlayState::passing()).resolve_storyboard(self)
+    }
+}
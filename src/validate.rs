@@ -0,0 +1,127 @@
+use std::{error, fmt};
+
+use rosu_map::{section::Section, DecodeBeatmap, DecodeState, LATEST_FORMAT_VERSION};
+
+use crate::{reader::Encoding, ParseStoryboardError, Storyboard, StoryboardState};
+
+const VERSION_PREFIX: &str = "osu file format v";
+
+/// A line that could not be parsed while decoding a [`Storyboard`].
+#[derive(Debug)]
+pub struct LineError {
+    /// One-based line number.
+    pub line: usize,
+    /// Content of the line without trailing whitespace.
+    pub content: String,
+    pub error: ParseStoryboardError,
+}
+
+impl error::Error for LineError {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        Some(&self.error)
+    }
+}
+
+impl fmt::Display for LineError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: {} `{}`", self.line, self.error, self.content)
+    }
+}
+
+impl Storyboard {
+    /// Decode a [`Storyboard`] while collecting all lines that failed to
+    /// parse.
+    ///
+    /// Regular decoding skips such lines silently; the resulting storyboard
+    /// is the same.
+    pub fn decode_with_errors(input: &str) -> (Self, Vec<LineError>) {
+        let (version, lines) = section_lines(input);
+        let mut state = StoryboardState::create(version);
+        let mut errors = Vec::new();
+
+        for (line_number, section, line) in lines {
+            let res = match section {
+                Section::General => Self::parse_general(&mut state, line),
+                Section::Events => Self::parse_events(&mut state, line),
+                Section::Variables => Self::parse_variables(&mut state, line),
+                // Irrelevant for storyboards
+                _ => Ok(()),
+            };
+
+            if let Err(error) = res {
+                errors.push(LineError {
+                    line: line_number,
+                    content: line.to_owned(),
+                    error,
+                });
+            }
+        }
+
+        (state.into(), errors)
+    }
+
+    /// All lines that fail to parse when decoding a [`Storyboard`].
+    pub fn validate(input: &str) -> Vec<LineError> {
+        Self::decode_with_errors(input).1
+    }
+
+    /// All lines that fail to parse when decoding a [`Storyboard`] from bytes.
+    ///
+    /// The bytes are decoded the same way as in [`Storyboard::from_bytes`],
+    /// i.e. UTF-16 is supported through a byte order mark.
+    pub fn validate_bytes(bytes: &[u8]) -> Vec<LineError> {
+        let (encoding, bom_len) = Encoding::from_bom(bytes);
+
+        Self::validate(&encoding.decode(&bytes[bom_len..]))
+    }
+}
+
+/// Read the format version of the input and iterate over all remaining lines
+/// that are neither skipped nor section headers.
+///
+/// Items consist of the one-based line number, the current section, and the
+/// line without trailing whitespace.
+pub(crate) fn section_lines(input: &str) -> (i32, impl Iterator<Item = (usize, Section, &str)>) {
+    let input = input.strip_prefix('\u{feff}').unwrap_or(input);
+
+    let mut lines = input
+        .split('\n')
+        .map(str::trim_end)
+        .enumerate()
+        .map(|(i, line)| (i + 1, line))
+        .skip_while(|(_, line)| line.is_empty())
+        .peekable();
+
+    let version = match lines.peek().and_then(|(_, line)| format_version(line)) {
+        Some(version) => {
+            lines.next();
+
+            version
+        }
+        None => LATEST_FORMAT_VERSION,
+    };
+
+    let mut section = None;
+
+    let lines = lines.filter_map(move |(line_number, line)| {
+        if let Some(next) = Section::try_from_line(line) {
+            section = Some(next);
+
+            return None;
+        }
+
+        if <Storyboard as DecodeBeatmap>::should_skip_line(line) {
+            return None;
+        }
+
+        section.map(|section| (line_number, section, line))
+    });
+
+    (version, lines)
+}
+
+/// Parse the format version if the line specifies it.
+pub(crate) fn format_version(line: &str) -> Option<i32> {
+    line.strip_prefix(VERSION_PREFIX)
+        .map(|version| version.parse().unwrap_or(LATEST_FORMAT_VERSION))
+}
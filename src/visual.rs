@@ -14,6 +14,22 @@ pub enum Origins {
 }
 
 impl Origins {
+    /// The name of the origin as it appears in `.osb` files.
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Origins::TopLeft => "TopLeft",
+            Origins::Centre => "Centre",
+            Origins::CentreLeft => "CentreLeft",
+            Origins::TopRight => "TopRight",
+            Origins::BottomCentre => "BottomCentre",
+            Origins::TopCentre => "TopCentre",
+            Origins::Custom => "Custom",
+            Origins::CentreRight => "CentreRight",
+            Origins::BottomLeft => "BottomLeft",
+            Origins::BottomRight => "BottomRight",
+        }
+    }
+
     pub fn parse(s: &str) -> Anchor {
         let origins = match s.parse::<u8>() {
             Ok(0) => Origins::TopLeft,
@@ -78,6 +94,7 @@ impl From<Anchor> for Origins {
 ///
 /// x and y counterparts can be accessed using bitwise flags.
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Anchor(pub u8);
 
 impl Anchor {
@@ -100,11 +117,35 @@ impl Anchor {
     pub const BOTTOM_LEFT: Self = Self(Self::Y2 | Self::X0);
     pub const BOTTOM_CENTER: Self = Self(Self::Y2 | Self::X1);
     pub const BOTTOM_RIGHT: Self = Self(Self::Y2 | Self::X2);
+
+    /// The position of the anchor relative to the size of its element.
+    ///
+    /// `(0.0, 0.0)` is the top left and `(1.0, 1.0)` the bottom right.
+    pub const fn relative_position(self) -> (f32, f32) {
+        let x = if self.0 & Self::X2 > 0 {
+            1.0
+        } else if self.0 & Self::X1 > 0 {
+            0.5
+        } else {
+            0.0
+        };
+
+        let y = if self.0 & Self::Y2 > 0 {
+            1.0
+        } else if self.0 & Self::Y1 > 0 {
+            0.5
+        } else {
+            0.0
+        };
+
+        (x, y)
+    }
 }
 
 /// Contains information about how a drawable element should be blended into
 /// its destination.
 #[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct BlendingParameters {
     /// The blending factor for the source color of the blend.
     pub src: Blending,
@@ -160,6 +201,7 @@ impl BlendingParameters {
 
 /// A blending type for [`BlendingParameters`].
 #[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Blending {
     #[default]
     Inherit,
@@ -182,6 +224,7 @@ pub enum Blending {
 
 /// A blending equation for [`BlendingParameters`].
 #[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum BlendingEquation {
     /// Inherits from parent.
     #[default]
@@ -202,6 +245,7 @@ macro_rules! define_easing {
     ( $( $variant:ident = $discriminant:literal, )* ) => {
         /// See <http://easings.net/> for more samples.
         #[derive(Copy, Clone, Debug, PartialEq, Eq)]
+        #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
         pub enum Easing {
             $( $variant, )*
         }
@@ -255,3 +299,117 @@ define_easing! {
     InOutBounce = 34,
     OutPow10 = 35,
 }
+
+impl Easing {
+    /// Apply the easing to a progress value between `0.0` and `1.0`.
+    pub fn apply(self, time: f64) -> f64 {
+        use std::f64::consts::PI;
+
+        const ELASTIC_CONST: f64 = 2.0 * PI / 0.3;
+        const ELASTIC_CONST2: f64 = 0.3 / 4.0;
+        const BACK_CONST: f64 = 1.701_58;
+        const BACK_CONST2: f64 = BACK_CONST * 1.525;
+
+        match self {
+            Easing::None => time,
+            Easing::In | Easing::InQuad => time * time,
+            Easing::Out | Easing::OutQuad => time * (2.0 - time),
+            Easing::InOutQuad if time < 0.5 => time * time * 2.0,
+            Easing::InOutQuad => (time - 1.0) * (time - 1.0) * -2.0 + 1.0,
+            Easing::InCubic => time.powi(3),
+            Easing::OutCubic => (time - 1.0).powi(3) + 1.0,
+            Easing::InOutCubic if time < 0.5 => time.powi(3) * 4.0,
+            Easing::InOutCubic => (time - 1.0).powi(3) * 4.0 + 1.0,
+            Easing::InQuart => time.powi(4),
+            Easing::OutQuart => 1.0 - (time - 1.0).powi(4),
+            Easing::InOutQuart if time < 0.5 => time.powi(4) * 8.0,
+            Easing::InOutQuart => (time - 1.0).powi(4) * -8.0 + 1.0,
+            Easing::InQuint => time.powi(5),
+            Easing::OutQuint => (time - 1.0).powi(5) + 1.0,
+            Easing::InOutQuint if time < 0.5 => time.powi(5) * 16.0,
+            Easing::InOutQuint => (time - 1.0).powi(5) * 16.0 + 1.0,
+            Easing::InSine => 1.0 - (time * PI * 0.5).cos(),
+            Easing::OutSine => (time * PI * 0.5).sin(),
+            Easing::InOutSine => 0.5 - 0.5 * (PI * time).cos(),
+            Easing::InExpo => 2.0_f64.powf(10.0 * (time - 1.0)),
+            Easing::OutExpo => 1.0 - 2.0_f64.powf(-10.0 * time),
+            Easing::InOutExpo if time < 0.5 => 0.5 * 2.0_f64.powf(20.0 * time - 10.0),
+            Easing::InOutExpo => 1.0 - 0.5 * 2.0_f64.powf(-20.0 * time + 10.0),
+            Easing::InCirc => 1.0 - (1.0 - time * time).sqrt(),
+            Easing::OutCirc => (1.0 - (time - 1.0) * (time - 1.0)).sqrt(),
+            Easing::InOutCirc if time < 0.5 => 0.5 - 0.5 * (1.0 - 4.0 * time * time).sqrt(),
+            Easing::InOutCirc => 0.5 + 0.5 * (1.0 - (time * 2.0 - 2.0).powi(2)).sqrt(),
+            Easing::InElastic => {
+                -(2.0_f64.powf(-10.0 + 10.0 * time))
+                    * ((1.0 - ELASTIC_CONST2 - time) * ELASTIC_CONST).sin()
+            }
+            Easing::OutElastic => {
+                2.0_f64.powf(-10.0 * time) * ((time - ELASTIC_CONST2) * ELASTIC_CONST).sin() + 1.0
+            }
+            Easing::OutElasticHalf => {
+                2.0_f64.powf(-10.0 * time) * ((0.5 * time - ELASTIC_CONST2) * ELASTIC_CONST).sin()
+                    + 1.0
+            }
+            Easing::OutElasticQuarter => {
+                2.0_f64.powf(-10.0 * time) * ((0.25 * time - ELASTIC_CONST2) * ELASTIC_CONST).sin()
+                    + 1.0
+            }
+            Easing::InOutElastic => {
+                let time = time * 2.0;
+
+                if time < 1.0 {
+                    -0.5 * 2.0_f64.powf(-10.0 + 10.0 * time)
+                        * ((1.0 - ELASTIC_CONST2 * 1.5 - time) * ELASTIC_CONST / 1.5).sin()
+                } else {
+                    let time = time - 1.0;
+
+                    0.5 * 2.0_f64.powf(-10.0 * time)
+                        * ((time - ELASTIC_CONST2 * 1.5) * ELASTIC_CONST / 1.5).sin()
+                        + 1.0
+                }
+            }
+            Easing::InBack => time * time * ((BACK_CONST + 1.0) * time - BACK_CONST),
+            Easing::OutBack => {
+                let time = time - 1.0;
+
+                time * time * ((BACK_CONST + 1.0) * time + BACK_CONST) + 1.0
+            }
+            Easing::InOutBack => {
+                let time = time * 2.0;
+
+                if time < 1.0 {
+                    0.5 * (time * time * ((BACK_CONST2 + 1.0) * time - BACK_CONST2))
+                } else {
+                    let time = time - 2.0;
+
+                    0.5 * (time * time * ((BACK_CONST2 + 1.0) * time + BACK_CONST2) + 2.0)
+                }
+            }
+            Easing::InBounce => 1.0 - out_bounce(1.0 - time),
+            Easing::OutBounce => out_bounce(time),
+            Easing::InOutBounce if time < 0.5 => 0.5 - 0.5 * out_bounce(1.0 - time * 2.0),
+            Easing::InOutBounce => out_bounce((time - 0.5) * 2.0) * 0.5 + 0.5,
+            Easing::OutPow10 => (time - 1.0) * (time - 1.0).powi(10) + 1.0,
+        }
+    }
+}
+
+fn out_bounce(time: f64) -> f64 {
+    const BOUNCE_CONST: f64 = 1.0 / 2.75;
+
+    if time < BOUNCE_CONST {
+        7.5625 * time * time
+    } else if time < 2.0 * BOUNCE_CONST {
+        let time = time - 1.5 * BOUNCE_CONST;
+
+        7.5625 * time * time + 0.75
+    } else if time < 2.5 * BOUNCE_CONST {
+        let time = time - 2.25 * BOUNCE_CONST;
+
+        7.5625 * time * time + 0.9375
+    } else {
+        let time = time - 2.625 * BOUNCE_CONST;
+
+        7.5625 * time * time + 0.984_375
+    }
+}
@@ -0,0 +1,150 @@
+use rosu_map::section::timing_points::{TimeSignature, TimingPoint};
+use rosu_storyboard::{
+    beat::{BeatClock, BeatPos, SnapTarget},
+    element::ElementKind,
+    reexport::Pos,
+    visual::{Anchor, BlendingParameters, Easing},
+    LayerId, Storyboard,
+};
+use test_log::test;
+
+fn clock() -> BeatClock {
+    let quadruple = TimeSignature::new_simple_quadruple();
+    let triple = TimeSignature::new_simple_triple();
+
+    BeatClock::new(&[
+        // 120 BPM in 4/4 starting at 100ms
+        TimingPoint::new(100.0, 500.0, false, quadruple),
+        // 200 BPM in 3/4 starting in the middle of the third measure
+        TimingPoint::new(5100.0, 300.0, false, triple),
+    ])
+}
+
+#[test]
+fn time_of() {
+    let clock = clock();
+
+    assert_eq!(clock.time_of(BeatPos::new(0, 0)), 100.0);
+    assert_eq!(clock.time_of(BeatPos::new(1, 2)), 100.0 + 6.0 * 500.0);
+    assert_eq!(clock.time_of(BeatPos::subdivided(0, 1, 1, 4)), 725.0);
+    assert_eq!(clock.time_of(BeatPos::new(-1, 0)), -1900.0);
+
+    // The cut off third measure counts as a measure
+    assert_eq!(clock.time_of(BeatPos::new(3, 0)), 5100.0);
+    assert_eq!(clock.time_of(BeatPos::new(4, 1)), 5100.0 + 4.0 * 300.0);
+
+    assert_eq!(clock.beat_len_at(0.0), 500.0);
+    assert_eq!(clock.beat_len_at(6000.0), 300.0);
+}
+
+#[test]
+fn snap() {
+    let clock = clock();
+
+    assert_eq!(clock.snap(340.0, 1), 100.0);
+    assert_eq!(clock.snap(360.0, 1), 600.0);
+    assert_eq!(clock.snap(360.0, 4), 350.0);
+    assert_eq!(clock.snap(5240.0, 2), 5250.0);
+}
+
+#[test]
+fn builder_with_beats() {
+    let clock = clock();
+    let mut storyboard = Storyboard::default();
+
+    storyboard
+        .layer(&LayerId::Foreground)
+        .unwrap()
+        .sprite("a.png", Anchor::CENTER, Pos::default())
+        .fade(
+            Easing::None,
+            (&clock, BeatPos::new(0, 0)..BeatPos::new(1, 0)),
+            0.0,
+            1.0,
+        )
+        .scale(Easing::None, (&clock, BeatPos::new(3, 0)), 2.0, 2.0)
+        .build()
+        .unwrap();
+
+    let ElementKind::Sprite(ref sprite) = storyboard.layers["Foreground"].elements[0].kind else {
+        panic!("expected sprite");
+    };
+
+    assert_eq!(sprite.timeline_group.alpha.start_time, 100.0);
+    assert_eq!(sprite.timeline_group.alpha.end_time, 2100.0);
+    assert_eq!(sprite.timeline_group.scale.start_time, 5100.0);
+}
+
+#[test]
+fn snap_storyboard() {
+    const OSB: &str = "osu file format v14
+
+[Events]
+Sprite,Foreground,Centre,\"a.png\",0,0
+ F,0,110,590,0,1
+ L,1095,2
+  S,0,0,10,1,2
+Sample,5245,3,\"hit.wav\",100
+";
+
+    let mut storyboard: Storyboard = OSB.parse().unwrap();
+    let report = storyboard.snap_to_beats(&clock(), 2);
+
+    assert_eq!(report.snapped.len(), 4);
+    assert_eq!(report.max_offset(), 10.0);
+
+    let loop_snap = report
+        .snapped
+        .iter()
+        .find(|snapped| snapped.target == SnapTarget::Loop)
+        .unwrap();
+
+    assert_eq!(loop_snap.original, 1095.0);
+    assert_eq!(loop_snap.offset(), 5.0);
+
+    let elems = &storyboard.layers["Foreground"].elements;
+
+    let ElementKind::Sprite(ref sprite) = elems[0].kind else {
+        panic!("expected sprite");
+    };
+
+    assert_eq!(sprite.timeline_group.alpha.start_time, 100.0);
+    assert_eq!(sprite.timeline_group.alpha.end_time, 600.0);
+    assert_eq!(sprite.loops[0].loop_start_time, 1100.0);
+
+    let ElementKind::Sample(ref sample) = elems[1].kind else {
+        panic!("expected sample");
+    };
+
+    assert_eq!(sample.start_time, 5250.0);
+}
+
+#[test]
+fn snap_parameter_commands() {
+    const OSB: &str = "osu file format v14
+
+[Events]
+Sprite,Foreground,Centre,\"a.png\",0,0
+ F,0,0,2000,1
+ P,0,100,110,A
+ P,0,340,360,H
+ P,0,600,1100,V
+";
+
+    let mut storyboard: Storyboard = OSB.parse().unwrap();
+    storyboard.snap_to_beats(&clock(), 2);
+
+    let ElementKind::Sprite(ref sprite) = storyboard.layers["Foreground"].elements[0].kind else {
+        panic!("expected sprite");
+    };
+
+    // Commands that lost their duration persist like they would when decoded
+    let state = sprite.state_at(1500.0);
+    assert_eq!(state.blending, BlendingParameters::ADDITIVE);
+    assert!(state.flip_h);
+    assert!(!state.flip_v);
+
+    let encoded = storyboard.encode_to_string().unwrap();
+    let decoded: Storyboard = encoded.parse().unwrap();
+    assert_eq!(decoded, storyboard);
+}
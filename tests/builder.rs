@@ -0,0 +1,143 @@
+use rosu_storyboard::{
+    builder::BuildError,
+    element::AnimationLoopType,
+    reexport::{Color, Pos},
+    visual::{Anchor, Easing},
+    LayerId, Storyboard,
+};
+use test_log::test;
+
+const OSB: &str = "osu file format v14
+
+[Events]
+Sprite,Foreground,Centre,\"sb/star.png\",320,240
+ F,1,1000,2000,0,1
+ M,0,1000,2000,320,240,320,100
+ R,0,1000,,0
+ C,0,1000,,255,0,0
+ P,0,1000,,A
+ L,3000,4
+  S,0,0,500,1,2
+ T,HitObjectHit,0,10000
+  F,0,0,100,1,0
+Animation,Background,TopLeft,\"sb/anim.png\",0,0,3,40,LoopOnce
+ V,0,0,500,1,1,2,1
+Sample,500,0,\"sb/hit.wav\",80
+";
+
+#[test]
+fn matches_decoded() {
+    let mut storyboard = Storyboard::default();
+
+    storyboard
+        .layer(&LayerId::Foreground)
+        .unwrap()
+        .sprite("sb/star.png", Anchor::CENTER, Pos::new(320.0, 240.0))
+        .fade(Easing::Out, 1000.0..2000.0, 0.0, 1.0)
+        .move_to(
+            Easing::None,
+            1000.0..2000.0,
+            Pos::new(320.0, 240.0),
+            Pos::new(320.0, 100.0),
+        )
+        .rotate(Easing::None, 1000.0, 0.0, 0.0)
+        .color(
+            Easing::None,
+            1000.0,
+            Color::new(255, 0, 0, 255),
+            Color::new(255, 0, 0, 255),
+        )
+        .additive(1000.0)
+        .loop_(3000.0, 4, |l| l.scale(Easing::None, 0.0..500.0, 1.0, 2.0))
+        .trigger("HitObjectHit", 0.0..10_000.0, 0, |t| {
+            t.fade(Easing::None, 0.0..100.0, 1.0, 0.0)
+        })
+        .build()
+        .unwrap();
+
+    let mut background = storyboard.layer(&LayerId::Background).unwrap();
+
+    background
+        .animation(
+            "sb/anim.png",
+            Anchor::TOP_LEFT,
+            Pos::new(0.0, 0.0),
+            3,
+            40.0,
+            AnimationLoopType::LoopOnce,
+        )
+        .scale_vec(
+            Easing::None,
+            0.0..500.0,
+            Pos::new(1.0, 1.0),
+            Pos::new(2.0, 1.0),
+        )
+        .build()
+        .unwrap();
+
+    background.sample("sb/hit.wav", 500.0, 80.0);
+
+    let decoded: Storyboard = OSB.parse().unwrap();
+
+    // The decoder picks the first sprite as background file
+    storyboard
+        .background_file
+        .clone_from(&decoded.background_file);
+
+    assert_eq!(storyboard, decoded);
+
+    let encoded = storyboard.encode_to_string().unwrap();
+    let decoded_after_encode: Storyboard = encoded.parse().unwrap();
+    assert_eq!(storyboard, decoded_after_encode);
+}
+
+#[test]
+fn validation() {
+    let mut storyboard = Storyboard::default();
+    let mut layer = storyboard.layer(&LayerId::Foreground).unwrap();
+
+    let err = layer
+        .sprite("a.png", Anchor::CENTER, Pos::default())
+        .fade(Easing::None, 2000.0..1000.0, 0.0, 1.0)
+        .fade(Easing::None, f64::NAN, 0.0, 1.0)
+        .build()
+        .unwrap_err();
+
+    assert_eq!(
+        err,
+        BuildError::InvalidTimeRange {
+            start_time: 2000.0,
+            end_time: 1000.0
+        }
+    );
+
+    let err = layer
+        .sprite("a.png", Anchor::CENTER, Pos::default())
+        .loop_(0.0, 0, |l| l.fade(Easing::None, 0.0, 1.0, 1.0))
+        .build()
+        .unwrap_err();
+
+    assert_eq!(err, BuildError::ZeroIterations);
+
+    let err = layer
+        .sprite("a.png", Anchor::CENTER, Pos::default())
+        .trigger("Passing", 0.0..1000.0, 0, |t| t)
+        .build()
+        .unwrap_err();
+
+    assert_eq!(err, BuildError::EmptyGroup);
+
+    assert!(storyboard.layers["Foreground"].elements.is_empty());
+}
+
+#[test]
+fn missing_layers_are_not_created() {
+    let mut storyboard = Storyboard::default();
+    let layers = storyboard.layers.len();
+
+    let typo = LayerId::from("Foregrund");
+
+    assert!(storyboard.get_layer(&typo).is_none());
+    assert!(storyboard.layer(&typo).is_none());
+    assert_eq!(storyboard.layers.len(), layers);
+}
@@ -0,0 +1,136 @@
+#![cfg(feature = "cli")]
+
+use std::{
+    env, fs,
+    process::{Command, Output},
+};
+
+use rosu_storyboard::Storyboard;
+
+const OSB: &str = "osu file format v14
+
+[Events]
+Sprite,Background,TopLeft,\"bg.png\",0,0
+ F,0,0,10000,1
+Sprite,Foreground,Centre,\"a.png\",320,240
+ F,0,1000,2000,0,1
+ M,0,1000,2000,0,0,100,100
+ L,3000,2
+  S,0,0,500,1,2
+Sample,500,3,\"hit.wav\",80
+";
+
+fn run(args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_rosu-storyboard"))
+        .args(args)
+        .output()
+        .unwrap()
+}
+
+fn write_input(name: &str, content: impl AsRef<[u8]>) -> String {
+    let path = env::temp_dir().join(format!("rosu-storyboard-cli-{name}"));
+    fs::write(&path, content).unwrap();
+
+    path.to_str().unwrap().to_owned()
+}
+
+#[test]
+fn stats() {
+    let input = write_input("stats.osb", OSB);
+    let output = run(&["stats", &input]);
+    assert!(output.status.success());
+
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.starts_with("Time span: 0ms - 10000ms (10000ms)\n"));
+    assert!(stdout.contains("  Background: 1 (1 sprites, 0 animations, 0 samples, 0 videos)\n"));
+    assert!(stdout.contains("  Foreground: 2 (1 sprites, 0 animations, 1 samples, 0 videos)\n"));
+    assert!(stdout.contains("  F: 2\n  MX: 1\n  MY: 1\n  S: 1\n"));
+    assert!(stdout.contains("  L: 1\n  T: 0\n"));
+}
+
+#[test]
+fn validate() {
+    let input = write_input("validate.osb", "[Events]\nSprite,Foreground\n");
+    let output = run(&["validate", &input]);
+    assert_eq!(output.status.code(), Some(1));
+
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert_eq!(
+        stdout,
+        format!("{input}:2: invalid line\n    Sprite,Foreground\n")
+    );
+
+    let input = write_input("valid.osb", OSB);
+    assert!(run(&["validate", &input]).status.success());
+
+    // UTF-16 with a byte order mark is accepted like in the other commands
+    let utf16: Vec<u8> = [0xFEFF]
+        .into_iter()
+        .chain("[Events]\nSprite,Foreground\n".encode_utf16())
+        .flat_map(u16::to_le_bytes)
+        .collect();
+
+    let input = write_input("validate-utf16.osb", utf16);
+    let output = run(&["validate", &input]);
+    assert_eq!(output.status.code(), Some(1));
+
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert_eq!(
+        stdout,
+        format!("{input}:2: invalid line\n    Sprite,Foreground\n")
+    );
+}
+
+#[test]
+fn json_roundtrip() {
+    let input = write_input("roundtrip.osb", OSB);
+    let json = write_input("roundtrip.json", "");
+    let osb = write_input("roundtrip-out.osb", "");
+
+    assert!(run(&["to-json", &input, "--pretty", "-o", &json])
+        .status
+        .success());
+    assert!(run(&["from-json", &json, "-o", &osb]).status.success());
+
+    let original: Storyboard = OSB.parse().unwrap();
+    let roundtrip = Storyboard::from_path(&osb).unwrap();
+    assert_eq!(roundtrip, original);
+}
+
+#[test]
+fn extract() {
+    let input = write_input("extract.osb", OSB);
+    let output = run(&["extract", &input, "--layer", "Foreground", "--to", "800"]);
+    assert!(output.status.success());
+
+    let extracted: Storyboard = String::from_utf8(output.stdout).unwrap().parse().unwrap();
+    assert!(extracted.layers["Background"].elements.is_empty());
+
+    let elems = &extracted.layers["Foreground"].elements;
+    assert_eq!(elems.len(), 1);
+    assert_eq!(elems[0].path, "hit.wav");
+
+    // The sprite's loop is still running in its second iteration
+    let output = run(&["extract", &input, "--from", "3800"]);
+    assert!(output.status.success());
+
+    let extracted: Storyboard = String::from_utf8(output.stdout).unwrap().parse().unwrap();
+    let elems = &extracted.layers["Foreground"].elements;
+    assert_eq!(elems.len(), 1);
+    assert_eq!(elems[0].path, "a.png");
+
+    let output = run(&["extract", &input, "--layer", "Nope"]);
+    assert_eq!(output.status.code(), Some(1));
+}
+
+#[test]
+fn usage() {
+    let output = run(&["stats"]);
+    assert_eq!(output.status.code(), Some(2));
+
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.starts_with("error: missing input\n"));
+
+    assert!(run(&["--help"]).status.success());
+    assert_eq!(run(&["frobnicate", "x"]).status.code(), Some(2));
+}
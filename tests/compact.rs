@@ -0,0 +1,125 @@
+use std::{fmt::Write, fs};
+
+use rosu_storyboard::{compact::CompactStoryboard, LayerId, Storyboard};
+use test_log::test;
+
+fn generate(sprites: usize) -> String {
+    let mut osb = String::from(
+        "osu file format v14
+
+[Events]
+0,0,\"bg.jpg\",0,0
+2,1000,2000
+",
+    );
+
+    for i in 0..sprites {
+        let layer = if i % 100 == 99 {
+            "Custom"
+        } else {
+            "Foreground"
+        };
+        let _ = writeln!(osb, "Sprite,{layer},Centre,\"sb/{}.png\",320,240", i % 20);
+        let _ = writeln!(osb, " F,0,{i},{},0,1", i + 500);
+        let _ = writeln!(osb, " M,1,{i},{},0,0,100,100", i + 500);
+        let _ = writeln!(osb, " S,0,{i},,0.5");
+        let _ = writeln!(osb, " L,{i},3");
+        let _ = writeln!(osb, "  R,0,0,100,0,3.14");
+        let _ = writeln!(osb, "  C,0,0,100,255,255,255,0,0,0");
+        let _ = writeln!(osb, " T,HitSoundClap,0,{}", i + 1000);
+        let _ = writeln!(osb, "  P,0,0,100,A");
+        let _ = writeln!(osb, "  V,0,0,100,1,1,2,2");
+    }
+
+    let _ = writeln!(osb, "Sample,500,3,\"hit.wav\",70");
+    let _ = writeln!(osb, "Animation,Overlay,TopLeft,\"sb/anim.png\",0,0,4,50");
+    let _ = writeln!(osb, " F,0,0,100,1");
+
+    osb
+}
+
+#[test]
+fn resources_roundtrip() {
+    for entry in fs::read_dir("./resources").unwrap() {
+        let path = entry.unwrap().path();
+        let storyboard = Storyboard::from_path(&path).unwrap();
+        let compact = CompactStoryboard::new(&storyboard);
+
+        assert_eq!(compact.to_storyboard(), storyboard, "{}", path.display());
+    }
+}
+
+#[test]
+fn large() {
+    let storyboard: Storyboard = generate(10_000).parse().unwrap();
+    let compact = CompactStoryboard::new(&storyboard);
+
+    assert_eq!(compact.to_storyboard(), storyboard);
+    assert_eq!(compact.len(), 10_002);
+    assert_eq!(compact.command_count(), 10_000 * 8 + 1);
+
+    // 20 paths, the trigger name, the sample and the animation
+    assert_eq!(compact.strings().len(), 23);
+
+    let full = storyboard.heap_size();
+    let compacted = compact.heap_size();
+
+    assert!(compacted * 4 < full, "{compacted} vs {full}");
+}
+
+#[test]
+fn element_access() {
+    let storyboard: Storyboard = generate(200).parse().unwrap();
+    let compact = CompactStoryboard::new(&storyboard);
+
+    let layer = &storyboard.layers["Foreground"];
+    assert_eq!(
+        compact.element(&LayerId::Foreground, 42).as_ref(),
+        layer.elements.get(42)
+    );
+    assert!(compact
+        .element(&LayerId::Foreground, layer.elements.len())
+        .is_none());
+    assert!(compact.element(&LayerId::from("Missing"), 0).is_none());
+
+    assert!(compact
+        .elements(&LayerId::from("Custom"))
+        .eq(storyboard.layers["Custom"].elements.clone()));
+    assert_eq!(compact.elements(&LayerId::from("Missing")).count(), 0);
+
+    let names: Vec<_> = compact.layer_ids().map(LayerId::as_str).collect();
+    assert_eq!(
+        names,
+        [
+            "Video",
+            "Background",
+            "Fail",
+            "Pass",
+            "Foreground",
+            "Custom",
+            "Overlay"
+        ]
+    );
+}
+
+#[test]
+fn push_matches_new() {
+    let storyboard: Storyboard = generate(300).parse().unwrap();
+
+    let mut compact = CompactStoryboard::default();
+    compact.format_version = storyboard.format_version;
+    compact
+        .background_file
+        .clone_from(&storyboard.background_file);
+    compact.breaks.clone_from(&storyboard.breaks);
+
+    for name in ["Foreground", "Custom", "Pass", "Overlay"] {
+        let id = LayerId::from(name);
+
+        for element in storyboard.layers[&id].elements.iter() {
+            compact.push(&id, element);
+        }
+    }
+
+    assert_eq!(compact.to_storyboard(), storyboard);
+}
@@ -1,8 +1,9 @@
 use rosu_map::util::Pos;
 use rosu_storyboard::{
     element::{AnimationLoopType, ElementKind, Video},
+    reexport::Color,
     visual::Anchor,
-    Storyboard,
+    BackgroundColor, LayerId, Storyboard,
 };
 use test_log::test;
 
@@ -355,21 +356,239 @@ fn decode_loop_count() {
 
 #[test]
 fn video_and_background_events_do_not_affect_storyboard_bounds() {
-    let mut storyboard: Storyboard =
+    let storyboard: Storyboard =
         rosu_map::from_path("./resources/video-background-events-ignored.osb").unwrap();
 
-    let elements = &storyboard.get_layer("Video").elements;
+    let elements = &storyboard.layers[&LayerId::Video].elements;
 
     assert_eq!(elements.len(), 1);
     assert!(matches!(
         elements[0].kind,
-        ElementKind::Video(Video { start_time }) if (-5678.0 - start_time).abs() < f64::EPSILON
+        ElementKind::Video(Video { start_time, .. }) if (-5678.0 - start_time).abs() < f64::EPSILON
     ));
 
     assert_eq!(storyboard.earliest_event_time(), None);
     assert_eq!(storyboard.latest_event_time(), None);
 }
 
+#[test]
+fn decode_background_and_video_offsets() {
+    let storyboard: Storyboard = "osu file format v14
+
+[Events]
+0,0,\"bg.jpg\",-20,15
+Video,-200,\"intro.webm\",10,-5
+1,0,\"outro.MPEG\"
+Video,500,\"clip.mkv\",x,3
+"
+    .parse()
+    .unwrap();
+
+    assert_eq!(storyboard.background_file, "bg.jpg");
+    assert_eq!(storyboard.background_offset, Pos::new(-20.0, 15.0));
+
+    let videos: Vec<_> = storyboard.layers["Video"]
+        .elements
+        .iter()
+        .map(|elem| match elem.kind {
+            ElementKind::Video(video) => (elem.path.as_str(), video),
+            _ => panic!("expected video"),
+        })
+        .collect();
+
+    let expected = [
+        (
+            "intro.webm",
+            Video::new(-200.0).with_offset(Pos::new(10.0, -5.0)),
+        ),
+        ("outro.MPEG", Video::new(0.0)),
+        // Invalid offsets default to zero
+        (
+            "clip.mkv",
+            Video::new(500.0).with_offset(Pos::new(0.0, 3.0)),
+        ),
+    ];
+
+    assert_eq!(videos, expected);
+}
+
+#[test]
+fn decode_background_through_video_event() {
+    let storyboard: Storyboard = "osu file format v14
+
+[Events]
+0,0,\"bg.jpg\",1,2
+Video,0,\"other.png\",3,4
+"
+    .parse()
+    .unwrap();
+
+    assert!(storyboard.layers["Video"].elements.is_empty());
+    assert_eq!(storyboard.background_file, "other.png");
+    assert_eq!(storyboard.background_offset, Pos::new(3.0, 4.0));
+}
+
+#[test]
+fn decode_background_colors() {
+    let storyboard: Storyboard = "osu file format v14
+
+[Events]
+3,100,255,0,0
+Colour,1100,0,0,255
+3,600,0,300,-5
+3,x,0,0,0
+"
+    .parse()
+    .unwrap();
+
+    let expected = [
+        BackgroundColor::new(100.0, Color::new(255, 0, 0, 255)),
+        BackgroundColor::new(1100.0, Color::new(0, 0, 255, 255)),
+        BackgroundColor::new(600.0, Color::new(0, 255, 0, 255)),
+    ];
+
+    assert_eq!(storyboard.background_colors, expected);
+
+    let color_at = |time| storyboard.background_color_at(time).unwrap();
+
+    assert_eq!(color_at(0.0), Color::new(255, 0, 0, 255));
+    assert_eq!(color_at(350.0), Color::new(128, 128, 0, 255));
+    assert_eq!(color_at(600.0), Color::new(0, 255, 0, 255));
+    assert_eq!(color_at(850.0), Color::new(0, 128, 128, 255));
+    assert_eq!(color_at(5000.0), Color::new(0, 0, 255, 255));
+
+    assert_eq!(Storyboard::default().background_color_at(0.0), None);
+}
+
+#[test]
+fn decode_sample_volume() {
+    let storyboard: Storyboard = "osu file format v14
+
+[Events]
+Sample,0,0,\"a.wav\",42.5
+Sample,0,0,\"b.wav\",150
+Sample,0,0,\"c.wav\",-20
+Sample,0,0,\"d.wav\"
+"
+    .parse()
+    .unwrap();
+
+    let volumes: Vec<_> = storyboard.layers["Background"]
+        .elements
+        .iter()
+        .map(|elem| match elem.kind {
+            ElementKind::Sample(ref sample) => sample.volume,
+            _ => panic!("expected sample"),
+        })
+        .collect();
+
+    assert_eq!(volumes, [42.5, 100.0, 0.0, 100.0]);
+}
+
+#[test]
+fn decode_numeric_and_named_layers() {
+    let storyboard: Storyboard = "osu file format v14
+
+[Events]
+Sprite,3,Centre,\"a.png\",0,0
+Sprite,Foreground,Centre,\"b.png\",0,0
+Sample,0,4,\"hit.wav\"
+Sample,0,Overlay,\"hit.wav\"
+Sprite,7,Centre,\"c.png\",0,0
+Sprite,foreground,Centre,\"d.png\",0,0
+"
+    .parse()
+    .unwrap();
+
+    assert_eq!(storyboard.layers[&LayerId::Foreground].elements.len(), 2);
+    assert_eq!(storyboard.layers[&LayerId::Overlay].elements.len(), 2);
+    assert_eq!(storyboard.layers["7"].elements.len(), 1);
+    assert_eq!(storyboard.layers["foreground"].elements.len(), 1);
+}
+
+#[test]
+fn layer_id_forms() {
+    for (number, name) in [
+        (0, "Background"),
+        (1, "Fail"),
+        (2, "Pass"),
+        (3, "Foreground"),
+        (4, "Overlay"),
+        (5, "Video"),
+    ] {
+        let id = LayerId::from_number(number).unwrap();
+
+        assert_eq!(id.number(), Some(number));
+        assert_eq!(id.as_str(), name);
+        assert_eq!(id.to_string(), name);
+        assert_eq!(LayerId::parse(name), id);
+        assert_eq!(LayerId::parse(&number.to_string()), id);
+    }
+
+    assert_eq!(LayerId::from_number(6), None);
+
+    let custom = LayerId::parse("6");
+    assert!(matches!(custom, LayerId::Custom(ref name) if name.as_str() == "6"));
+    assert_eq!(custom.number(), None);
+
+    // Known names never become custom layers
+    for id in [
+        LayerId::from("Fail"),
+        LayerId::from("Fail".to_owned()),
+        "Fail".parse().unwrap(),
+    ] {
+        assert!(matches!(id, LayerId::Fail));
+        assert_eq!(id.number(), Some(1));
+    }
+}
+
+#[test]
+fn commands_after_other_events_target_last_group() {
+    let storyboard: Storyboard = "osu file format v14
+
+[Events]
+Sprite,Foreground,Centre,\"sb/a.png\",0,0
+ L,1000,2
+  F,0,0,500,0,1
+Sample,0,3,\"sb/hit.wav\"
+  S,0,0,500,1,2
+ R,0,0,500,0,1
+"
+    .parse()
+    .unwrap();
+
+    let elements = &storyboard.layers["Foreground"].elements;
+    assert_eq!(elements.len(), 2);
+
+    let ElementKind::Sprite(ref sprite) = elements[0].kind else {
+        panic!("expected sprite");
+    };
+
+    // Both nested and top-level commands still go to the previous sprite's
+    // loop because the sample doesn't start a new command group
+    assert_eq!(sprite.loops[0].group.scale.len(), 1);
+    assert_eq!(sprite.loops[0].group.rotation.len(), 1);
+}
+
+#[test]
+fn decode_on_multiple_threads() {
+    fn assert_send_sync<T: Send + Sync>() {}
+
+    assert_send_sync::<Storyboard>();
+    assert_send_sync::<rosu_storyboard::StoryboardState>();
+
+    let path = "./resources/Himeringo - Yotsuya-san ni Yoroshiku (RLC) [Winber1's Extreme].osu";
+    let expected = Storyboard::from_path(path).unwrap();
+
+    let handles: Vec<_> = (0..4)
+        .map(|_| std::thread::spawn(move || Storyboard::from_path(path).unwrap()))
+        .collect();
+
+    for handle in handles {
+        assert_eq!(handle.join().unwrap(), expected);
+    }
+}
+
 #[track_caller]
 fn assert_eq_f64(actual: f64, expected: f64) {
     assert!(
@@ -0,0 +1,438 @@
+use rosu_storyboard::{
+    diff::{
+        BackgroundChange, CommandChange, CommandValue, ElementChange, ElementDiff, GroupId,
+        LoopInfo, StateProperty, TimelineId, VisualDiffOptions, VisualTolerance,
+    },
+    reexport::Pos,
+    visual::Anchor,
+    Storyboard,
+};
+use test_log::test;
+
+const OLD: &str = "osu file format v14
+
+[Events]
+Sprite,Foreground,Centre,\"sb/star.png\",320,240
+ F,0,1000,2000,0,1
+ M,0,1000,2000,320,240,320,100
+ L,3000,2
+  S,0,0,500,1,2
+Sprite,Foreground,Centre,\"sb/star.png\",100,100
+ F,0,0,500,0,1
+Sprite,Foreground,TopLeft,\"sb/old.png\",0,0
+ F,0,0,500,0,1
+Sample,500,0,\"sb/hit.wav\",80
+";
+
+const NEW: &str = "osu file format v14
+
+[Events]
+Sprite,Foreground,Centre,\"sb/star.png\",100,100
+ F,0,0,500,0,1
+Sprite,Foreground,TopLeft,\"sb/star.png\",320,200
+ F,0,1000,2000,0,0.5
+ MX,0,1000,2000,320,400
+ R,0,2500,,1
+ L,3000,3
+  S,0,0,500,1,2
+Sprite,Foreground,Centre,\"sb/new.png\",0,0
+ F,0,0,500,0,1
+Sample,500,0,\"sb/hit.wav\",80
+";
+
+#[test]
+fn identical() {
+    let storyboard: Storyboard = OLD.parse().unwrap();
+    let diff = storyboard.diff(&storyboard.clone());
+
+    assert!(diff.is_empty());
+    assert_eq!(diff.to_string(), "");
+}
+
+#[test]
+fn structural() {
+    let old: Storyboard = OLD.parse().unwrap();
+    let new: Storyboard = NEW.parse().unwrap();
+
+    let diff = old.diff(&new);
+    assert_eq!(diff.layers.len(), 1);
+
+    let layer = &diff.layers[0];
+    assert_eq!(layer.name, "Foreground");
+    assert_eq!(layer.elements.len(), 3);
+
+    // The moved star is matched through its timing despite reordering
+    let ElementDiff::Changed {
+        old_index,
+        new_index,
+        ref changes,
+        ..
+    } = layer.elements[0]
+    else {
+        panic!("expected changed element");
+    };
+
+    assert_eq!((old_index, new_index), (0, 1));
+
+    assert_eq!(
+        changes[0],
+        ElementChange::Origin {
+            old: Anchor::CENTER,
+            new: Anchor::TOP_LEFT
+        }
+    );
+
+    assert_eq!(
+        changes[1],
+        ElementChange::InitialPos {
+            old: Pos::new(320.0, 240.0),
+            new: Pos::new(320.0, 200.0)
+        }
+    );
+
+    let commands: Vec<_> = changes
+        .iter()
+        .filter_map(|change| match change {
+            ElementChange::Command(command) => Some(command),
+            _ => None,
+        })
+        .collect();
+
+    let alpha = commands
+        .iter()
+        .find(|command| command.timeline == TimelineId::Alpha)
+        .unwrap();
+
+    let CommandChange::Changed { old, new } = alpha.change else {
+        panic!("expected changed command");
+    };
+
+    assert_eq!(old.end_value, CommandValue::Float(1.0));
+    assert_eq!(new.end_value, CommandValue::Float(0.5));
+
+    // `M` is split into `MX` and `MY` so only `MY` is deleted
+    assert!(commands
+        .iter()
+        .any(|command| command.timeline == TimelineId::X
+            && matches!(command.change, CommandChange::Changed { .. })));
+
+    let timelines: Vec<_> = commands
+        .iter()
+        .map(|command| (command.group, command.timeline, &command.change))
+        .filter(|(.., change)| !matches!(change, CommandChange::Changed { .. }))
+        .map(|(group, timeline, change)| {
+            let inserted = matches!(change, CommandChange::Inserted(_));
+
+            (group, timeline, inserted)
+        })
+        .collect();
+
+    assert_eq!(
+        timelines,
+        [
+            (GroupId::Main, TimelineId::Y, false),
+            (GroupId::Main, TimelineId::Rotation, true),
+        ]
+    );
+
+    assert!(changes.contains(&ElementChange::Loop {
+        index: 0,
+        old: LoopInfo {
+            start_time: 3000.0,
+            total_iterations: 2
+        },
+        new: LoopInfo {
+            start_time: 3000.0,
+            total_iterations: 3
+        },
+    }));
+
+    assert_eq!(
+        layer.elements[1],
+        ElementDiff::Removed {
+            index: 2,
+            path: "sb/old.png".to_owned()
+        }
+    );
+
+    assert_eq!(
+        layer.elements[2],
+        ElementDiff::Added {
+            index: 2,
+            path: "sb/new.png".to_owned()
+        }
+    );
+}
+
+#[test]
+fn human_readable() {
+    let old: Storyboard = OLD.parse().unwrap();
+    let new: Storyboard = NEW.parse().unwrap();
+
+    let text = old.diff(&new).to_string();
+
+    assert!(text.starts_with("Foreground:\n  ~ [0 -> 1] \"sb/star.png\"\n"));
+    assert!(text.contains("      origin: Centre -> TopLeft\n"));
+    assert!(text.contains("      position: (320, 240) -> (320, 200)\n"));
+    assert!(text.contains("      F: 1000..2000 0 -> 1 => 1000..2000 0 -> 0.5\n"));
+    assert!(text.contains("      R: + 2500..2500 57.29578 -> 57.29578\n"));
+    assert!(text.contains("      loop #0: 3000 (2x) -> 3000 (3x)\n"));
+    assert!(text.contains("  - [2] \"sb/old.png\"\n"));
+    assert!(text.ends_with("  + [2] \"sb/new.png\"\n"));
+}
+
+#[test]
+fn background_and_video() {
+    let old: Storyboard = "osu file format v14
+
+[Events]
+0,0,\"bg.jpg\",0,0
+Video,0,\"video.mp4\",10,20
+3,100,255,0,0
+"
+    .parse()
+    .unwrap();
+
+    let new: Storyboard = "osu file format v14
+
+[Events]
+0,0,\"bg.jpg\",5,0
+Video,0,\"video.mp4\",10,30
+3,100,255,0,0
+3,200,0,0,255
+"
+    .parse()
+    .unwrap();
+
+    let diff = old.diff(&new);
+
+    assert_eq!(
+        diff.background[0],
+        BackgroundChange::Offset {
+            old: Pos::new(0.0, 0.0),
+            new: Pos::new(5.0, 0.0),
+        }
+    );
+    assert!(matches!(
+        diff.background[1],
+        BackgroundChange::Colors { ref old, ref new } if old.len() == 1 && new.len() == 2
+    ));
+
+    let ElementDiff::Changed { ref changes, .. } = diff.layers[0].elements[0] else {
+        panic!("expected changed video");
+    };
+
+    assert_eq!(
+        changes,
+        &[ElementChange::Offset {
+            old: Pos::new(10.0, 20.0),
+            new: Pos::new(10.0, 30.0),
+        }]
+    );
+
+    assert_eq!(
+        diff.to_string(),
+        "Background:
+  ~ offset: (0, 0) -> (5, 0)
+  ~ colors: 1 -> 2 events
+Video:
+  ~ [0 -> 0] \"video.mp4\"
+      offset: (10, 20) -> (10, 30)
+"
+    );
+}
+
+#[test]
+fn visually_equal() {
+    let old: Storyboard = "osu file format v14
+
+[Events]
+Sprite,Foreground,Centre,\"a.png\",0,0
+ F,0,0,1000,0,1
+"
+    .parse()
+    .unwrap();
+
+    let new: Storyboard = "osu file format v14
+
+[Events]
+Sprite,Foreground,Centre,\"a.png\",0,0
+ F,0,0,500,0,0.5
+ F,0,500,1000,0.5,1
+"
+    .parse()
+    .unwrap();
+
+    assert!(!old.diff(&new).is_empty());
+    assert!(old
+        .visual_diff(&new, &VisualDiffOptions::default())
+        .is_empty());
+}
+
+#[test]
+fn visually_different() {
+    let old: Storyboard = "osu file format v14
+
+[Events]
+Sprite,Foreground,Centre,\"a.png\",0,0
+ F,0,0,1000,1
+"
+    .parse()
+    .unwrap();
+
+    let new: Storyboard = "osu file format v14
+
+[Events]
+Sprite,Foreground,Centre,\"b.png\",0,0
+ F,0,0,200,1
+Sprite,Foreground,Centre,\"a.png\",0,0
+ F,0,0,1000,1
+ R,0,500,1000,0,1
+"
+    .parse()
+    .unwrap();
+
+    let options = VisualDiffOptions {
+        interval: 100.0,
+        ..Default::default()
+    };
+
+    let diff = old.visual_diff(&new, &options);
+    assert_eq!(diff.ranges.len(), 2);
+
+    let first = &diff.ranges[0];
+    assert_eq!((first.start_time, first.end_time), (0.0, 200.0));
+    assert_eq!(first.elements.len(), 1);
+    assert_eq!(first.elements[0].path, "b.png");
+    assert_eq!(first.elements[0].old_index, None);
+    assert_eq!(first.elements[0].new_index, Some(0));
+    assert_eq!(first.elements[0].properties, [StateProperty::Visibility]);
+
+    let second = &diff.ranges[1];
+    assert_eq!((second.start_time, second.end_time), (600.0, 1000.0));
+    assert_eq!(second.elements[0].old_index, Some(0));
+    assert_eq!(second.elements[0].new_index, Some(1));
+    assert_eq!(second.elements[0].properties, [StateProperty::Rotation]);
+
+    assert_eq!(
+        diff.to_string(),
+        "0..200:
+  Foreground \"b.png\" [- -> 0]: visibility
+600..1000:
+  Foreground \"a.png\" [0 -> 1]: rotation
+"
+    );
+
+    // Tolerating the rotation leaves only the additional sprite
+    let options = VisualDiffOptions {
+        interval: 100.0,
+        tolerance: VisualTolerance {
+            rotation: 60.0,
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+
+    assert_eq!(old.visual_diff(&new, &options).ranges.len(), 1);
+}
+
+#[test]
+fn visual_diff_of_huge_loop() {
+    let old: Storyboard = "osu file format v14
+
+[Events]
+Sprite,Foreground,Centre,\"a.png\",0,0
+ L,0,2000000000
+  F,0,0,1000,1
+"
+    .parse()
+    .unwrap();
+
+    let mut new = old.clone();
+    new.layers.get_mut("Foreground").unwrap().elements[0].path = "b.png".to_owned();
+
+    let options = VisualDiffOptions {
+        max_samples: 1000,
+        ..Default::default()
+    };
+
+    let diff = old.visual_diff(&new, &options);
+
+    assert_eq!(diff.ranges.len(), 1);
+    assert_eq!(diff.ranges[0].start_time, 0.0);
+    assert!(diff.ranges[0].end_time > 1e12);
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn machine_readable() {
+    let old: Storyboard = OLD.parse().unwrap();
+    let new: Storyboard = NEW.parse().unwrap();
+
+    let diff = old.diff(&new);
+    let value = serde_json::to_value(&diff).unwrap();
+
+    let changes = &value["layers"][0]["elements"][0]["Changed"]["changes"];
+    assert_eq!(
+        changes[1]["InitialPos"]["new"],
+        serde_json::json!({ "x": 320.0, "y": 200.0 })
+    );
+
+    let json = serde_json::to_string(&diff).unwrap();
+    let deserialized = serde_json::from_str(&json).unwrap();
+    assert_eq!(diff, deserialized);
+}
+
+#[test]
+fn many_elements_with_same_path() {
+    const COUNT: usize = 20_000;
+
+    let mut old = String::from("osu file format v14\n\n[Events]\n");
+
+    for i in 0..COUNT {
+        old.push_str(&format!(
+            "Sprite,Foreground,Centre,\"sb/dot.png\",{i},0\n F,0,{i},,1\n"
+        ));
+    }
+
+    // Shift every element by one and change the position of the last one
+    let new = old
+        .replacen(
+            "[Events]\n",
+            "[Events]\nSprite,Foreground,Centre,\"sb/dot.png\",0,0\n F,0,-1,,1\n",
+            1,
+        )
+        .replace(
+            &format!("\"sb/dot.png\",{},0", COUNT - 1),
+            "\"sb/dot.png\",0,1",
+        );
+
+    let old: Storyboard = old.parse().unwrap();
+    let new: Storyboard = new.parse().unwrap();
+
+    let diff = old.diff(&new);
+    let elements = &diff.layers[0].elements;
+
+    assert_eq!(elements.len(), 2);
+
+    let ElementDiff::Changed {
+        old_index,
+        new_index,
+        ref changes,
+        ..
+    } = elements[0]
+    else {
+        panic!("expected changed element");
+    };
+
+    assert_eq!((old_index, new_index), (COUNT - 1, COUNT));
+    assert_eq!(
+        changes,
+        &[ElementChange::InitialPos {
+            old: Pos::new((COUNT - 1) as f32, 0.0),
+            new: Pos::new(0.0, 1.0),
+        }]
+    );
+
+    assert!(matches!(elements[1], ElementDiff::Added { index: 0, .. }));
+}
@@ -1,6 +1,12 @@
 use std::fs;
 
-use rosu_storyboard::Storyboard;
+use rosu_map::section::events::BreakPeriod;
+use rosu_storyboard::{
+    element::{Animation, AnimationLoopType, Element, Sample, Sprite, Video},
+    reexport::Pos,
+    visual::{Anchor, Easing},
+    LayerId, OsbWriter, Storyboard,
+};
 use test_log::test;
 
 #[test]
@@ -32,3 +38,142 @@ fn stability() {
         assert_eq!(decoded, decoded_after_encode, "{filename:?}");
     }
 }
+
+#[test]
+fn writer_matches_storyboard() {
+    for entry in fs::read_dir("./resources").unwrap() {
+        let path = entry.unwrap().path();
+        let storyboard = Storyboard::from_path(&path).unwrap();
+
+        let mut writer = OsbWriter::new(Vec::new(), storyboard.format_version).unwrap();
+        writer
+            .use_skin_sprites(storyboard.use_skin_sprites)
+            .unwrap();
+
+        if !storyboard.background_file.is_empty() {
+            writer
+                .background(&storyboard.background_file, storyboard.background_offset)
+                .unwrap();
+        }
+
+        for period in storyboard.breaks.iter() {
+            writer.break_period(period).unwrap();
+        }
+
+        for color in storyboard.background_colors.iter() {
+            writer.background_color(color).unwrap();
+        }
+
+        for (name, layer) in storyboard.layers.iter() {
+            for element in layer.elements.iter() {
+                writer.element(name, element).unwrap();
+            }
+        }
+
+        let bytes = writer.finish().unwrap();
+        let decoded = Storyboard::from_bytes(&bytes).unwrap();
+
+        assert_eq!(decoded, storyboard, "{}", path.display());
+    }
+}
+
+#[test]
+fn writer_interleaved_layers() {
+    let mut writer = OsbWriter::new(Vec::new(), 14).unwrap();
+    writer.variable("$star", "\"sb/star.png\"").unwrap();
+    writer.use_skin_sprites(true).unwrap();
+    writer.background("bg.jpg", Pos::new(0.0, 0.0)).unwrap();
+    writer.video("video.mp4", &Video::new(-200.0)).unwrap();
+    writer
+        .break_period(&BreakPeriod {
+            start_time: 1000.0,
+            end_time: 2000.0,
+        })
+        .unwrap();
+
+    for i in 0..100 {
+        let layer = if i % 2 == 0 {
+            LayerId::Foreground
+        } else {
+            LayerId::Overlay
+        };
+        let mut sprite = Sprite::new(Anchor::CENTER, Pos::new(320.0, 240.0));
+        sprite
+            .timeline_group
+            .alpha
+            .add(Easing::None, f64::from(i), f64::from(i) + 100.0, 0.0, 1.0);
+
+        writer.sprite(&layer, "$star", &sprite).unwrap();
+        writer
+            .sample(&LayerId::Pass, "hit.wav", &Sample::new(f64::from(i), 70.0))
+            .unwrap();
+    }
+
+    let animation = Animation::new(
+        Anchor::TOP_LEFT,
+        Pos::new(0.0, 0.0),
+        4,
+        50.0,
+        AnimationLoopType::LoopOnce,
+    );
+    writer
+        .element(
+            &LayerId::Background,
+            &Element::new("anim.png".to_owned(), animation),
+        )
+        .unwrap();
+
+    let bytes = writer.finish().unwrap();
+    let storyboard = Storyboard::from_bytes(&bytes).unwrap();
+
+    assert!(storyboard.use_skin_sprites);
+    assert_eq!(storyboard.background_file, "bg.jpg");
+    assert_eq!(storyboard.breaks.len(), 1);
+    assert_eq!(storyboard.layers["Video"].elements.len(), 1);
+    assert_eq!(storyboard.layers["Background"].elements.len(), 1);
+    assert_eq!(storyboard.layers["Pass"].elements.len(), 100);
+
+    for (layer, start) in [("Foreground", 0.0), ("Overlay", 1.0)] {
+        let elements = &storyboard.layers[layer].elements;
+        assert_eq!(elements.len(), 50);
+        assert_eq!(elements[0].path, "sb/star.png");
+        assert_eq!(elements[0].start_time(), start);
+    }
+}
+
+#[test]
+fn background_colors() {
+    let input = "osu file format v14
+
+[Events]
+3,100,255,0,0
+3,600,0,255,0
+";
+
+    let storyboard: Storyboard = input.parse().unwrap();
+    let encoded = storyboard.encode_to_string().unwrap();
+
+    assert!(encoded.contains("//Background Colour Transformations\n3,100,255,0,0\n3,600,0,255,0\n"));
+    assert_eq!(encoded.parse::<Storyboard>().unwrap(), storyboard);
+}
+
+#[test]
+fn background_and_video_offsets() {
+    let input = "osu file format v14
+
+[Events]
+0,0,\"bg.jpg\",-20,15.5
+Video,-200,\"intro.webm\",10,-5
+Video,300,\"outro.mpeg\"
+";
+
+    let storyboard: Storyboard = input.parse().unwrap();
+    let encoded = storyboard.encode_to_string().unwrap();
+
+    assert!(encoded.contains("0,0,\"bg.jpg\",-20,15.5\n"));
+    assert!(encoded.contains("1,-200,\"intro.webm\",10,-5\n"));
+    assert!(encoded.contains("1,300,\"outro.mpeg\"\n"));
+
+    let decoded: Storyboard = encoded.parse().unwrap();
+    assert_eq!(decoded, storyboard);
+}
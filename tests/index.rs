@@ -0,0 +1,110 @@
+use rosu_storyboard::{element::ElementKind, LayerId, Storyboard};
+use test_log::test;
+
+const PATH: &str = "./resources/Himeringo - Yotsuya-san ni Yoroshiku (RLC) [Winber1's Extreme].osu";
+
+fn brute_force(storyboard: &Storyboard, start: f64, end: f64) -> Vec<(LayerId, usize)> {
+    let mut elems: Vec<_> = storyboard
+        .layers
+        .iter()
+        .flat_map(|(name, layer)| {
+            layer
+                .elements
+                .iter()
+                .enumerate()
+                .map(move |(i, elem)| (name, i, elem))
+        })
+        .filter(|(.., elem)| {
+            elem.start_time() <= end && elem.end_time_for_display().max(elem.start_time()) >= start
+        })
+        .map(|(name, i, _)| (name.clone(), i))
+        .collect();
+
+    elems.sort_unstable();
+
+    elems
+}
+
+#[test]
+fn index_matches_full_scan() {
+    let storyboard = Storyboard::from_path(PATH).unwrap();
+    let index = storyboard.time_index();
+
+    assert_eq!(index.len(), 167);
+    assert_eq!(index.start_time(), storyboard.earliest_event_time());
+    assert_eq!(index.end_time(), storyboard.latest_event_time());
+
+    for time in (0..150_000).step_by(997).map(f64::from) {
+        let mut active: Vec<_> = index
+            .active_at(time)
+            .map(|elem| (elem.layer.to_owned(), elem.index))
+            .collect();
+
+        active.sort_unstable();
+
+        assert_eq!(active, brute_force(&storyboard, time, time), "time={time}");
+    }
+
+    for start in (0..150_000).step_by(4999).map(f64::from) {
+        let end = start + 2500.0;
+
+        let mut active: Vec<_> = index
+            .active_in(start..=end)
+            .map(|elem| (elem.layer.to_owned(), elem.index))
+            .collect();
+
+        active.sort_unstable();
+
+        assert_eq!(
+            active,
+            brute_force(&storyboard, start, end),
+            "range={start}..={end}"
+        );
+    }
+}
+
+#[test]
+fn index_respects_exclusive_bounds() {
+    let storyboard: Storyboard = "osu file format v14
+
+[Events]
+Sprite,Background,TopLeft,\"a.png\",0,0
+ F,0,1000,2000,1
+Sprite,Background,TopLeft,\"b.png\",0,0
+ F,0,2000,3000,1
+"
+    .parse()
+    .unwrap();
+
+    let index = storyboard.time_index();
+
+    assert_eq!(index.active_at(2000.0).count(), 2);
+    assert_eq!(index.active_in(..2000.0).count(), 1);
+    assert_eq!(index.active_in(3000.0..).count(), 1);
+    assert_eq!(index.active_in(3000.1..).count(), 0);
+    assert_eq!(index.active_in(..).count(), 2);
+}
+
+#[test]
+fn index_covers_all_loop_iterations() {
+    let storyboard: Storyboard = "osu file format v14
+
+[Events]
+Sprite,Background,TopLeft,\"a.png\",0,0
+ L,0,10
+  F,0,0,1000,0,1
+"
+    .parse()
+    .unwrap();
+
+    let ElementKind::Sprite(ref sprite) = storyboard.layers["Background"].elements[0].kind else {
+        panic!("expected sprite");
+    };
+
+    assert!(sprite.is_active_at(5000.0));
+
+    let index = storyboard.time_index();
+
+    assert_eq!(index.active_at(5000.0).count(), 1);
+    assert_eq!(index.end_time(), Some(10_000.0));
+}
@@ -0,0 +1,151 @@
+#![cfg(feature = "json")]
+
+use std::fs;
+
+use rosu_storyboard::{json::JsonError, Storyboard};
+use test_log::test;
+
+#[test]
+fn roundtrip() {
+    for entry in fs::read_dir("./resources").unwrap() {
+        let entry = entry.unwrap();
+        let filename = entry.file_name();
+        let filename = filename.to_str().unwrap();
+
+        if !(filename.ends_with(".osu") || filename.ends_with(".osb")) {
+            continue;
+        }
+
+        let decoded = Storyboard::from_path(entry.path())
+            .unwrap_or_else(|e| panic!("Failed to decode storyboard {filename:?}: {e:?}"));
+
+        let json = decoded
+            .to_json()
+            .unwrap_or_else(|e| panic!("Failed to export storyboard {filename:?}: {e:?}"));
+
+        let imported = Storyboard::from_json(&json)
+            .unwrap_or_else(|e| panic!("Failed to import storyboard {filename:?}: {e:?}"));
+
+        assert_eq!(decoded, imported, "{filename:?}");
+
+        let encoded = imported.encode_to_string().unwrap();
+        let decoded_after_encode = Storyboard::from_bytes(encoded.as_bytes()).unwrap();
+        let json_after_encode = Storyboard::from_json(&decoded_after_encode.to_json().unwrap());
+
+        assert_eq!(decoded, json_after_encode.unwrap(), "{filename:?}");
+    }
+}
+
+#[test]
+fn import_matches_osb() {
+    const OSB: &str = "osu file format v14
+
+[Events]
+2,1000,2000
+Sprite,Foreground,Centre,\"sb/dot.png\",320,240
+ M,2,0,500,0,0,100,50
+ R,0,0,,0
+ C,0,0,,255,128,0
+ P,0,100,,A
+ L,1000,3
+  F,0,0,100,0,1
+ T,HitSoundClap,0,5000
+  S,0,0,50,1,2
+Animation,Background,TopLeft,\"sb/anim.png\",0,0,4,50,LoopOnce
+ V,0,0,1000,1,1,2,0.5
+Sample,300,0,\"sb/hit.wav\",60
+";
+
+    const JSON: &str = r#"{
+        "version": 1,
+        "background_file": "sb/dot.png",
+        "breaks": [{ "start_time": 1000, "end_time": 2000 }],
+        "layers": [
+            {
+                "name": "Background",
+                "elements": [
+                    {
+                        "type": "animation",
+                        "path": "sb/anim.png",
+                        "frame_count": 4,
+                        "frame_delay": 50,
+                        "loop_type": "LoopOnce",
+                        "commands": [
+                            { "type": "vector_scale", "start_time": 0, "end_time": 1000, "start": { "x": 1, "y": 1 }, "end": { "x": 2, "y": 0.5 } }
+                        ]
+                    },
+                    { "type": "sample", "path": "sb/hit.wav", "start_time": 300, "volume": 60 }
+                ]
+            },
+            {
+                "name": "Foreground",
+                "elements": [
+                    {
+                        "type": "sprite",
+                        "path": "sb/dot.png",
+                        "origin": "Centre",
+                        "position": { "x": 320, "y": 240 },
+                        "commands": [
+                            { "type": "move_x", "easing": "In", "start_time": 0, "end_time": 500, "start": 0, "end": 100 },
+                            { "type": "move_y", "easing": "In", "start_time": 0, "end_time": 500, "start": 0, "end": 50 },
+                            { "type": "rotate", "start_time": 0, "start": 0 },
+                            { "type": "color", "start_time": 0, "start": [255, 128, 0] },
+                            { "type": "additive", "start_time": 100 }
+                        ],
+                        "loops": [
+                            {
+                                "start_time": 1000,
+                                "iterations": 3,
+                                "commands": [{ "type": "fade", "start_time": 0, "end_time": 100, "start": 0, "end": 1 }]
+                            }
+                        ],
+                        "triggers": [
+                            {
+                                "trigger": "HitSoundClap",
+                                "start_time": 0,
+                                "end_time": 5000,
+                                "commands": [{ "type": "scale", "start_time": 0, "end_time": 50, "start": 1, "end": 2 }]
+                            }
+                        ]
+                    }
+                ]
+            }
+        ]
+    }"#;
+
+    let from_osb: Storyboard = OSB.parse().unwrap();
+    let from_json = Storyboard::from_json(JSON).unwrap();
+
+    assert_eq!(from_osb, from_json);
+}
+
+#[test]
+fn background_offsets_and_colors() {
+    let storyboard: Storyboard = "osu file format v14
+
+[Events]
+0,0,\"bg.jpg\",-20,15
+Video,-200,\"intro.webm\",10,-5
+3,100,255,0,0
+"
+    .parse()
+    .unwrap();
+
+    let json = storyboard.to_json().unwrap();
+    assert!(json.contains("background_offset"));
+    assert!(json.contains("background_colors"));
+    assert_eq!(Storyboard::from_json(&json).unwrap(), storyboard);
+
+    let default = Storyboard::default().to_json().unwrap();
+    assert!(!default.contains("offset"));
+    assert!(!default.contains("background_colors"));
+}
+
+#[test]
+fn unsupported_version() {
+    let err = Storyboard::from_json(r#"{ "version": 2 }"#).unwrap_err();
+    assert!(matches!(err, JsonError::UnsupportedVersion(2)));
+
+    let err = Storyboard::from_json(r#"{ "layers": [] }"#).unwrap_err();
+    assert!(matches!(err, JsonError::Serde(_)));
+}
@@ -0,0 +1,131 @@
+use rosu_map::util::Pos;
+use rosu_storyboard::{
+    load::{LoadOptions, LoadReport},
+    PlayState, Storyboard,
+};
+use test_log::test;
+
+const STORYBOARD: &str = "osu file format v14
+
+[Events]
+Sprite,Background,TopLeft,\"bg.png\",0,0
+ F,0,1000,3000,1
+Sprite,Background,Centre,\"half.png\",320,240
+ F,0,2000,3000,1
+Sprite,Fail,Centre,\"fail.png\",320,240
+ F,0,1000,3000,1
+";
+
+fn image_size(path: &str) -> Option<Pos> {
+    match path {
+        "bg.png" | "fail.png" => Some(Pos::new(640.0, 480.0)),
+        "half.png" => Some(Pos::new(320.0, 480.0)),
+        _ => None,
+    }
+}
+
+#[test]
+fn load_of_overlapping_sprites() {
+    let storyboard: Storyboard = STORYBOARD.parse().unwrap();
+
+    let options = LoadOptions {
+        interval: 500.0,
+        ..Default::default()
+    };
+
+    let report = storyboard.estimate_load(image_size, &options);
+
+    assert_eq!(report.samples.len(), 5);
+    assert_eq_f64(report.samples[0].load, 1.0);
+    assert_eq_f64(report.samples[2].load, 1.5);
+    assert_eq_f64(report.peak.time, 2000.0);
+    assert_eq_f64(report.peak.load, 1.5);
+    assert_eq_f64(report.average, 1.3);
+
+    assert_eq!(report.top_elements.len(), 2);
+    assert_eq!(report.top_elements[0].path, "bg.png");
+    assert_eq!(report.top_elements[1].path, "half.png");
+    assert_eq_f64(report.top_elements[1].peak, 0.5);
+}
+
+#[test]
+fn load_respects_fail_layer() {
+    let storyboard: Storyboard = STORYBOARD.parse().unwrap();
+
+    let options = LoadOptions {
+        interval: 500.0,
+        play_state: PlayState::failing(),
+        ..Default::default()
+    };
+
+    let report = storyboard.estimate_load(image_size, &options);
+
+    assert_eq_f64(report.peak.load, 2.5);
+    assert!(report
+        .top_elements
+        .iter()
+        .any(|elem| elem.path == "fail.png"));
+}
+
+#[test]
+fn load_clips_to_screen() {
+    let storyboard: Storyboard = "osu file format v14
+
+[Events]
+Sprite,Foreground,TopLeft,\"bg.png\",320,0
+ F,0,0,1000,1
+"
+    .parse()
+    .unwrap();
+
+    let report = storyboard.estimate_load(image_size, &LoadOptions::default());
+
+    assert_eq_f64(report.peak.load, 0.5);
+}
+
+#[test]
+fn load_without_commands() {
+    let storyboard: Storyboard = "osu file format v14
+
+[Events]
+Sprite,Foreground,TopLeft,\"bg.png\",0,0
+"
+    .parse()
+    .unwrap();
+
+    let report = storyboard.estimate_load(image_size, &LoadOptions::default());
+
+    assert_eq!(report, LoadReport::default());
+}
+
+#[test]
+fn load_limits_sample_count() {
+    let storyboard: Storyboard = "osu file format v14
+
+[Events]
+Sprite,Foreground,TopLeft,\"bg.png\",0,0
+ F,0,0,10000000,1
+"
+    .parse()
+    .unwrap();
+
+    let options = LoadOptions {
+        max_samples: 1000,
+        ..LoadOptions::default()
+    };
+
+    let report = storyboard.estimate_load(image_size, &options);
+
+    assert_eq!(report.samples.len(), 1000);
+    assert_eq!(report.samples[0].time, 0.0);
+    assert_eq_f64(report.samples[999].time, 10_000_000.0);
+    assert_eq_f64(report.average, 1.0);
+}
+
+#[track_caller]
+fn assert_eq_f64(actual: f64, expected: f64) {
+    assert!(
+        (actual - expected).abs() < 1e-9,
+        "actual={actual} | expected={expected}"
+    );
+}
@@ -0,0 +1,314 @@
+use rosu_storyboard::{mix::PlannedSample, LayerId, PlayState, PlayStateTransition, Storyboard};
+use test_log::test;
+
+const STORYBOARD: &str = "osu file format v14
+
+[Events]
+Sample,3000,0,\"sb\\hit.wav\",50.5
+Sample,1000,1,\"fail.wav\"
+Sample,2000,2,\"pass.wav\",80
+Sample,-500,3,\"sb/intro.wav\",20
+";
+
+#[test]
+fn plan_samples() {
+    let storyboard: Storyboard = STORYBOARD.parse().unwrap();
+    let plan = storyboard.mix_plan();
+
+    let summary: Vec<_> = plan
+        .samples
+        .iter()
+        .map(|sample| {
+            (
+                sample.start_time,
+                sample.path.as_str(),
+                sample.layer.as_str(),
+                sample.plays_when_passing,
+                sample.plays_when_failing,
+            )
+        })
+        .collect();
+
+    assert_eq!(
+        summary,
+        [
+            (-500.0, "sb/intro.wav", "Foreground", true, true),
+            (1000.0, "fail.wav", "Fail", false, true),
+            (2000.0, "pass.wav", "Pass", true, false),
+            (3000.0, "sb/hit.wav", "Background", true, true),
+        ]
+    );
+
+    assert_eq!(plan.samples[3].volume, 50.5);
+    assert_eq!(plan.samples[0].volume, 20.0);
+}
+
+#[test]
+fn plan_playing() {
+    let storyboard: Storyboard = STORYBOARD.parse().unwrap();
+    let plan = storyboard.mix_plan();
+
+    let paths = |play_state: &PlayState| -> Vec<String> {
+        plan.playing(play_state)
+            .map(|sample| sample.path.clone())
+            .collect()
+    };
+
+    assert_eq!(
+        paths(&PlayState::passing()),
+        ["sb/intro.wav", "pass.wav", "sb/hit.wav"]
+    );
+    assert_eq!(
+        paths(&PlayState::failing()),
+        ["sb/intro.wav", "fail.wav", "sb/hit.wav"]
+    );
+
+    let transitions = [PlayStateTransition {
+        time: 1500.0,
+        passing: false,
+    }];
+    let play_state = PlayState::new(true, transitions);
+
+    assert_eq!(paths(&play_state), ["sb/intro.wav", "sb/hit.wav"]);
+}
+
+#[test]
+fn resolve_path() {
+    let sample = PlannedSample {
+        start_time: 0.0,
+        path: String::from("sb\\sfx//hit.wav"),
+        layer: LayerId::Background,
+        volume: 100.0,
+        plays_when_passing: true,
+        plays_when_failing: true,
+    };
+
+    assert_eq!(
+        sample.resolve("beatmap"),
+        std::path::Path::new("beatmap")
+            .join("sb")
+            .join("sfx")
+            .join("hit.wav")
+    );
+}
+
+#[cfg(feature = "audio")]
+mod audio {
+    use std::{fs, io::Cursor, path::Path};
+
+    use hound::{SampleFormat, WavReader, WavSpec, WavWriter};
+    use rosu_storyboard::{
+        mix::{AudioError, MixOptions, MixedAudio},
+        PlayState, Storyboard,
+    };
+    use test_log::test;
+
+    fn write_wav(path: &Path, channels: u16, sample_rate: u32, values: &[i16]) {
+        let spec = WavSpec {
+            channels,
+            sample_rate,
+            bits_per_sample: 16,
+            sample_format: SampleFormat::Int,
+        };
+
+        let mut writer = WavWriter::create(path, spec).unwrap();
+
+        for &value in values {
+            writer.write_sample(value).unwrap();
+        }
+
+        writer.finalize().unwrap();
+    }
+
+    #[test]
+    fn mix_wav_samples() {
+        let dir = std::env::temp_dir().join("rosu-storyboard-mix");
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(dir.join("sb")).unwrap();
+
+        // Mono, gets duplicated onto both channels
+        write_wav(&dir.join("sb").join("mono.wav"), 1, 1000, &[16_384; 4]);
+        // Stereo at twice the output rate, gets resampled
+        write_wav(
+            &dir.join("stereo.wav"),
+            2,
+            2000,
+            &[8192, -8192, 8192, -8192],
+        );
+        fs::write(dir.join("text.wav"), "not audio").unwrap();
+
+        let storyboard: Storyboard = "osu file format v14
+
+[Events]
+Sample,0,0,\"sb\\mono.wav\",50
+Sample,2,3,\"stereo.wav\"
+Sample,0,1,\"sb/mono.wav\"
+Sample,3,2,\"sb/mono.wav\"
+Sample,0,0,\"text.wav\"
+Sample,0,0,\"missing.wav\"
+"
+        .parse()
+        .unwrap();
+
+        let options = MixOptions {
+            sample_rate: 1000,
+            play_state: PlayState::passing(),
+            song: None,
+            max_duration: 60_000.0,
+        };
+
+        let mixed = storyboard.mix_plan().mix(&dir, &options);
+
+        assert_eq!(mixed.sample_rate, 1000);
+        assert_eq!(mixed.start_time, 0.0);
+        assert_eq!(mixed.frame_count(), 7);
+        assert_eq!(mixed.duration(), 7.0);
+
+        #[rustfmt::skip]
+        let expected = [
+            0.25, 0.25,
+            0.25, 0.25,
+            0.5, 0.0,
+            0.75, 0.75,
+            0.5, 0.5,
+            0.5, 0.5,
+            0.5, 0.5,
+        ];
+
+        assert_eq!(mixed.pcm, expected);
+
+        let mut skipped: Vec<_> = mixed
+            .skipped
+            .iter()
+            .map(|skipped| (skipped.path.as_str(), &skipped.error))
+            .collect();
+        skipped.sort_by_key(|(path, _)| *path);
+
+        assert!(matches!(
+            skipped[..],
+            [
+                ("missing.wav", AudioError::Io(_)),
+                ("text.wav", AudioError::UnsupportedFormat),
+            ]
+        ));
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn write_preview_with_song() {
+        let dir = std::env::temp_dir().join("rosu-storyboard-preview");
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+
+        write_wav(&dir.join("song.wav"), 1, 1000, &[8192; 6]);
+        write_wav(&dir.join("hit.wav"), 1, 1000, &[16_384; 2]);
+
+        let storyboard: Storyboard = "osu file format v14
+
+[Events]
+Sample,-2,0,\"hit.wav\"
+Sample,3,0,\"hit.wav\",50
+"
+        .parse()
+        .unwrap();
+
+        let options = MixOptions {
+            sample_rate: 1000,
+            song: Some(String::from("song.wav")),
+            ..MixOptions::default()
+        };
+
+        let mixed = storyboard.mix_plan().mix(&dir, &options);
+
+        // The song starts at 0ms and thus after the first sample
+        assert_eq!(mixed.start_time, -2.0);
+        assert!(mixed.skipped.is_empty());
+
+        #[rustfmt::skip]
+        let expected = [
+            0.5, 0.5,
+            0.5, 0.5,
+            0.25, 0.25,
+            0.25, 0.25,
+            0.25, 0.25,
+            0.5, 0.5,
+            0.5, 0.5,
+            0.25, 0.25,
+        ];
+
+        assert_eq!(mixed.pcm, expected);
+
+        let path = dir.join("preview.wav");
+        mixed.save_wav(&path).unwrap();
+
+        let mut reader = WavReader::open(&path).unwrap();
+        let spec = reader.spec();
+        assert_eq!((spec.channels, spec.sample_rate), (2, 1000));
+
+        let values: Vec<i16> = reader.samples().map(Result::unwrap).collect();
+        assert_eq!(values.len(), expected.len());
+        assert_eq!(values[0], 16_383);
+        assert_eq!(values[4], 8191);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn mix_limits_duration() {
+        let dir = std::env::temp_dir().join("rosu-storyboard-mix-limit");
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+
+        write_wav(&dir.join("hit.wav"), 1, 1000, &[16_384; 4]);
+
+        let storyboard: Storyboard = "osu file format v14
+
+[Events]
+Sample,0,0,\"hit.wav\"
+Sample,8,0,\"hit.wav\"
+Sample,2000000000,0,\"hit.wav\"
+"
+        .parse()
+        .unwrap();
+
+        let options = MixOptions {
+            sample_rate: 1000,
+            max_duration: 10.0,
+            ..MixOptions::default()
+        };
+
+        let mixed = storyboard.mix_plan().mix(&dir, &options);
+
+        // The second sample is cut off and the third one is skipped
+        assert_eq!(mixed.frame_count(), 10);
+        assert_eq!(mixed.pcm[16..], [0.5; 4]);
+        assert!(matches!(
+            mixed.skipped[..],
+            [ref skipped] if skipped.path == "hit.wav" && matches!(skipped.error, AudioError::TooLate)
+        ));
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn write_clamps_values() {
+        let mixed = MixedAudio {
+            sample_rate: 44_100,
+            pcm: vec![2.0, -2.0, 0.0, 1.0],
+            ..MixedAudio::default()
+        };
+
+        let mut bytes = Cursor::new(Vec::new());
+        mixed.write_wav(&mut bytes).unwrap();
+        bytes.set_position(0);
+
+        let values: Vec<i16> = WavReader::new(bytes)
+            .unwrap()
+            .samples()
+            .map(Result::unwrap)
+            .collect();
+
+        assert_eq!(values, [i16::MAX, -i16::MAX, 0, i16::MAX]);
+    }
+}
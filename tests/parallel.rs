@@ -0,0 +1,128 @@
+#![cfg(feature = "parallel")]
+
+use std::{fmt::Write, fs};
+
+use rosu_storyboard::Storyboard;
+use test_log::test;
+
+/// Generate a storyboard that is large enough to be split into chunks and
+/// let `extra` add lines after each sprite.
+fn generate(extra: impl Fn(usize, &mut String)) -> String {
+    let mut osb = String::from(
+        "osu file format v14
+
+[General]
+UseSkinSprites: 1
+
+[Variables]
+$layer=Foreground
+$fade=F,0
+
+[Events]
+0,0,\"bg.jpg\",0,0
+",
+    );
+
+    for i in 0..5000 {
+        let layer = match i % 1000 {
+            // New layers appear in later chunks
+            999 => format!("Custom{}", i / 1000),
+            _ => "$layer".to_owned(),
+        };
+
+        let _ = writeln!(osb, "Sprite,{layer},Centre,\"sb/{i}.png\",320,240");
+        let _ = writeln!(osb, " $fade,{i},{},0,1", i + 100);
+        let _ = writeln!(osb, " L,{i},2");
+        let _ = writeln!(osb, "  M,0,0,100,0,0,10,10");
+
+        match i % 700 {
+            0 => {
+                let _ = writeln!(osb, "Sample,{i},3,\"sb/hit.wav\",70");
+            }
+            1 => {
+                let _ = writeln!(osb, "2,{i},{}", i + 50);
+            }
+            2 => {
+                let _ = writeln!(osb, "Animation,Overlay,TopLeft,\"sb/anim.png\",0,0,4,50");
+                let _ = writeln!(osb, " S,0,{i},,2");
+            }
+            _ => {}
+        }
+
+        extra(i, &mut osb);
+    }
+
+    osb
+}
+
+#[test]
+fn resources() {
+    for entry in fs::read_dir("./resources").unwrap() {
+        let path = entry.unwrap().path();
+
+        let expected = Storyboard::from_path(&path).unwrap();
+        let actual = Storyboard::from_path_parallel(&path).unwrap();
+
+        assert_eq!(actual, expected, "{}", path.display());
+    }
+}
+
+#[test]
+fn large() {
+    let osb = generate(|_, _| {});
+
+    let expected: Storyboard = osb.parse().unwrap();
+    let actual = Storyboard::from_str_parallel(&osb);
+
+    assert_eq!(actual, expected);
+    assert!(actual.use_skin_sprites);
+    assert_eq!(actual.background_file, "bg.jpg");
+    assert_eq!(actual.breaks.len(), 8);
+    assert_eq!(actual.layers["Foreground"].elements.len(), 4995 + 8);
+    assert_eq!(actual.layers["Custom4"].elements.len(), 1);
+}
+
+#[test]
+fn commands_continuing_previous_group() {
+    let mut osb = String::from(
+        "osu file format v14
+
+[Events]
+Sprite,Foreground,Centre,\"sb/first.png\",0,0
+ L,0,2
+  F,0,0,100,0,1
+",
+    );
+
+    for i in 0..5000 {
+        // Nested commands right after a sprite, or after an invalid sprite,
+        // are added to the previous group
+        if i % 2 == 0 {
+            let _ = writeln!(osb, "Sprite,Foreground,Centre,\"sb/{i}.png\",0,0");
+        } else {
+            let _ = writeln!(osb, "Sprite,Foreground,Centre,\"sb/{i}.png\",x,0");
+        }
+
+        let _ = writeln!(osb, "  R,0,{i},,1");
+        let _ = writeln!(osb, " F,0,{i},,1");
+    }
+
+    let expected: Storyboard = osb.parse().unwrap();
+    let actual = Storyboard::from_str_parallel(&osb);
+
+    assert_eq!(actual, expected);
+}
+
+#[test]
+fn utf16() {
+    let osb = generate(|_, _| {});
+
+    let mut bytes = vec![0xFF, 0xFE];
+    bytes.extend(osb.encode_utf16().flat_map(u16::to_le_bytes));
+
+    let expected = Storyboard::from_bytes(&bytes).unwrap();
+    let actual = Storyboard::from_bytes_parallel(&bytes);
+
+    assert_eq!(actual, expected);
+    assert_eq!(actual.layers["Foreground"].elements.len(), 4995 + 8);
+}
@@ -0,0 +1,77 @@
+use rosu_storyboard::{PlayState, PlayStateTransition, Storyboard};
+use test_log::test;
+
+const STORYBOARD: &str = "osu file format v14
+
+[Events]
+2,1000,2000
+2,5000,6000
+Sprite,Background,TopLeft,\"bg.png\",0,0
+ F,0,0,8000,1
+";
+
+#[test]
+fn play_state_switches_at_breaks() {
+    let storyboard: Storyboard = STORYBOARD.parse().unwrap();
+    let state = storyboard.play_state([false, true]);
+
+    assert!(state.is_passing_at(999.0));
+    assert!(!state.is_passing_at(1000.0));
+    assert!(!state.is_passing_at(4999.0));
+    assert!(state.is_passing_at(5000.0));
+
+    let failing = storyboard.failing_play_state();
+    assert!(failing.is_passing_at(0.0));
+    assert!(!failing.is_passing_at(7000.0));
+
+    let passing = storyboard.passing_play_state();
+    assert!(passing.transitions().is_empty());
+    assert!(passing.is_passing_at(7000.0));
+}
+
+#[test]
+fn play_state_sorts_transitions() {
+    let state = PlayState::new(
+        false,
+        [
+            PlayStateTransition {
+                time: 300.0,
+                passing: false,
+            },
+            PlayStateTransition {
+                time: 100.0,
+                passing: true,
+            },
+        ],
+    );
+
+    assert!(!state.is_passing_at(0.0));
+    assert!(state.is_passing_at(200.0));
+    assert!(!state.is_passing_at(300.0));
+}
+
+#[test]
+fn visible_layers() {
+    let storyboard: Storyboard = STORYBOARD.parse().unwrap();
+    let state = storyboard.failing_play_state();
+
+    let passing: Vec<_> = storyboard
+        .visible_layers_at(0.0, &state)
+        .map(|(name, _)| name)
+        .collect();
+
+    assert_eq!(
+        passing,
+        ["Video", "Background", "Pass", "Foreground", "Overlay"]
+    );
+
+    let failing: Vec<_> = storyboard
+        .visible_layers_at(1000.0, &state)
+        .map(|(name, _)| name)
+        .collect();
+
+    assert_eq!(
+        failing,
+        ["Video", "Background", "Fail", "Foreground", "Overlay"]
+    );
+}
@@ -0,0 +1,89 @@
+#![cfg(feature = "serde")]
+
+use rosu_storyboard::{element::ElementKind, Storyboard};
+use serde_json::json;
+use test_log::test;
+
+const STORYBOARD: &str = "osu file format v14
+
+[Events]
+2,1000,2000
+Sprite,Foreground,Centre,\"sb/dot.png\",320,240
+ M,2,0,500,0,0,100,50
+ C,0,0,,255,128,0
+ L,0,2
+  F,0,0,100,0,1
+";
+
+#[test]
+fn json_schema() {
+    let storyboard: Storyboard = STORYBOARD.parse().unwrap();
+    let value = serde_json::to_value(&storyboard).unwrap();
+
+    assert_eq!(
+        value["breaks"],
+        json!([{ "start_time": 1000.0, "end_time": 2000.0 }])
+    );
+
+    let sprite = &value["layers"]["Foreground"]["elements"][0];
+    assert_eq!(sprite["path"], "sb/dot.png");
+
+    let sprite = &sprite["kind"]["Sprite"];
+    assert_eq!(sprite["initial_pos"], json!({ "x": 320.0, "y": 240.0 }));
+    assert_eq!(sprite["origin"], json!(18));
+
+    let group = &sprite["timeline_group"];
+    assert_eq!(
+        group["x"],
+        json!([{
+            "easing": "In",
+            "start_time": 0.0,
+            "end_time": 500.0,
+            "start_value": 0.0,
+            "end_value": 100.0,
+        }])
+    );
+    assert_eq!(group["color"][0]["start_value"], json!([255, 128, 0, 255]));
+
+    let loop_alpha = &sprite["loops"][0]["group"]["alpha"];
+    assert_eq!(loop_alpha.as_array().map(Vec::len), Some(1));
+    assert_eq!(sprite["loops"][0]["total_iterations"], json!(2));
+}
+
+#[test]
+fn roundtrip_json() {
+    let path = "./resources/Himeringo - Yotsuya-san ni Yoroshiku (RLC) [Winber1's Extreme].osu";
+    let storyboard = Storyboard::from_path(path).unwrap();
+
+    let json = serde_json::to_string(&storyboard).unwrap();
+    let deserialized: Storyboard = serde_json::from_str(&json).unwrap();
+
+    assert_eq!(deserialized, storyboard);
+}
+
+#[test]
+fn deserialize_recomputes_timeline_bounds() {
+    let storyboard: Storyboard = STORYBOARD.parse().unwrap();
+    let mut value = serde_json::to_value(&storyboard).unwrap();
+
+    let x =
+        &mut value["layers"]["Foreground"]["elements"][0]["kind"]["Sprite"]["timeline_group"]["x"];
+    x.as_array_mut().unwrap().push(json!({
+        "easing": "None",
+        "start_time": 1000.0,
+        "end_time": 3000.0,
+        "start_value": 100.0,
+        "end_value": 200.0,
+    }));
+
+    let deserialized: Storyboard = serde_json::from_value(value).unwrap();
+
+    let ElementKind::Sprite(ref sprite) = deserialized.layers["Foreground"].elements[0].kind else {
+        panic!("expected sprite");
+    };
+
+    let x = &sprite.timeline_group.x;
+    assert_eq!(x.start_time, 0.0);
+    assert_eq!(x.end_time, 3000.0);
+    assert_eq!(x.end_value, 200.0);
+}
@@ -0,0 +1,147 @@
+use std::{collections::HashMap, fs, io::Cursor};
+
+use rosu_storyboard::{
+    element::ElementKind, reexport::Pos, Storyboard, StoryboardEvent, StoryboardEvents,
+};
+use test_log::test;
+
+#[test]
+fn resources_match_storyboard() {
+    for entry in fs::read_dir("./resources").unwrap() {
+        let path = entry.unwrap().path();
+
+        let storyboard = Storyboard::from_path(&path).unwrap();
+        let events = StoryboardEvents::from_path(&path).unwrap();
+        assert_eq!(events.format_version(), storyboard.format_version);
+
+        let mut layers: HashMap<_, Vec<_>> = HashMap::new();
+        let mut breaks = Vec::new();
+        let mut colors = Vec::new();
+
+        for event in events {
+            match event.unwrap() {
+                StoryboardEvent::Element { layer, element } => {
+                    layers.entry(layer).or_default().push(element);
+                }
+                StoryboardEvent::Background { .. } => {}
+                StoryboardEvent::Break(period) => breaks.push(period),
+                StoryboardEvent::BackgroundColor(color) => colors.push(color),
+            }
+        }
+
+        for (name, layer) in storyboard.layers {
+            let elements = layers.remove(&name).unwrap_or_default();
+            assert_eq!(elements, layer.elements, "{}: {name}", path.display());
+        }
+
+        assert!(layers.is_empty());
+        assert_eq!(breaks, storyboard.breaks);
+        assert_eq!(colors, storyboard.background_colors);
+    }
+}
+
+#[test]
+fn events_in_order() {
+    let input = "\u{feff}osu file format v14
+
+[General]
+UseSkinSprites: 1
+
+[Variables]
+$star=\"sb/star.png\"
+
+[Events]
+0,0,\"bg.jpg\",0,0
+Video,-200,\"video.mp4\"
+Sprite,Foreground,Centre,$star,320,240
+ F,0,0,1000,0,1
+ L,0,2
+  S,0,0,500,1,2
+Sample,500,3,\"hit.wav\",80
+ R,0,0,500,0,1
+2,1000,2000
+3,1500,255,0,0
+Animation,Overlay,TopLeft,\"sb/anim.png\",0,0,4,50
+ M,0,0,500,0,0,100,100
+";
+
+    let mut events = StoryboardEvents::new(Cursor::new(input)).unwrap();
+    assert_eq!(events.format_version(), 14);
+
+    let events: Vec<_> = events.by_ref().map(Result::unwrap).collect();
+    assert_eq!(events.len(), 7);
+
+    assert_eq!(
+        events[0],
+        StoryboardEvent::Background {
+            file: "bg.jpg".to_owned(),
+            offset: Pos::new(0.0, 0.0),
+        }
+    );
+
+    let StoryboardEvent::Element {
+        ref layer,
+        ref element,
+    } = events[1]
+    else {
+        panic!("expected video");
+    };
+
+    assert_eq!(layer, "Video");
+    assert!(matches!(element.kind, ElementKind::Video(_)));
+
+    let StoryboardEvent::Element {
+        ref layer,
+        ref element,
+    } = events[2]
+    else {
+        panic!("expected sprite");
+    };
+
+    assert_eq!(layer, "Foreground");
+    assert_eq!(element.path, "sb/star.png");
+
+    let ElementKind::Sprite(ref sprite) = element.kind else {
+        panic!("expected sprite");
+    };
+
+    assert_eq!(sprite.timeline_group.alpha.len(), 1);
+    assert_eq!(sprite.loops[0].group.scale.len(), 1);
+
+    // The rotation after the sample cannot be added to the sprite anymore
+    assert!(sprite.timeline_group.rotation.is_empty());
+    assert!(sprite.loops[0].group.rotation.is_empty());
+
+    assert!(matches!(
+        events[3],
+        StoryboardEvent::Element { ref element, .. } if matches!(element.kind, ElementKind::Sample(_))
+    ));
+    assert!(matches!(events[4], StoryboardEvent::Break(_)));
+    assert!(matches!(events[5], StoryboardEvent::BackgroundColor(_)));
+
+    let StoryboardEvent::Element { ref element, .. } = events[6] else {
+        panic!("expected animation");
+    };
+
+    let ElementKind::Animation(ref animation) = element.kind else {
+        panic!("expected animation");
+    };
+
+    assert_eq!(animation.sprite.timeline_group.x.len(), 1);
+}
+
+#[test]
+fn general_section() {
+    let input = "osu file format v9
+
+[General]
+UseSkinSprites: 1
+";
+
+    let mut events = StoryboardEvents::new(Cursor::new(input)).unwrap();
+    assert_eq!(events.format_version(), 9);
+    assert!(!events.use_skin_sprites());
+
+    assert!(events.next().is_none());
+    assert!(events.use_skin_sprites());
+}
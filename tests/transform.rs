@@ -0,0 +1,249 @@
+use rosu_storyboard::{
+    element::{ElementKind, Sprite},
+    reexport::Pos,
+    visual::Anchor,
+    AspectRatio, Storyboard, WIDESCREEN_OFFSET,
+};
+use test_log::test;
+
+const OSB: &str = "osu file format v14
+
+[Events]
+2,1000,2000
+Video,300,\"video.mp4\"
+Animation,Foreground,Centre,\"anim.png\",320,240,4,60,LoopForever
+ F,0,600,1200,0,1
+ L,3000,2
+  S,0,0,300,1,2
+ T,Passing,1500,9000
+  M,0,0,150,0,0,10,10
+ T,HitObjectHit
+  R,0,0,,1
+Sample,4500,3,\"hit.wav\",100
+";
+
+#[test]
+fn shift() {
+    let mut storyboard: Storyboard = OSB.parse().unwrap();
+    storyboard.shift(-200.0);
+
+    assert_eq!(storyboard.breaks[0].start_time, 800.0);
+    assert_eq!(storyboard.breaks[0].end_time, 1800.0);
+
+    let ElementKind::Video(ref video) = storyboard.layers["Video"].elements[0].kind else {
+        panic!("expected video");
+    };
+
+    assert_eq!(video.start_time, 100.0);
+
+    let elems = &storyboard.layers["Foreground"].elements;
+
+    let ElementKind::Animation(ref animation) = elems[0].kind else {
+        panic!("expected animation");
+    };
+
+    assert_eq!(animation.frame_delay, 60.0);
+
+    let sprite = &animation.sprite;
+    assert_eq!(sprite.timeline_group.alpha.start_time, 400.0);
+    assert_eq!(sprite.timeline_group.alpha.end_time, 1000.0);
+    assert_eq!(sprite.loops[0].loop_start_time, 2800.0);
+    assert_eq!(sprite.loops[0].group.scale.end_time, 300.0);
+    assert_eq!(sprite.triggers[0].start_time, 1300.0);
+    assert_eq!(sprite.triggers[0].end_time, 8800.0);
+    assert_eq!(sprite.triggers[0].group.x.end_time, 150.0);
+    assert_eq!(sprite.triggers[1].start_time, f64::MIN);
+    assert_eq!(sprite.triggers[1].end_time, f64::MAX);
+
+    assert_eq!(sprite.start_time(), 400.0);
+    assert_eq!(sprite.end_time(), 3100.0);
+
+    let ElementKind::Sample(ref sample) = elems[1].kind else {
+        panic!("expected sample");
+    };
+
+    assert_eq!(sample.start_time, 4300.0);
+}
+
+#[test]
+fn rescale_time() {
+    let mut storyboard: Storyboard = OSB.parse().unwrap();
+    storyboard.rescale_time(1.5);
+
+    assert_eq!(storyboard.breaks[0].start_time, 1000.0 / 1.5);
+
+    let elems = &storyboard.layers["Foreground"].elements;
+
+    let ElementKind::Animation(ref animation) = elems[0].kind else {
+        panic!("expected animation");
+    };
+
+    assert_eq!(animation.frame_delay, 40.0);
+
+    let sprite = &animation.sprite;
+    assert_eq!(sprite.timeline_group.alpha.start_time, 400.0);
+    assert_eq!(sprite.timeline_group.alpha.end_time, 800.0);
+    assert_eq!(sprite.loops[0].loop_start_time, 2000.0);
+    assert_eq!(sprite.loops[0].group.scale.end_time, 200.0);
+    assert_eq!(sprite.triggers[0].start_time, 1000.0);
+    assert_eq!(sprite.triggers[0].end_time, 6000.0);
+    assert_eq!(sprite.triggers[0].group.x.end_time, 100.0);
+
+    let ElementKind::Sample(ref sample) = elems[1].kind else {
+        panic!("expected sample");
+    };
+
+    assert_eq!(sample.start_time, 3000.0);
+}
+
+#[test]
+fn shift_roundtrip() {
+    let original: Storyboard = OSB.parse().unwrap();
+
+    let mut storyboard = original.clone();
+    storyboard.shift(1234.0);
+    storyboard.shift(-1234.0);
+
+    assert_eq!(storyboard, original);
+}
+
+const SPATIAL: &str = "osu file format v14
+
+[Events]
+Sprite,Foreground,TopLeft,\"a.png\",100,50
+ M,0,0,1000,100,50,200,150
+ V,0,0,1000,1,1,2,0.5
+ R,0,0,1000,0,1
+ P,0,500,600,H
+ L,2000,2
+  MX,0,0,100,300,400
+";
+
+fn sprite(storyboard: &Storyboard) -> &Sprite {
+    match storyboard.layers["Foreground"].elements[0].kind {
+        ElementKind::Sprite(ref sprite) => sprite,
+        _ => panic!("expected sprite"),
+    }
+}
+
+#[test]
+fn mirror_horizontal() {
+    let original: Storyboard = SPATIAL.parse().unwrap();
+    let mut storyboard = original.clone();
+    storyboard.mirror_horizontal();
+
+    let before = sprite(&original);
+    let after = sprite(&storyboard);
+
+    assert_eq!(after.origin, Anchor::TOP_RIGHT);
+    assert_eq!(after.initial_pos, Pos::new(540.0, 50.0));
+    assert_eq!(after.loops[0].group.x.start_value, 340.0);
+
+    for time in [0.0, 250.0, 550.0, 700.0, 1000.0] {
+        let a = before.state_at(time);
+        let b = after.state_at(time);
+
+        assert_eq!(b.position, Pos::new(640.0 - a.position.x, a.position.y));
+        assert_eq!(b.rotation, -a.rotation);
+        assert_eq!(b.scale, a.scale);
+        assert_eq!(b.flip_h, !a.flip_h, "{time}");
+        assert_eq!(b.flip_v, a.flip_v);
+    }
+
+    // Mirroring twice restores the original appearance
+    storyboard.mirror_horizontal();
+    let restored = sprite(&storyboard);
+
+    for time in [0.0, 250.0, 550.0, 700.0, 1000.0] {
+        assert_eq!(restored.state_at(time), before.state_at(time), "{time}");
+    }
+}
+
+#[test]
+fn mirror_vertical() {
+    let mut storyboard: Storyboard = SPATIAL.parse().unwrap();
+    storyboard.mirror_vertical();
+
+    let sprite = sprite(&storyboard);
+    assert_eq!(sprite.origin, Anchor::BOTTOM_LEFT);
+    assert_eq!(sprite.initial_pos, Pos::new(100.0, 430.0));
+    assert_eq!(sprite.timeline_group.y.end_value, 330.0);
+    assert!(sprite.state_at(0.0).flip_v);
+    assert!(!sprite.state_at(0.0).flip_h);
+}
+
+#[test]
+fn translate_and_scale() {
+    let mut storyboard: Storyboard = SPATIAL.parse().unwrap();
+    storyboard.translate(Pos::new(10.0, -10.0));
+
+    let translated = sprite(&storyboard);
+    assert_eq!(translated.initial_pos, Pos::new(110.0, 40.0));
+    assert_eq!(translated.timeline_group.x.end_value, 210.0);
+    assert_eq!(translated.timeline_group.y.start_value, 40.0);
+    assert_eq!(translated.loops[0].group.x.end_value, 410.0);
+
+    storyboard.scale_around(Pos::new(110.0, 40.0), 2.0);
+
+    let scaled = sprite(&storyboard);
+    assert_eq!(scaled.initial_pos, Pos::new(110.0, 40.0));
+    assert_eq!(scaled.timeline_group.x.end_value, 310.0);
+    assert_eq!(scaled.timeline_group.y.end_value, 240.0);
+    assert_eq!(
+        scaled.timeline_group.vector_scale.end_value,
+        Pos::new(4.0, 1.0)
+    );
+    assert_eq!(scaled.state_at(1000.0).scale, Pos::new(4.0, 1.0));
+}
+
+#[test]
+fn scale_applies_factor_once() {
+    let mut storyboard: Storyboard = "osu file format v14
+
+[Events]
+Sprite,Foreground,Centre,\"both.png\",0,0
+ S,0,0,1000,1
+ V,0,0,1000,1,1
+Sprite,Foreground,Centre,\"uniform.png\",0,0
+ S,0,0,1000,1.5
+Sprite,Foreground,Centre,\"none.png\",0,0
+ F,0,500,1000,1
+ L,200,2
+  M,0,0,100,10,10
+"
+    .parse()
+    .unwrap();
+
+    storyboard.scale_around(Pos::new(0.0, 0.0), 2.0);
+
+    let elements = &storyboard.layers["Foreground"].elements;
+    let sprite_at = |i: usize| match elements[i].kind {
+        ElementKind::Sprite(ref sprite) => sprite,
+        _ => panic!("expected sprite"),
+    };
+
+    let both = sprite_at(0);
+    assert_eq!(both.state_at(500.0).scale, Pos::new(2.0, 2.0));
+    assert_eq!(both.timeline_group.scale.end_value, 1.0);
+
+    let uniform = sprite_at(1);
+    assert_eq!(uniform.state_at(500.0).scale, Pos::new(3.0, 3.0));
+
+    let none = sprite_at(2);
+    assert_eq!(none.start_time(), 200.0);
+    assert_eq!(none.state_at(0.0).scale, Pos::new(2.0, 2.0));
+    assert_eq!(none.state_at(800.0).scale, Pos::new(2.0, 2.0));
+    assert_eq!(none.state_at(800.0).position, Pos::new(20.0, 20.0));
+}
+
+#[test]
+fn recenter() {
+    let mut storyboard: Storyboard = SPATIAL.parse().unwrap();
+    storyboard.recenter(AspectRatio::Standard, AspectRatio::Widescreen);
+
+    let moved = sprite(&storyboard);
+    assert_eq!(moved.initial_pos.x, 100.0 + WIDESCREEN_OFFSET);
+
+    storyboard.recenter(AspectRatio::Widescreen, AspectRatio::Standard);
+    assert!((sprite(&storyboard).initial_pos.x - 100.0).abs() < 1e-4);
+}
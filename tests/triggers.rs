@@ -0,0 +1,193 @@
+use rosu_map::section::hit_objects::hit_samples::SampleBank;
+use rosu_storyboard::{
+    command::{HitSoundAddition, HitSoundTrigger, TriggerKind},
+    triggers::{GameplayEvent, TriggerActivation, TriggerEngine},
+    Storyboard,
+};
+use test_log::test;
+
+#[test]
+fn parse_trigger_kinds() {
+    assert_eq!(TriggerKind::parse("Passing"), TriggerKind::Passing);
+    assert_eq!(TriggerKind::parse("Failing"), TriggerKind::Failing);
+    assert_eq!(
+        TriggerKind::parse("HitObjectHit"),
+        TriggerKind::HitObjectHit
+    );
+    assert_eq!(
+        TriggerKind::parse("HitSound"),
+        TriggerKind::HitSound(HitSoundTrigger::default())
+    );
+    assert_eq!(
+        TriggerKind::parse("HitSoundClap"),
+        TriggerKind::HitSound(HitSoundTrigger {
+            addition: Some(HitSoundAddition::Clap),
+            ..Default::default()
+        })
+    );
+    assert_eq!(
+        TriggerKind::parse("HitSoundSoftWhistle"),
+        TriggerKind::HitSound(HitSoundTrigger {
+            sample_set: Some(SampleBank::Soft),
+            addition: Some(HitSoundAddition::Whistle),
+            ..Default::default()
+        })
+    );
+    assert_eq!(
+        TriggerKind::parse("HitSoundDrumFinish2"),
+        TriggerKind::HitSound(HitSoundTrigger {
+            sample_set: Some(SampleBank::Drum),
+            addition: Some(HitSoundAddition::Finish),
+            custom_sample_set: Some(2),
+            ..Default::default()
+        })
+    );
+    assert_eq!(
+        TriggerKind::parse("HitSoundAllNormal"),
+        TriggerKind::HitSound(HitSoundTrigger {
+            additions_sample_set: Some(SampleBank::Normal),
+            ..Default::default()
+        })
+    );
+    assert_eq!(
+        TriggerKind::parse("HitSoundSlap"),
+        TriggerKind::Unknown("HitSoundSlap".to_owned())
+    );
+
+    for name in [
+        "Passing",
+        "HitSoundClap",
+        "HitSoundSoftWhistle",
+        "HitSoundDrumFinish2",
+        "HitSoundAllNormal",
+        "HitSoundNormalSoft3",
+    ] {
+        assert_eq!(TriggerKind::parse(name).to_string(), name);
+    }
+}
+
+const STORYBOARD: &str = "osu file format v14
+
+[Events]
+Sprite,Foreground,Centre,\"a.png\",320,240
+ T,HitSoundClap,0,5000
+  F,0,0,500,1,0
+ T,HitSoundDrum,0,5000
+  S,0,0,300,1,2
+ T,Passing,0,10000,1
+  F,0,0,1000,0,1
+Sprite,Foreground,Centre,\"b.png\",320,240
+ T,Failing
+  F,0,0,1000,0,1
+";
+
+fn hit_sound(time: f64, sample_set: SampleBank, additions: &[HitSoundAddition]) -> GameplayEvent {
+    GameplayEvent::HitSound {
+        time,
+        sample_set,
+        additions_sample_set: sample_set,
+        additions: additions.to_vec(),
+        custom_sample_set: 0,
+    }
+}
+
+#[test]
+fn resolve_triggers() {
+    let storyboard: Storyboard = STORYBOARD.parse().unwrap();
+
+    let engine = TriggerEngine::new([
+        hit_sound(1000.0, SampleBank::Normal, &[HitSoundAddition::Clap]),
+        hit_sound(1200.0, SampleBank::Drum, &[]),
+        hit_sound(6000.0, SampleBank::Normal, &[HitSoundAddition::Clap]),
+        GameplayEvent::PlayState {
+            time: 3000.0,
+            passing: false,
+        },
+        GameplayEvent::PlayState {
+            time: 4000.0,
+            passing: true,
+        },
+    ]);
+
+    let mut resolved = engine.resolve_storyboard(&storyboard);
+    resolved.sort_unstable_by_key(|elem| elem.index);
+
+    assert_eq!(resolved.len(), 2);
+
+    assert_eq!(
+        resolved[0].activations,
+        [
+            TriggerActivation {
+                trigger: 0,
+                time: 1000.0,
+                end_time: 1200.0,
+            },
+            TriggerActivation {
+                trigger: 1,
+                time: 1200.0,
+                end_time: 1500.0,
+            },
+            TriggerActivation {
+                trigger: 2,
+                time: 4000.0,
+                end_time: 5000.0,
+            },
+        ]
+    );
+
+    assert_eq!(
+        resolved[1].activations,
+        [TriggerActivation {
+            trigger: 0,
+            time: 3000.0,
+            end_time: 4000.0,
+        }]
+    );
+}
+
+const BEATMAP: &str = "osu file format v14
+
+[General]
+SampleSet: Normal
+
+[Difficulty]
+SliderMultiplier:1
+SliderTickRate:1
+
+[Events]
+2,5000,6000
+Sprite,Foreground,Centre,\"a.png\",320,240
+ T,HitSoundClap
+  F,0,0,100,1,0
+ T,HitSoundSoftFinish,0,5000,1
+  F,0,0,100,1,0
+ T,Passing,0,10000,2
+  F,0,0,1000,0,1
+
+[TimingPoints]
+0,500,4,1,0,100,1,0
+1500,-100,4,2,0,100,0,0
+
+[HitObjects]
+256,192,1000,1,8,0:0:0:0:
+256,192,1500,1,4,0:0:0:0:
+100,100,2000,2,0,L|200:100,1,100,8|0,0:0|0:0,0:0:0:0:
+";
+
+#[test]
+fn resolve_triggers_from_beatmap() {
+    let beatmap: rosu_map::Beatmap = rosu_map::from_str(BEATMAP).unwrap();
+    let storyboard: Storyboard = BEATMAP.parse().unwrap();
+
+    let resolved = storyboard.resolve_triggers(&beatmap);
+    assert_eq!(resolved.len(), 1);
+
+    let activations = &resolved[0].activations;
+
+    let times: Vec<_> = activations
+        .iter()
+        .map(|activation| (activation.trigger, activation.time))
+        .collect();
+
+    assert_eq!(times, [(0, 1000.0), (1, 1500.0), (0, 2000.0), (2, 5000.0)]);
+}
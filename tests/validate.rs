@@ -0,0 +1,47 @@
+use rosu_storyboard::{ParseStoryboardError, Storyboard};
+use test_log::test;
+
+const OSB: &str = "osu file format v14
+
+[Variables]
+$pos=320,240
+
+[Events]
+Sprite,Foreground,Centre,\"a.png\",$pos
+ F,0,0,1000,0,1
+ F,0,abc,1000,0,1
+ Q,0,0,1000
+Sprite,Foreground,Centre,\"b.png\"
+ M,0,0,500,$pos,0,0
+";
+
+#[test]
+fn line_numbers() {
+    let errors = Storyboard::validate(OSB);
+
+    let lines: Vec<_> = errors.iter().map(|err| err.line).collect();
+    assert_eq!(lines, [9, 10, 11]);
+
+    assert!(matches!(errors[0].error, ParseStoryboardError::Number(_)));
+    assert_eq!(errors[0].content, " F,0,abc,1000,0,1");
+    assert!(matches!(
+        errors[1].error,
+        ParseStoryboardError::UnknownCommandType
+    ));
+    assert!(matches!(errors[2].error, ParseStoryboardError::InvalidLine));
+    assert_eq!(
+        errors[2].to_string(),
+        "line 11: invalid line `Sprite,Foreground,Centre,\"b.png\"`"
+    );
+}
+
+#[test]
+fn same_as_decoding() {
+    let (storyboard, _) = Storyboard::decode_with_errors(OSB);
+    let decoded: Storyboard = OSB.parse().unwrap();
+
+    assert_eq!(storyboard, decoded);
+
+    let valid = "osu file format v14\r\n\r\n[Events]\r\nSample,0,0,\"a.wav\",100\r\n";
+    assert!(Storyboard::validate(valid).is_empty());
+}